@@ -0,0 +1,141 @@
+//! Timing harness for root computation.
+//!
+//! Run with `cargo bench`. Each case is warmed up once and then timed over a
+//! fixed number of iterations; the mean time per iteration is printed.
+
+use dig_network_block::block::{self, DigL2Block};
+use dig_network_block::body::L2BlockBody;
+use dig_network_block::dig_l2_definition::{self as definitions, DataRootMode, Hash32};
+use dig_network_block::emission::Emission;
+use dig_network_block::header::L2BlockHeader;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn bench<T>(name: &str, iters: u32, mut f: impl FnMut() -> T) {
+    black_box(f());
+    let start = Instant::now();
+    for _ in 0..iters {
+        black_box(f());
+    }
+    let per_iter: Duration = start.elapsed() / iters;
+    println!("{name:<40} {per_iter:>12?}/iter ({iters} iters)");
+}
+
+fn data_body(len: usize) -> L2BlockBody {
+    L2BlockBody {
+        data: (0..len).map(|i| (i % 251) as u8).collect(),
+        emissions: vec![],
+    }
+}
+
+fn leaves(n: usize) -> Vec<Hash32> {
+    (0..n)
+        .map(|i| {
+            let mut leaf = [0u8; 32];
+            leaf[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            leaf
+        })
+        .collect()
+}
+
+fn sealed(body: L2BlockBody) -> DigL2Block {
+    let header = L2BlockHeader {
+        version: 1,
+        network_id: [1u8; 32],
+        epoch: 0,
+        prev_block_root: [0u8; 32],
+        body_root: body.calculate_root(),
+        data_count: body.data.len() as u32,
+        emissions_count: body.emissions.len() as u32,
+        proposer_pubkey: [0u8; 48],
+        timestamp: 0,
+        extra_data: [0u8; 32],
+    };
+    DigL2Block { header, body }
+}
+
+fn emissions_body(n: usize) -> L2BlockBody {
+    L2BlockBody {
+        data: vec![],
+        emissions: (0..n)
+            .map(|i| {
+                let mut pubkey = [0u8; 48];
+                pubkey[..8].copy_from_slice(&(i as u64).to_le_bytes());
+                Emission {
+                    pubkey,
+                    weight: i as u64,
+                }
+            })
+            .collect(),
+    }
+}
+
+fn small_blocks(n: usize) -> Vec<DigL2Block> {
+    (0..n)
+        .map(|i| {
+            let body = L2BlockBody {
+                data: (0..32).map(|b| (b + i) as u8).collect(),
+                emissions: (0..4u8)
+                    .map(|k| Emission {
+                        pubkey: [k; 48],
+                        weight: i as u64 + u64::from(k),
+                    })
+                    .collect(),
+            };
+            let header = L2BlockHeader {
+                version: 1,
+                network_id: [1u8; 32],
+                epoch: i as u64,
+                prev_block_root: [0u8; 32],
+                body_root: body.calculate_root(),
+                data_count: body.data.len() as u32,
+                emissions_count: body.emissions.len() as u32,
+                proposer_pubkey: [0u8; 48],
+                timestamp: 0,
+                extra_data: [0u8; 32],
+            };
+            DigL2Block { header, body }
+        })
+        .collect()
+}
+
+fn main() {
+    let cases = [
+        ("block_root/empty", sealed(data_body(0)), 10_000),
+        ("block_root/data/1KB", sealed(data_body(1 << 10)), 200),
+        ("block_root/data/10KB", sealed(data_body(10 << 10)), 20),
+        ("block_root/emissions/100", sealed(emissions_body(100)), 500),
+        (
+            "block_root/emissions/1000",
+            sealed(emissions_body(1000)),
+            50,
+        ),
+    ];
+    for (name, block, iters) in &cases {
+        bench(name, *iters, || block.calculate_root());
+    }
+
+    let l10 = leaves(1 << 10);
+    bench("merkle_root/2^10", 500, || definitions::MERKLE_ROOT(&l10));
+    let l14 = leaves(1 << 14);
+    bench("merkle_root/2^14", 50, || definitions::MERKLE_ROOT(&l14));
+
+    let mb = data_body(1 << 20);
+    bench("data_root/per_byte_sorted/1MB", 3, || {
+        mb.calculate_data_root_with_mode(DataRootMode::PerByteSorted)
+    });
+    bench("data_root/chunked/1MB", 20, || {
+        mb.calculate_data_root_with_mode(DataRootMode::Chunked)
+    });
+
+    let blocks = small_blocks(10_000);
+    bench("block_roots/per_block/10k", 5, || {
+        blocks
+            .iter()
+            .map(DigL2Block::calculate_root)
+            .collect::<Vec<_>>()
+    });
+    bench("block_roots/batch/10k", 5, || {
+        block::calculate_roots(&blocks)
+    });
+}
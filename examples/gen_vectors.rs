@@ -0,0 +1,116 @@
+//! Prints golden test vectors for cross-implementation checks.
+//!
+//! Run with `cargo run --example gen_vectors > vectors/blocks.json`. Inputs are
+//! hard-coded, so the output is reproducible; each vector lists the block JSON
+//! and every intermediate root as `0x` hex.
+
+use dig_network_block::block::DigL2Block;
+use dig_network_block::body::L2BlockBody;
+use dig_network_block::dig_l2_definition::{self as definitions, DataRootMode};
+use dig_network_block::emission::Emission;
+use dig_network_block::header::L2BlockHeader;
+use serde_json::json;
+
+fn hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn sealed(version: u32, timestamp: u64, data: Vec<u8>, emissions: Vec<Emission>) -> DigL2Block {
+    let mut block = DigL2Block {
+        header: L2BlockHeader {
+            version,
+            network_id: [0x01; 32],
+            epoch: 42,
+            prev_block_root: [0x02; 32],
+            body_root: [0; 32],
+            data_count: 0,
+            emissions_count: 0,
+            proposer_pubkey: [0x03; 48],
+            timestamp,
+            extra_data: [0u8; 32],
+        },
+        body: L2BlockBody { data, emissions },
+    };
+    block.reseal().unwrap();
+    block
+}
+
+fn emissions() -> Vec<Emission> {
+    vec![
+        Emission {
+            pubkey: [0x03; 48],
+            weight: 12,
+        },
+        Emission {
+            pubkey: [0x11; 48],
+            weight: 44,
+        },
+        Emission {
+            pubkey: [0x22; 48],
+            weight: 44,
+        },
+    ]
+}
+
+fn main() {
+    let data: Vec<u8> = b"dig network block".to_vec();
+    let blocks = [
+        ("empty", sealed(1, 0, vec![], vec![])),
+        ("data_only", sealed(1, 0, data.clone(), vec![])),
+        ("emissions_only", sealed(1, 0, vec![], emissions())),
+        ("full", sealed(1, 0, data.clone(), emissions())),
+        (
+            "full_v3",
+            sealed(
+                definitions::TIMESTAMP_VERSION,
+                1_700_000_000,
+                (0..=200).collect(),
+                emissions(),
+            ),
+        ),
+        (
+            "full_v5",
+            sealed(
+                definitions::LABELED_BODY_ROOT_VERSION,
+                1_700_000_000,
+                (0..=200).collect(),
+                emissions(),
+            ),
+        ),
+        (
+            "full_v6",
+            sealed(
+                definitions::LENGTH_PREFIXED_FIELDS_VERSION,
+                1_700_000_000,
+                (0..=200).collect(),
+                emissions(),
+            ),
+        ),
+        (
+            "full_v7",
+            sealed(
+                definitions::ORDERED_EMISSIONS_VERSION,
+                1_700_000_000,
+                (0..=200).collect(),
+                emissions().into_iter().rev().collect(),
+            ),
+        ),
+    ];
+    let vectors: Vec<_> = blocks
+        .iter()
+        .map(|(name, block)| {
+            let version = block.header.version;
+            let mode = DataRootMode::for_version(version);
+            json!({
+                "name": name,
+                "block": block,
+                "data_root": hex(&block.body.calculate_data_root_with_mode(mode)),
+                "emissions_root": hex(&block.body.calculate_emissions_root_for_version(version)),
+                "body_root": hex(&block.body.calculate_root_for_version(version)),
+                "header_root": hex(&block.header.calculate_root()),
+                "block_root": hex(&block.calculate_root()),
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&vectors).unwrap());
+}
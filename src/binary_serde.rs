@@ -0,0 +1,707 @@
+//! Serde plumbing shared by the self-describing binary formats.
+//!
+//! `Encoder` and `Decoder` map the serde data model onto a stream of items
+//! (integers, byte and text strings, arrays, maps, booleans, null, floats);
+//! each format supplies only the byte layout of an item header through
+//! `Wire`. Both report `is_human_readable() == false`, so `serde_hex` fields
+//! travel as raw byte strings instead of hex text.
+//!
+//! Structs are maps keyed by field name, so field order and serde aliases
+//! behave as in JSON. Enum variants are the variant name (unit variants) or a
+//! one-entry map from the name to the content. Sequence and map lengths must
+//! be known up front, as both formats write them before the contents.
+
+use serde::de::{self, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use std::marker::PhantomData;
+use thiserror::Error;
+
+/// Deepest array/map nesting accepted while decoding.
+pub const MAX_DEPTH: usize = 128;
+
+/// Errors from encoding or decoding a self-describing binary format.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FormatError {
+    /// A `Serialize` or `Deserialize` implementation rejected the value.
+    #[error("{0}")]
+    Message(String),
+
+    /// The input ended inside an item.
+    #[error("unexpected end of input")]
+    UnexpectedEof,
+
+    /// Bytes remain after the top-level value.
+    #[error("{len} trailing bytes after the value")]
+    TrailingBytes { len: usize },
+
+    /// An item header byte this decoder does not support (e.g. a tag or an
+    /// extension type).
+    #[error("unsupported item header 0x{byte:02x}")]
+    Unsupported { byte: u8 },
+
+    /// A text string is not valid UTF-8.
+    #[error("invalid UTF-8 in text string")]
+    InvalidUtf8,
+
+    /// A negative integer below `i64::MIN`.
+    #[error("integer out of range")]
+    IntegerOutOfRange,
+
+    /// A length the format cannot represent, or that does not fit `usize`.
+    #[error("length {len} does not fit the encoding")]
+    LengthOverflow { len: u64 },
+
+    /// A sequence or map was serialized without a known length.
+    #[error("sequence or map length must be known up front")]
+    UnknownLength,
+
+    /// A sequence or map produced a different number of entries than it
+    /// declared.
+    #[error("entry count differs from the declared length")]
+    CountMismatch,
+
+    /// Arrays and maps are nested deeper than `MAX_DEPTH`.
+    #[error("nesting deeper than {max}")]
+    TooDeep { max: usize },
+}
+
+impl ser::Error for FormatError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        FormatError::Message(msg.to_string())
+    }
+}
+
+impl de::Error for FormatError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        FormatError::Message(msg.to_string())
+    }
+}
+
+/// The header of one item: its type and either its value or, for strings,
+/// arrays and maps, its length. String contents follow the header.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Head {
+    Unsigned(u64),
+    /// Always below zero.
+    Negative(i64),
+    Bytes(usize),
+    Text(usize),
+    Array(usize),
+    Map(usize),
+    Bool(bool),
+    Null,
+    F32(f32),
+    F64(f64),
+}
+
+/// The byte layout of item headers in one format.
+pub(crate) trait Wire {
+    /// Appends the encoding of `head` to `out`.
+    fn write_head(out: &mut Vec<u8>, head: Head) -> Result<(), FormatError>;
+
+    /// Reads one header from the front of `input`.
+    fn read_head(input: &mut &[u8]) -> Result<Head, FormatError>;
+}
+
+/// Splits the first `len` bytes off `input`.
+pub(crate) fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], FormatError> {
+    let (head, rest) = input
+        .split_at_checked(len)
+        .ok_or(FormatError::UnexpectedEof)?;
+    *input = rest;
+    Ok(head)
+}
+
+/// Splits the first `N` bytes off `input` as an array.
+pub(crate) fn take_array<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], FormatError> {
+    Ok(take(input, N)?.try_into().expect("took N bytes"))
+}
+
+/// A decoded length as `usize`.
+pub(crate) fn length(len: u64) -> Result<usize, FormatError> {
+    usize::try_from(len).map_err(|_| FormatError::LengthOverflow { len })
+}
+
+/// Encodes `value` in format `W`.
+pub(crate) fn to_vec<W: Wire, T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FormatError> {
+    let mut encoder = Encoder::<W> {
+        out: Vec::new(),
+        wire: PhantomData,
+    };
+    value.serialize(&mut encoder)?;
+    Ok(encoder.out)
+}
+
+/// Decodes exactly one `T` from `bytes` in format `W`.
+pub(crate) fn from_slice<'de, W: Wire, T: de::Deserialize<'de>>(
+    bytes: &'de [u8],
+) -> Result<T, FormatError> {
+    let mut decoder = Decoder::<W> {
+        input: bytes,
+        depth: 0,
+        wire: PhantomData,
+    };
+    let value = T::deserialize(&mut decoder)?;
+    if !decoder.input.is_empty() {
+        return Err(FormatError::TrailingBytes {
+            len: decoder.input.len(),
+        });
+    }
+    Ok(value)
+}
+
+struct Encoder<W> {
+    out: Vec<u8>,
+    wire: PhantomData<W>,
+}
+
+impl<W: Wire> Encoder<W> {
+    fn head(&mut self, head: Head) -> Result<(), FormatError> {
+        W::write_head(&mut self.out, head)
+    }
+
+    fn signed(&mut self, v: i64) -> Result<(), FormatError> {
+        match u64::try_from(v) {
+            Ok(v) => self.head(Head::Unsigned(v)),
+            Err(_) => self.head(Head::Negative(v)),
+        }
+    }
+
+    fn string(&mut self, head: Head, bytes: &[u8]) -> Result<(), FormatError> {
+        self.head(head)?;
+        self.out.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn text(&mut self, s: &str) -> Result<(), FormatError> {
+        self.string(Head::Text(s.len()), s.as_bytes())
+    }
+
+    /// Opens a container of `len` entries.
+    fn compound(&mut self, head: Head, len: usize) -> Result<Compound<'_, W>, FormatError> {
+        self.head(head)?;
+        Ok(Compound {
+            encoder: self,
+            remaining: len,
+        })
+    }
+
+    /// Opens the one-entry map `{variant: content}` holding an enum variant.
+    fn variant(&mut self, variant: &str) -> Result<(), FormatError> {
+        self.head(Head::Map(1))?;
+        self.text(variant)
+    }
+}
+
+/// An open array, map or struct that checks its declared entry count.
+struct Compound<'a, W> {
+    encoder: &'a mut Encoder<W>,
+    remaining: usize,
+}
+
+impl<W: Wire> Compound<'_, W> {
+    fn entry<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FormatError> {
+        self.remaining = self
+            .remaining
+            .checked_sub(1)
+            .ok_or(FormatError::CountMismatch)?;
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn finish(self) -> Result<(), FormatError> {
+        if self.remaining == 0 {
+            Ok(())
+        } else {
+            Err(FormatError::CountMismatch)
+        }
+    }
+}
+
+impl<'a, W: Wire> ser::Serializer for &'a mut Encoder<W> {
+    type Ok = ();
+    type Error = FormatError;
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Compound<'a, W>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Compound<'a, W>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), FormatError> {
+        self.head(Head::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), FormatError> {
+        self.signed(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), FormatError> {
+        self.signed(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), FormatError> {
+        self.signed(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), FormatError> {
+        self.signed(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), FormatError> {
+        self.head(Head::Unsigned(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), FormatError> {
+        self.head(Head::Unsigned(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), FormatError> {
+        self.head(Head::Unsigned(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), FormatError> {
+        self.head(Head::Unsigned(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), FormatError> {
+        self.head(Head::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), FormatError> {
+        self.head(Head::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), FormatError> {
+        self.text(v.encode_utf8(&mut [0u8; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), FormatError> {
+        self.text(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), FormatError> {
+        self.string(Head::Bytes(v.len()), v)
+    }
+
+    fn serialize_none(self) -> Result<(), FormatError> {
+        self.head(Head::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), FormatError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), FormatError> {
+        self.head(Head::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), FormatError> {
+        self.head(Head::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), FormatError> {
+        self.text(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), FormatError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), FormatError> {
+        self.variant(variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a, W>, FormatError> {
+        let len = len.ok_or(FormatError::UnknownLength)?;
+        self.compound(Head::Array(len), len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a, W>, FormatError> {
+        self.compound(Head::Array(len), len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, FormatError> {
+        self.compound(Head::Array(len), len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, FormatError> {
+        self.variant(variant)?;
+        self.compound(Head::Array(len), len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a, W>, FormatError> {
+        let len = len.ok_or(FormatError::UnknownLength)?;
+        // Keys and values are counted separately.
+        self.compound(Head::Map(len), 2 * len)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, FormatError> {
+        self.compound(Head::Map(len), 2 * len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, FormatError> {
+        self.variant(variant)?;
+        self.compound(Head::Map(len), 2 * len)
+    }
+}
+
+impl<W: Wire> ser::SerializeSeq for Compound<'_, W> {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FormatError> {
+        self.entry(value)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        self.finish()
+    }
+}
+
+impl<W: Wire> ser::SerializeTuple for Compound<'_, W> {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FormatError> {
+        self.entry(value)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        self.finish()
+    }
+}
+
+impl<W: Wire> ser::SerializeTupleStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FormatError> {
+        self.entry(value)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        self.finish()
+    }
+}
+
+impl<W: Wire> ser::SerializeTupleVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FormatError> {
+        self.entry(value)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        self.finish()
+    }
+}
+
+impl<W: Wire> ser::SerializeMap for Compound<'_, W> {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), FormatError> {
+        self.entry(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FormatError> {
+        self.entry(value)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        self.finish()
+    }
+}
+
+impl<W: Wire> ser::SerializeStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FormatError> {
+        self.entry(key)?;
+        self.entry(value)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        self.finish()
+    }
+}
+
+impl<W: Wire> ser::SerializeStructVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FormatError> {
+        self.entry(key)?;
+        self.entry(value)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        self.finish()
+    }
+}
+
+struct Decoder<'de, W> {
+    input: &'de [u8],
+    depth: usize,
+    wire: PhantomData<W>,
+}
+
+impl<'de, W: Wire> Decoder<'de, W> {
+    fn head(&mut self) -> Result<Head, FormatError> {
+        W::read_head(&mut self.input)
+    }
+
+    /// The next header, without consuming it.
+    fn peek(&self) -> Result<Head, FormatError> {
+        W::read_head(&mut { self.input })
+    }
+
+    fn text(&mut self, len: usize) -> Result<&'de str, FormatError> {
+        std::str::from_utf8(take(&mut self.input, len)?).map_err(|_| FormatError::InvalidUtf8)
+    }
+
+    /// Runs `f` one nesting level deeper, enforcing `MAX_DEPTH`.
+    fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, FormatError>,
+    ) -> Result<T, FormatError> {
+        if self.depth == MAX_DEPTH {
+            return Err(FormatError::TooDeep { max: MAX_DEPTH });
+        }
+        self.depth += 1;
+        let out = f(self);
+        self.depth -= 1;
+        out
+    }
+}
+
+impl<'de, W: Wire> de::Deserializer<'de> for &mut Decoder<'de, W> {
+    type Error = FormatError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FormatError> {
+        match self.head()? {
+            Head::Unsigned(v) => visitor.visit_u64(v),
+            Head::Negative(v) => visitor.visit_i64(v),
+            Head::Bytes(len) => visitor.visit_borrowed_bytes(take(&mut self.input, len)?),
+            Head::Text(len) => visitor.visit_borrowed_str(self.text(len)?),
+            Head::Array(len) => self.nested(|d| {
+                let mut seq = Entries {
+                    decoder: d,
+                    remaining: len,
+                };
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.finish().map(|()| value)
+            }),
+            Head::Map(len) => self.nested(|d| {
+                let mut map = Entries {
+                    decoder: d,
+                    remaining: len,
+                };
+                let value = visitor.visit_map(&mut map)?;
+                map.finish().map(|()| value)
+            }),
+            Head::Bool(v) => visitor.visit_bool(v),
+            Head::Null => visitor.visit_unit(),
+            Head::F32(v) => visitor.visit_f32(v),
+            Head::F64(v) => visitor.visit_f64(v),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FormatError> {
+        if self.peek()? == Head::Null {
+            self.head()?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, FormatError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, FormatError> {
+        match self.head()? {
+            Head::Text(len) => visitor.visit_enum(self.text(len)?.into_deserializer()),
+            Head::Map(1) => self.nested(|d| visitor.visit_enum(d)),
+            _ => Err(de::Error::custom("expected an enum variant")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// The entries of an array or map with a known count.
+struct Entries<'a, 'de, W> {
+    decoder: &'a mut Decoder<'de, W>,
+    remaining: usize,
+}
+
+impl<W> Entries<'_, '_, W> {
+    fn finish(&self) -> Result<(), FormatError> {
+        if self.remaining == 0 {
+            Ok(())
+        } else {
+            Err(FormatError::CountMismatch)
+        }
+    }
+}
+
+impl<'de, W: Wire> de::SeqAccess<'de> for Entries<'_, 'de, W> {
+    type Error = FormatError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, FormatError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        // Every entry takes at least one byte, so a forged count cannot
+        // reserve more than the input could hold.
+        Some(self.remaining.min(self.decoder.input.len()))
+    }
+}
+
+impl<'de, W: Wire> de::MapAccess<'de> for Entries<'_, 'de, W> {
+    type Error = FormatError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, FormatError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, FormatError> {
+        seed.deserialize(&mut *self.decoder)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining.min(self.decoder.input.len()))
+    }
+}
+
+impl<'de, W: Wire> de::EnumAccess<'de> for &mut Decoder<'de, W> {
+    type Error = FormatError;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), FormatError> {
+        let variant = seed.deserialize(&mut *self)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, W: Wire> de::VariantAccess<'de> for &mut Decoder<'de, W> {
+    type Error = FormatError;
+
+    fn unit_variant(self) -> Result<(), FormatError> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, FormatError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, FormatError> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, FormatError> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
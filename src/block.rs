@@ -1,31 +1,280 @@
 //! L2 block: header and body, with delegated root calculation.
 //!
-//! `L2Block::calculate_root()` defers to `header.calculate_root()` and
-//! `body.calculate_root()` and then composes them via `COMPUTE_BLOCK_ROOT`.
+//! `DigL2Block::calculate_root()` defers to `header.calculate_root()` and
+//! `body.calculate_root_for_version(header.version)` and then composes them via
+//! `COMPUTE_BLOCK_ROOT`.
 //!
 //! Construction via `new` enforces invariants between header and body
 //! (counts and body_root) and can surface `HeaderError`/`BodyError` via
 //! transparent composition.
 
 use crate::dig_l2_definition as definitions;
+use crate::hasher::{DynRootHasher, Keccak256, RootHasher, RuntimeHasher, Sha256Hasher};
+use crate::types::{BlockRoot, NetworkId, Pubkey};
 use crate::{body::L2BlockBody, emission::Emission, header::L2BlockHeader};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Write};
 use thiserror::Error;
 
 pub struct BuildL2BlockArgs<'ba> {
     pub version: u32,
-    pub network_id: [u8; 32],
+    pub network_id: NetworkId,
     pub epoch: u64,
-    pub prev_block_root: [u8; 32],
-    pub proposer_pubkey: [u8; 48],
+    pub prev_block_root: BlockRoot,
+    pub proposer_pubkey: Pubkey,
     pub data: Vec<u8>,
     pub extra_emissions: Vec<Emission>,
     pub attester_pubkeys: &'ba [[u8; 48]],
     pub cfg: &'ba crate::emission_config::ConsensusEmissionConfig,
+    /// Block timestamp (unix seconds); must be zero before `TIMESTAMP_VERSION`.
+    pub timestamp: u64,
+    /// Opaque application commitment; must be zero before `EXTRA_DATA_VERSION`.
+    pub extra_data: [u8; 32],
 }
 
-/// Full L2 block containing a header and a body.
+/// Optional validation rules applied by `DigL2Block::build_with_policy` and
+/// `DigL2Block::verify_with_policy`.
+///
+/// The default is the strict policy used by `build` and `verify`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockPolicy {
+    /// Permit several emissions to the same pubkey, e.g. when `extra_emissions`
+    /// intentionally top up the proposer.
+    pub allow_duplicate_pubkeys: bool,
+    /// Permit an all-zero `proposer_pubkey` at build time, for tests that use
+    /// placeholder keys. Rejected by default as a likely uninitialized array.
+    pub allow_zero_proposer_pubkey: bool,
+    /// Permit an all-zero `network_id` at build time, for tests that leave it
+    /// unset. Rejected by default: it almost always means an uninitialized
+    /// field and would place the block on "network 0".
+    pub allow_zero_network_id: bool,
+    /// Build without the proposer/attester consensus emissions, for
+    /// non-reward application blocks. `cfg` and `attester_pubkeys` are then
+    /// not used; `extra_emissions` are still included.
+    pub skip_consensus_emissions: bool,
+    /// What to do with zero-weight `extra_emissions` at build time.
+    pub zero_weight_extras: ZeroWeightPolicy,
+}
+
+/// How `DigL2Block::build_with_merge` assembles `extra_emissions` whose pubkey
+/// already receives a consensus emission.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Append every extra emission as its own record (the `build` behavior).
+    #[default]
+    Append,
+    /// Add the extra weight into the first consensus record for that pubkey,
+    /// so each consensus recipient keeps a single record. Extras paying other
+    /// pubkeys are still appended.
+    MergeIntoConsensus,
+}
+
+/// Build-time handling of zero-weight `extra_emissions` (see `Emission::validate`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ZeroWeightPolicy {
+    /// Keep them unchecked, as earlier versions did.
+    #[default]
+    Allow,
+    /// Drop them from the body; all-zero pubkeys are still rejected.
+    Filter,
+    /// Fail the build with `EmissionError::ZeroWeight` (or `ZeroPubkey`).
+    Reject,
+}
+
+/// Size limits enforced by `DigL2Block::from_json_checked` on untrusted input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockLimits {
+    /// Largest accepted `data_count`.
+    pub max_data_count: u32,
+    /// Largest accepted `emissions_count`.
+    pub max_emissions_count: u32,
+}
+
+/// A block's header and root without its body, e.g. for block explorer APIs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSummary {
+    /// The block header.
+    pub header: L2BlockHeader,
+    /// `DigL2Block::calculate_root()`, serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex32")]
+    pub block_root: definitions::Hash32,
+}
+
+impl From<&DigL2Block> for BlockSummary {
+    fn from(block: &DigL2Block) -> Self {
+        BlockSummary {
+            header: block.header.clone(),
+            block_root: block.calculate_root(),
+        }
+    }
+}
+
+/// A succinct block commitment announced over gossip before the full block,
+/// as returned by `DigL2Block::announcement`.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockAnnouncement {
+    /// `DigL2Block::calculate_root()`, serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex32")]
+    pub block_root: definitions::Hash32,
+    /// Header epoch.
+    pub epoch: u64,
+    /// Header proposer pubkey, serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex48")]
+    pub proposer_pubkey: [u8; 48],
+    /// Header version.
+    pub version: u32,
+}
+
+impl BlockAnnouncement {
+    /// Whether `block` is the block this announces: its recomputed root and
+    /// its header epoch, proposer and version all agree with the announcement.
+    pub fn matches(&self, block: &DigL2Block) -> bool {
+        let h = &block.header;
+        h.epoch == self.epoch
+            && h.proposer_pubkey == self.proposer_pubkey
+            && h.version == self.version
+            && definitions::ct_eq_hash(&block.calculate_root(), &self.block_root)
+    }
+}
+
+/// A block without its consensus emissions, as returned by
+/// `DigL2Block::to_compact`.
+///
+/// The proposer and attester records are derived again from the header's
+/// `proposer_pubkey`, `attester_pubkeys` and the consensus config by
+/// `reconstruct`; only the extra emissions are stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactBlock {
+    /// The original header, including the `body_root` the rebuilt body must match.
+    pub header: L2BlockHeader,
+    /// Application data.
+    pub data: Vec<u8>,
+    /// Attesters paid by the consensus emissions, in build order.
+    pub attester_pubkeys: Vec<[u8; 48]>,
+    /// Body emissions after the consensus records.
+    pub extra_emissions: Vec<Emission>,
+}
+
+impl CompactBlock {
+    /// Rebuilds the full block, regenerating the consensus emissions under
+    /// `cfg` ahead of the extras exactly as `DigL2Block::build` does.
+    ///
+    /// Fails with `BodyRootMismatch` if the rebuilt body does not commit to
+    /// the stored `header.body_root`, e.g. when `cfg` differs from the one
+    /// used by `to_compact`.
+    pub fn reconstruct(
+        &self,
+        cfg: &crate::emission_config::ConsensusEmissionConfig,
+    ) -> Result<DigL2Block, BlockError> {
+        let h = &self.header;
+        let args = BuildL2BlockArgs {
+            version: h.version,
+            network_id: h.network_id.into(),
+            epoch: h.epoch,
+            prev_block_root: h.prev_block_root.into(),
+            proposer_pubkey: h.proposer_pubkey.into(),
+            data: self.data.clone(),
+            extra_emissions: self.extra_emissions.clone(),
+            attester_pubkeys: &self.attester_pubkeys,
+            cfg,
+            timestamp: h.timestamp,
+            extra_data: h.extra_data,
+        };
+        // The stored header already passed whatever policy built it; only
+        // agreement with its body root matters here.
+        let policy = BlockPolicy {
+            allow_duplicate_pubkeys: true,
+            allow_zero_proposer_pubkey: true,
+            allow_zero_network_id: true,
+            ..BlockPolicy::default()
+        };
+        let block = DigL2Block::build_with_policy(&args, &policy)?;
+        if !definitions::ct_eq_hash(&block.header.body_root, &h.body_root) {
+            return Err(BlockError::BodyRootMismatch {
+                header_body_root: h.body_root,
+                calculated: block.header.body_root,
+            });
+        }
+        Ok(block)
+    }
+}
+
+/// Every intermediate root behind a block root, as returned by
+/// `DigL2Block::root_breakdown`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RootBreakdown {
+    /// `DATA_ROOT` under the data root mode of the header version.
+    pub data_root: definitions::Hash32,
+    /// `EMISSIONS_ROOT`.
+    pub emissions_root: definitions::Hash32,
+    /// `BODY_ROOT` recomputed from the body (not the header's stored value).
+    pub body_root: definitions::Hash32,
+    /// `HEADER_ROOT`.
+    pub header_root: definitions::Hash32,
+    /// `BLOCK_ROOT`, equal to `DigL2Block::calculate_root()`.
+    pub block_root: definitions::Hash32,
+}
+
+/// Total emission weights of a block grouped by recipient role.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RewardSummary {
+    /// Sum of weights paid to the header's `proposer_pubkey`.
+    pub proposer: u64,
+    /// Sum of weights paid to the supplied attesters.
+    pub attesters: u64,
+    /// Sum of weights paid to every other recipient.
+    pub extras: u64,
+}
+
+/// Magic bytes that open every canonical block encoding.
+pub const CANONICAL_MAGIC: [u8; 4] = *b"DIGB";
+
+/// Layout version written after `CANONICAL_MAGIC`. Bump it whenever the
+/// canonical byte layout changes so older decoders reject the new format.
+pub const CANONICAL_FORMAT_VERSION: u8 = 1;
+
+/// Length of the `CANONICAL_MAGIC || format version` prefix.
+pub const CANONICAL_PREFIX_LEN: usize = CANONICAL_MAGIC.len() + 1;
+
+/// Length of the fixed header part of the canonical byte format.
+pub const CANONICAL_HEADER_LEN: usize = 4 + 32 + 8 + 32 + 32 + 4 + 4 + 48 + 8 + 32;
+
+/// Length of one emission in the canonical byte format (`pubkey || weight`).
+pub const CANONICAL_EMISSION_LEN: usize = 48 + 8;
+
+/// Length of the canonical byte encoding of a block with `data_len` data bytes
+/// and `emissions_len` emissions; `DigL2Block::encoded_size` for such a block.
+pub fn encoded_size_for(data_len: usize, emissions_len: usize) -> usize {
+    CANONICAL_PREFIX_LEN
+        + CANONICAL_HEADER_LEN
+        + 4
+        + data_len
+        + 4
+        + emissions_len * CANONICAL_EMISSION_LEN
+}
+
+/// One difference reported by `DigL2Block::diff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockFieldDiff {
+    /// A header field (named as in `L2BlockHeader`) differs.
+    Header { field: &'static str },
+    /// The body data bytes differ.
+    Data,
+    /// An emission present only in the other block.
+    EmissionAdded { pubkey: [u8; 48], weight: u64 },
+    /// An emission present only in this block.
+    EmissionRemoved { pubkey: [u8; 48], weight: u64 },
+    /// An emission to the same pubkey with a different weight.
+    EmissionChanged {
+        pubkey: [u8; 48],
+        old_weight: u64,
+        new_weight: u64,
+    },
+}
+
+/// Full L2 block containing a header and a body.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DigL2Block {
     pub header: L2BlockHeader,
     pub body: L2BlockBody,
@@ -33,38 +282,575 @@ pub struct DigL2Block {
 
 impl DigL2Block {
     /// Calculates the `BLOCK_ROOT` by composing the `HEADER_ROOT` and `BODY_ROOT`.
+    ///
+    /// The body root follows the rules of `header.version`.
     pub fn calculate_root(&self) -> definitions::Hash32 {
         let header_root = self.header.calculate_root();
-        let body_root = self.body.calculate_root();
+        let body_root = self.body.calculate_root_for_version(self.header.version);
         definitions::COMPUTE_BLOCK_ROOT(&header_root, &body_root)
     }
 
+    /// Whether `header.body_root` commits to `body` under the rules of
+    /// `header.version`.
+    ///
+    /// This is the root comparison `new` and `verify` make, without their
+    /// version, timestamp and count checks; it still hashes the whole body.
+    /// `reseal` makes it true.
+    pub fn verify_header_commits_body(&self) -> bool {
+        check_body_root(&self.header, &self.body).is_ok()
+    }
+
+    /// The intermediate roots `calculate_root` composes, for debugging which
+    /// subtree changed a block root.
+    pub fn root_breakdown(&self) -> RootBreakdown {
+        let mode = definitions::DataRootMode::for_version(self.header.version);
+        let data_root = self.body.calculate_data_root_with_mode(mode);
+        let emissions_root = self
+            .body
+            .calculate_emissions_root_for_version(self.header.version);
+        let body_root = definitions::COMPUTE_BODY_ROOT_FOR_VERSION(
+            self.header.version,
+            &data_root,
+            &emissions_root,
+        );
+        let header_root = self.header.calculate_root();
+        RootBreakdown {
+            data_root,
+            emissions_root,
+            body_root,
+            header_root,
+            block_root: definitions::COMPUTE_BLOCK_ROOT(&header_root, &body_root),
+        }
+    }
+
+    /// `calculate_root` with every hash computed by `hasher` instead of
+    /// SHA-256. Domain tags, leaf layouts and version gating are unchanged.
+    pub fn calculate_root_with<H: RootHasher + ?Sized>(&self, hasher: &H) -> definitions::Hash32 {
+        let header_root = definitions::header_root_with(hasher, &self.header);
+        let body_root = self.body.calculate_root_for_version_with(
+            hasher,
+            self.header.version,
+            &mut Vec::new(),
+            &mut Vec::new(),
+        );
+        definitions::block_root_with(hasher, &header_root, &body_root)
+    }
+
+    /// `calculate_root_with` for a hasher chosen at runtime, e.g. from
+    /// `HashAlgorithm::runtime_hasher`. Every hash goes through dynamic
+    /// dispatch, so this is slower than the generic path.
+    ///
+    /// `hasher` is reset first, so input absorbed before the call is
+    /// discarded; it is left reset.
+    pub fn calculate_root_dyn(&self, hasher: &mut dyn RuntimeHasher) -> definitions::Hash32 {
+        hasher.reset();
+        self.calculate_root_with(&DynRootHasher::new(hasher))
+    }
+
+    /// The block root under `Keccak256`, matching Solidity's `keccak256`, so
+    /// the root can be recomputed on an EVM chain.
+    pub fn calculate_root_keccak(&self) -> definitions::Hash32 {
+        self.calculate_root_with(&Keccak256)
+    }
+
+    /// The gossip announcement for this block (root, epoch, proposer, version).
+    pub fn announcement(&self) -> BlockAnnouncement {
+        BlockAnnouncement {
+            block_root: self.calculate_root(),
+            epoch: self.header.epoch,
+            proposer_pubkey: self.header.proposer_pubkey,
+            version: self.header.version,
+        }
+    }
+
+    /// Start a fluent `DigL2BlockBuilder`.
+    pub fn builder() -> crate::builder::DigL2BlockBuilder {
+        crate::builder::DigL2BlockBuilder::new()
+    }
+
+    /// Calculates the `BLOCK_ROOT` as a `0x`-prefixed lowercase hex string.
+    pub fn calculate_root_hex(&self) -> String {
+        crate::serde_hex::encode_lower_hex_prefixed(&self.calculate_root())
+    }
+
+    /// Length in bytes of `to_canonical_bytes()`, computed without serializing.
+    pub fn encoded_size(&self) -> usize {
+        encoded_size_for(self.body.data.len(), self.body.emissions.len())
+    }
+
+    /// Canonical byte encoding of the block.
+    ///
+    /// Layout: `CANONICAL_MAGIC` and the `CANONICAL_FORMAT_VERSION` byte, the
+    /// header fields in declaration order, then a `u32` data length followed by
+    /// the data, then a `u32` emission count followed by each emission as
+    /// `pubkey || weight`. All integers are little-endian. Lengths are written
+    /// as `u32`, matching the header's count fields.
+    ///
+    /// # Panics
+    /// If `data` or `emissions` is longer than `u32::MAX`; such a body cannot
+    /// match the header counts, so it never passes `verify` or `reseal`.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let len = |field, n| {
+            count_u32(field, n).expect("canonical body length fits the u32 length prefix")
+        };
+        let h = &self.header;
+        let mut out = Vec::with_capacity(self.encoded_size());
+        out.extend_from_slice(&CANONICAL_MAGIC);
+        out.push(CANONICAL_FORMAT_VERSION);
+        out.extend_from_slice(&h.version.to_le_bytes());
+        out.extend_from_slice(&h.network_id);
+        out.extend_from_slice(&h.epoch.to_le_bytes());
+        out.extend_from_slice(&h.prev_block_root);
+        out.extend_from_slice(&h.body_root);
+        out.extend_from_slice(&h.data_count.to_le_bytes());
+        out.extend_from_slice(&h.emissions_count.to_le_bytes());
+        out.extend_from_slice(&h.proposer_pubkey);
+        out.extend_from_slice(&h.timestamp.to_le_bytes());
+        out.extend_from_slice(&h.extra_data);
+        out.extend_from_slice(&len("data_count", self.body.data.len()).to_le_bytes());
+        out.extend_from_slice(&self.body.data);
+        out.extend_from_slice(&len("emissions_count", self.body.emissions.len()).to_le_bytes());
+        for e in &self.body.emissions {
+            out.extend_from_slice(&e.pubkey);
+            out.extend_from_slice(&e.weight.to_le_bytes());
+        }
+        out
+    }
+
+    /// Decodes `to_canonical_bytes` output.
+    ///
+    /// Rejects a missing magic with `BadMagic`, a format version other than
+    /// `CANONICAL_FORMAT_VERSION` with `UnsupportedFormatVersion`, and truncated
+    /// or trailing input with `InvalidCanonicalLength`. The block is decoded
+    /// as-is; call `verify` to check it.
+    pub fn from_canonical_bytes(bytes: &[u8]) -> Result<Self, BlockError> {
+        let invalid = || BlockError::InvalidCanonicalLength { len: bytes.len() };
+        let mut r = CanonicalReader(bytes);
+        if r.take::<4>().ok_or(BlockError::BadMagic)? != CANONICAL_MAGIC {
+            return Err(BlockError::BadMagic);
+        }
+        let [version] = r.take::<1>().ok_or_else(invalid)?;
+        if version != CANONICAL_FORMAT_VERSION {
+            return Err(BlockError::UnsupportedFormatVersion { version });
+        }
+        let header = r.take_header().ok_or_else(invalid)?;
+        let data_len = r.take_u32().ok_or_else(invalid)? as usize;
+        let data = r.take_slice(data_len).ok_or_else(invalid)?.to_vec();
+        let emissions_len = r.take_u32().ok_or_else(invalid)? as usize;
+        if r.0.len() != emissions_len.saturating_mul(CANONICAL_EMISSION_LEN) {
+            return Err(invalid());
+        }
+        let emissions =
+            r.0.chunks_exact(CANONICAL_EMISSION_LEN)
+                .map(|chunk| {
+                    let (pubkey, weight) = chunk.split_at(48);
+                    Emission {
+                        pubkey: pubkey.try_into().unwrap(),
+                        weight: u64::from_le_bytes(weight.try_into().unwrap()),
+                    }
+                })
+                .collect();
+        Ok(DigL2Block {
+            header,
+            body: L2BlockBody { data, emissions },
+        })
+    }
+
+    /// Lists the differences from `self` to `other`, for debugging root mismatches.
+    ///
+    /// Header fields are reported in declaration order, then `Data`, then
+    /// emission changes ordered by pubkey. Emissions are matched by pubkey, so a
+    /// new weight is reported as `EmissionChanged`; when a pubkey appears several
+    /// times its weights are paired in ascending order and the surplus on either
+    /// side is reported as added/removed. Emission order alone is not a difference.
+    pub fn diff(&self, other: &DigL2Block) -> Vec<BlockFieldDiff> {
+        let (a, b) = (&self.header, &other.header);
+        let header_fields = [
+            ("version", a.version != b.version),
+            ("network_id", a.network_id != b.network_id),
+            ("epoch", a.epoch != b.epoch),
+            ("prev_block_root", a.prev_block_root != b.prev_block_root),
+            ("body_root", a.body_root != b.body_root),
+            ("data_count", a.data_count != b.data_count),
+            ("emissions_count", a.emissions_count != b.emissions_count),
+            ("proposer_pubkey", a.proposer_pubkey != b.proposer_pubkey),
+            ("timestamp", a.timestamp != b.timestamp),
+            ("extra_data", a.extra_data != b.extra_data),
+        ];
+        let mut out: Vec<BlockFieldDiff> = header_fields
+            .into_iter()
+            .filter(|(_, differs)| *differs)
+            .map(|(field, _)| BlockFieldDiff::Header { field })
+            .collect();
+        if self.body.data != other.body.data {
+            out.push(BlockFieldDiff::Data);
+        }
+
+        let mut by_pubkey: BTreeMap<[u8; 48], (Vec<u64>, Vec<u64>)> = BTreeMap::new();
+        for e in &self.body.emissions {
+            by_pubkey.entry(e.pubkey).or_default().0.push(e.weight);
+        }
+        for e in &other.body.emissions {
+            by_pubkey.entry(e.pubkey).or_default().1.push(e.weight);
+        }
+        for (pubkey, (mut old, mut new)) in by_pubkey {
+            old.sort_unstable();
+            new.sort_unstable();
+            let paired = old.len().min(new.len());
+            for (&old_weight, &new_weight) in old.iter().zip(&new) {
+                if old_weight != new_weight {
+                    out.push(BlockFieldDiff::EmissionChanged {
+                        pubkey,
+                        old_weight,
+                        new_weight,
+                    });
+                }
+            }
+            for &weight in &old[paired..] {
+                out.push(BlockFieldDiff::EmissionRemoved { pubkey, weight });
+            }
+            for &weight in &new[paired..] {
+                out.push(BlockFieldDiff::EmissionAdded { pubkey, weight });
+            }
+        }
+        out
+    }
+
+    /// Checks that the body contains the consensus emissions `cfg` prescribes.
+    ///
+    /// Recomputes the proposer and attester tuples with
+    /// `BUILD_CONSENSUS_EMISSIONS_SPEC` and requires each to appear in the body; every
+    /// body emission matches at most one tuple, and any remaining emissions are
+    /// treated as extras and ignored. Order is not checked.
+    pub fn verify_consensus_emissions(
+        &self,
+        proposer_pubkey: &[u8; 48],
+        attesters: &[[u8; 48]],
+        cfg: &crate::emission_config::ConsensusEmissionConfig,
+    ) -> Result<(), BlockError> {
+        cfg.validate_for_attesters(attesters.len())?;
+        let expected = definitions::BUILD_CONSENSUS_EMISSIONS_SPEC(
+            *proposer_pubkey,
+            attesters,
+            cfg.proposer_reward_share,
+            cfg.attester_reward_share,
+        )?;
+        let mut unmatched: Vec<&Emission> = self.body.emissions.iter().collect();
+        for definitions::EmissionSpec { pubkey, weight } in expected {
+            match unmatched
+                .iter()
+                .position(|e| e.pubkey == pubkey && e.weight == weight)
+            {
+                Some(i) => {
+                    unmatched.swap_remove(i);
+                }
+                None => return Err(BlockError::MissingConsensusEmission { pubkey, weight }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits the body emissions into `(consensus, extra)`, each in body order.
+    ///
+    /// Recomputes the proposer and attester tuples with
+    /// `BUILD_CONSENSUS_EMISSIONS_SPEC` and claims one body emission per tuple:
+    /// an exact `(pubkey, weight)` match first, otherwise a record to the same
+    /// pubkey with a larger weight, i.e. a consensus record an extra was merged
+    /// into (`MergePolicy::MergeIntoConsensus`). Everything unclaimed is extra.
+    /// If `cfg` is invalid for `attesters`, no tuples exist and every emission
+    /// is extra.
+    pub fn classify_emissions(
+        &self,
+        proposer_pubkey: &[u8; 48],
+        attesters: &[[u8; 48]],
+        cfg: &crate::emission_config::ConsensusEmissionConfig,
+    ) -> (Vec<&Emission>, Vec<&Emission>) {
+        let expected = cfg
+            .validate_for_attesters(attesters.len())
+            .ok()
+            .and_then(|()| {
+                definitions::BUILD_CONSENSUS_EMISSIONS_SPEC(
+                    *proposer_pubkey,
+                    attesters,
+                    cfg.proposer_reward_share,
+                    cfg.attester_reward_share,
+                )
+                .ok()
+            })
+            .unwrap_or_default();
+        let emissions = &self.body.emissions;
+        let mut claimed = vec![false; emissions.len()];
+        let mut topped_up = Vec::new();
+        for spec in expected {
+            match (0..emissions.len()).find(|&i| {
+                !claimed[i]
+                    && emissions[i].pubkey == spec.pubkey
+                    && emissions[i].weight == spec.weight
+            }) {
+                Some(i) => claimed[i] = true,
+                None => topped_up.push(spec),
+            }
+        }
+        for spec in topped_up {
+            if let Some(i) = (0..emissions.len()).find(|&i| {
+                !claimed[i]
+                    && emissions[i].pubkey == spec.pubkey
+                    && emissions[i].weight > spec.weight
+            }) {
+                claimed[i] = true;
+            }
+        }
+        let (consensus, extra): (Vec<_>, Vec<_>) =
+            emissions.iter().zip(claimed).partition(|(_, c)| *c);
+        (
+            consensus.into_iter().map(|(e, _)| e).collect(),
+            extra.into_iter().map(|(e, _)| e).collect(),
+        )
+    }
+
+    /// Strips the consensus emissions `cfg` prescribes for `attesters`,
+    /// keeping the data, the attester list and the remaining extras.
+    ///
+    /// The body must start with exactly those records in build order, as
+    /// `build` lays them out; otherwise the first missing one is reported as
+    /// `MissingConsensusEmission`. `CompactBlock::reconstruct` with the same
+    /// `cfg` returns this block.
+    pub fn to_compact(
+        &self,
+        attesters: &[[u8; 48]],
+        cfg: &crate::emission_config::ConsensusEmissionConfig,
+    ) -> Result<CompactBlock, BlockError> {
+        cfg.validate_for_attesters(attesters.len())?;
+        let expected = definitions::BUILD_CONSENSUS_EMISSIONS_SPEC(
+            self.header.proposer_pubkey,
+            attesters,
+            cfg.proposer_reward_share,
+            cfg.attester_reward_share,
+        )?;
+        for (i, spec) in expected.iter().enumerate() {
+            match self.body.emissions.get(i) {
+                Some(e) if e.pubkey == spec.pubkey && e.weight == spec.weight => {}
+                _ => {
+                    return Err(BlockError::MissingConsensusEmission {
+                        pubkey: spec.pubkey,
+                        weight: spec.weight,
+                    });
+                }
+            }
+        }
+        Ok(CompactBlock {
+            header: self.header.clone(),
+            data: self.body.data.clone(),
+            attester_pubkeys: attesters.to_vec(),
+            extra_emissions: self.body.emissions[expected.len()..].to_vec(),
+        })
+    }
+
+    /// Totals emission weights for the proposer, the given attesters, and extras.
+    ///
+    /// Roles are assigned by pubkey: emissions to `header.proposer_pubkey` count
+    /// as proposer (including extras that top it up), emissions to a key in
+    /// `attesters` count as attesters, everything else counts as extras.
+    /// Returns `BlockError::WeightOverflow` if any total exceeds `u64::MAX`.
+    pub fn reward_summary(&self, attesters: &[[u8; 48]]) -> Result<RewardSummary, BlockError> {
+        let mut summary = RewardSummary::default();
+        for e in &self.body.emissions {
+            let (total, role) = if e.pubkey == self.header.proposer_pubkey {
+                (&mut summary.proposer, "proposer")
+            } else if attesters.contains(&e.pubkey) {
+                (&mut summary.attesters, "attesters")
+            } else {
+                (&mut summary.extras, "extras")
+            };
+            *total = total
+                .checked_add(e.weight)
+                .ok_or(BlockError::WeightOverflow { role })?;
+        }
+        Ok(summary)
+    }
+
     /// Validates consistency between `header` and `body` and returns a block if valid.
     ///
     /// Checks:
     /// - `data_count` and `emissions_count` match body lengths.
-    /// - `header.body_root` equals `body.calculate_root()`.
+    /// - `header.body_root` equals `body.calculate_root_for_version(header.version)`.
     /// - If `expected_version` is provided, header version matches it.
     pub fn new(
         header: L2BlockHeader,
         body: L2BlockBody,
         expected_version: Option<u32>,
     ) -> Result<Self, BlockError> {
-        if let Some(v) = expected_version {
-            header.validate_version(v)?;
+        check_header_body(&header, &body, expected_version)?;
+        Ok(DigL2Block { header, body })
+    }
+
+    /// Recomputes `body_root`, `data_count` and `emissions_count` from the
+    /// current body and writes them into the header.
+    ///
+    /// All other header fields are left as they are, so a body edited after
+    /// assembly can be resealed and then pass `verify`. Fails with
+    /// `CountOverflow`, leaving the header untouched, if a count exceeds `u32`.
+    pub fn reseal(&mut self) -> Result<(), BlockError> {
+        let (data_count, emissions_count) = body_counts(&self.body)?;
+        self.header.body_root = self.body.calculate_root_for_version(self.header.version);
+        self.header.data_count = data_count;
+        self.header.emissions_count = emissions_count;
+        Ok(())
+    }
+
+    /// Parses and verifies a block from untrusted JSON, rejecting it early if
+    /// its declared counts exceed `limits`.
+    ///
+    /// The header is decoded first while the body is skipped without being
+    /// materialized; a count over `limits` fails with `LimitExceeded`. The body
+    /// is then decoded against the declared counts and decoding stops with a
+    /// `Json` error as soon as it holds more data bytes or emissions than
+    /// declared, so neither allocation nor hashing exceeds `limits`. A shorter
+    /// body fails with `HeaderError::CountMismatch` before any root is
+    /// computed; only then is the block checked with `verify(None)`.
+    pub fn from_json_checked(json: &str, limits: &BlockLimits) -> Result<Self, BlockError> {
+        #[derive(Deserialize)]
+        struct HeaderOnly {
+            header: L2BlockHeader,
         }
-        // Compare roots first so that a mutated body triggers BodyRootMismatch
-        // which is typically the more informative error than counts mismatch.
-        let calc_body_root = body.calculate_root();
-        if header.body_root != calc_body_root {
-            return Err(BlockError::BodyRootMismatch {
-                header_body_root: header.body_root,
-                calculated: calc_body_root,
+        let HeaderOnly { header } = serde_json::from_str(json)?;
+        for (field, count, max) in [
+            ("data_count", header.data_count, limits.max_data_count),
+            (
+                "emissions_count",
+                header.emissions_count,
+                limits.max_emissions_count,
+            ),
+        ] {
+            if count > max {
+                return Err(BlockError::LimitExceeded { field, count, max });
+            }
+        }
+        let seed = BoundedBlockSeed {
+            max_data: header.data_count as usize,
+            max_emissions: header.emissions_count as usize,
+        };
+        let mut de = serde_json::Deserializer::from_str(json);
+        let block = serde::de::DeserializeSeed::deserialize(seed, &mut de)?;
+        de.end()?;
+        block
+            .header
+            .validate_counts(block.body.data.len(), block.body.emissions.len())?;
+        block.verify(None)?;
+        Ok(block)
+    }
+
+    /// Verifies an existing block under the default `BlockPolicy`.
+    ///
+    /// Runs the same checks as `new` and additionally rejects duplicate
+    /// emission pubkeys.
+    pub fn verify(&self, expected_version: Option<u32>) -> Result<(), BlockError> {
+        self.verify_with_policy(expected_version, &BlockPolicy::default())
+    }
+
+    /// Verifies an existing block, applying the optional checks of `policy`.
+    pub fn verify_with_policy(
+        &self,
+        expected_version: Option<u32>,
+        policy: &BlockPolicy,
+    ) -> Result<(), BlockError> {
+        check_header_body(&self.header, &self.body, expected_version)?;
+        check_policy(&self.body, policy)
+    }
+
+    /// Runs every `verify` check and reports all failures instead of the first.
+    ///
+    /// Covers the version (if `expected_version` is given), timestamp and
+    /// `extra_data` gating, `body_root`, both counts, duplicate emission
+    /// pubkeys, and per-pubkey weight overflow (see `emission_totals`), in that
+    /// order. Meant for tooling; `new` and `verify` stay fail-fast.
+    pub fn validate_all(&self, expected_version: Option<u32>) -> Result<(), Vec<BlockError>> {
+        let header = &self.header;
+        let body = &self.body;
+        let mut errors = Vec::new();
+        if let Some(v) = expected_version
+            && let Err(e) = header.validate_version(v)
+        {
+            errors.push(e.into());
+        }
+        if let Err(e) = header.validate_timestamp() {
+            errors.push(e.into());
+        }
+        if let Err(e) = header.validate_extra_data() {
+            errors.push(e.into());
+        }
+        if let Err(e) = check_body_root(header, body) {
+            errors.push(e);
+        }
+        for (field, expected, actual) in [
+            ("data_count", header.data_count, body.data.len()),
+            (
+                "emissions_count",
+                header.emissions_count,
+                body.emissions.len(),
+            ),
+        ] {
+            if expected as usize != actual {
+                errors.push(
+                    crate::header::HeaderError::CountMismatch {
+                        field,
+                        expected: expected as usize,
+                        actual,
+                    }
+                    .into(),
+                );
+            }
+        }
+        if let Err(e) = check_policy(body, &BlockPolicy::default()) {
+            errors.push(e);
+        }
+        if let Err(e) = body.emission_totals() {
+            errors.push(e.into());
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validates that `child` directly follows `self`.
+    ///
+    /// Checks that both share a `network_id`, that `child.prev_block_root` is
+    /// this block's root, and that `child.epoch == self.epoch + 1`. A parent at
+    /// `u64::MAX` has no successor (`EpochOverflow`).
+    pub fn validate_successor(&self, child: &DigL2Block) -> Result<(), BlockError> {
+        if child.header.network_id != self.header.network_id {
+            return Err(BlockError::NetworkIdMismatch {
+                parent: self.header.network_id,
+                child: child.header.network_id,
             });
         }
-        // Then validate counts for completeness.
-        header.validate_counts(body.data.len(), body.emissions.len())?;
-        Ok(DigL2Block { header, body })
+        let parent_root = self.calculate_root();
+        if !definitions::ct_eq_hash(&child.header.prev_block_root, &parent_root) {
+            return Err(BlockError::PrevBlockRootMismatch {
+                expected: parent_root,
+                found: child.header.prev_block_root,
+            });
+        }
+        if child.header.timestamp < self.header.timestamp {
+            return Err(BlockError::TimestampRegression {
+                parent: self.header.timestamp,
+                child: child.header.timestamp,
+            });
+        }
+        let expected_epoch = self
+            .header
+            .epoch
+            .checked_add(1)
+            .ok_or(BlockError::EpochOverflow)?;
+        if child.header.epoch != expected_epoch {
+            return Err(BlockError::EpochNotSuccessor {
+                expected: expected_epoch,
+                found: child.header.epoch,
+            });
+        }
+        Ok(())
     }
 
     /// Build a block from raw inputs, constructing required consensus emissions
@@ -72,119 +858,894 @@ impl DigL2Block {
     ///
     /// Steps:
     /// - Validates the provided `ConsensusEmissionConfig` against the attester list.
-    /// - Uses `BUILD_CONSENSUS_EMISSIONS` to create mandatory emissions (proposer + attesters).
+    /// - Uses `BUILD_CONSENSUS_EMISSIONS_SPEC` to create mandatory emissions (proposer + attesters).
     /// - Appends any `extra_emissions` provided by the caller.
-    /// - Assembles the body from `data` and all emissions, computes `body_root`.
+    /// - Rejects duplicate emission pubkeys and an all-zero `proposer_pubkey`
+    ///   or `network_id` (see `build_with_policy` to allow them).
+    /// - Assembles the body from `data` and all emissions, computes `body_root`
+    ///   under the rules of `version`.
     /// - Fills header counts and `body_root`, leaving other header fields as provided.
+    ///
+    /// Empty `data` is valid: with no attesters and a zero attester share the
+    /// body holds only the proposer emission, and its data root is
+    /// `EMPTY_MERKLE_ROOT()` under every data root mode.
     pub fn build(args: &BuildL2BlockArgs<'_>) -> Result<Self, BlockError> {
-        // Validate config with respect to the number of attesters
-        args.cfg
-            .validate_for_attesters(args.attester_pubkeys.len())?;
-
-        // Build consensus emissions tuples then convert to Emission
-        let tuples = definitions::BUILD_CONSENSUS_EMISSIONS(
-            args.proposer_pubkey,
-            args.attester_pubkeys,
-            args.cfg.proposer_reward_share,
-            args.cfg.attester_reward_share,
-        )?;
-        let mut emissions: Vec<Emission> = tuples
-            .into_iter()
-            .map(|(pk, w)| Emission {
-                pubkey: pk,
-                weight: w,
-            })
-            .collect();
-        emissions.extend(args.extra_emissions.clone());
+        Self::build_with_policy(args, &BlockPolicy::default())
+    }
+
+    /// Builds the largest block whose `encoded_size` fits `max_bytes`, dropping
+    /// `extra_emissions` from the back of the list.
+    ///
+    /// Extras are taken in caller order, so list them by descending priority.
+    /// Consensus emissions and `data` are always kept; if they alone exceed
+    /// the budget the build fails with `OverBudget`. Every emission encodes to
+    /// the same `CANONICAL_EMISSION_LEN`, so the kept extras are exactly the
+    /// longest prefix that fits. The block is then assembled with `build`.
+    pub fn pack_to_budget(
+        mut args: BuildL2BlockArgs<'_>,
+        max_bytes: usize,
+    ) -> Result<Self, BlockError> {
+        let consensus = 1 + args.attester_pubkeys.len();
+        let base = encoded_size_for(args.data.len(), consensus);
+        if base > max_bytes {
+            return Err(BlockError::OverBudget {
+                size: base,
+                max: max_bytes,
+            });
+        }
+        let room = (max_bytes - base) / CANONICAL_EMISSION_LEN;
+        args.extra_emissions.truncate(room);
+        Self::build(&args)
+    }
+
+    /// Builds an application block without consensus emissions, i.e.
+    /// `build_with_policy` with `BlockPolicy::skip_consensus_emissions` set.
+    ///
+    /// With no `extra_emissions` the body equals `L2BlockBody::data_only(args.data)`.
+    pub fn build_data_only(args: &BuildL2BlockArgs<'_>) -> Result<Self, BlockError> {
+        Self::build_with_policy(
+            args,
+            &BlockPolicy {
+                skip_consensus_emissions: true,
+                ..BlockPolicy::default()
+            },
+        )
+    }
+
+    /// Like `build`, applying the optional checks of `policy`.
+    pub fn build_with_policy(
+        args: &BuildL2BlockArgs<'_>,
+        policy: &BlockPolicy,
+    ) -> Result<Self, BlockError> {
+        Self::build_with_merge(args, policy, MergePolicy::Append)
+    }
+
+    /// Like `build_with_policy`, with `merge` deciding how `extra_emissions`
+    /// that pay a consensus recipient are assembled.
+    pub fn build_with_merge(
+        args: &BuildL2BlockArgs<'_>,
+        policy: &BlockPolicy,
+        merge: MergePolicy,
+    ) -> Result<Self, BlockError> {
+        if !policy.allow_zero_proposer_pubkey && *args.proposer_pubkey.as_bytes() == [0u8; 48] {
+            return Err(BlockError::ZeroProposerPubkey);
+        }
+        if !policy.allow_zero_network_id && *args.network_id.as_bytes() == [0u8; 32] {
+            return Err(BlockError::ZeroNetworkId);
+        }
+        let mut emissions: Vec<Emission> = Vec::new();
+        if !policy.skip_consensus_emissions {
+            // Validate config with respect to the number of attesters
+            args.cfg
+                .validate_for_attesters(args.attester_pubkeys.len())?;
+
+            // Build consensus emission records then convert to Emission
+            let specs = definitions::BUILD_CONSENSUS_EMISSIONS_SPEC(
+                args.proposer_pubkey.into(),
+                args.attester_pubkeys,
+                args.cfg.proposer_reward_share,
+                args.cfg.attester_reward_share,
+            )?;
+            emissions.extend(specs.into_iter().map(Emission::from));
+        }
+        let consensus_len = emissions.len();
+        for extra in &args.extra_emissions {
+            match policy.zero_weight_extras {
+                ZeroWeightPolicy::Allow => {}
+                ZeroWeightPolicy::Filter => {
+                    extra.validate(true)?;
+                    if extra.weight == 0 {
+                        continue;
+                    }
+                }
+                ZeroWeightPolicy::Reject => extra.validate(false)?,
+            }
+            if merge == MergePolicy::MergeIntoConsensus
+                && let Some(record) = emissions[..consensus_len]
+                    .iter_mut()
+                    .find(|e| e.pubkey == extra.pubkey)
+            {
+                record.weight = record.weight.checked_add(extra.weight).ok_or(
+                    crate::body::BodyError::WeightOverflow {
+                        pubkey: extra.pubkey,
+                    },
+                )?;
+                continue;
+            }
+            emissions.push(extra.clone());
+        }
 
         let body = L2BlockBody {
             data: args.data.clone(),
             emissions,
         };
-        let body_root = body.calculate_root();
+        check_policy(&body, policy)?;
+        let (data_count, emissions_count) = body_counts(&body)?;
+        let body_root = body.calculate_root_for_version(args.version);
 
         let header = L2BlockHeader {
             version: args.version,
-            network_id: args.network_id,
+            network_id: args.network_id.into(),
             epoch: args.epoch,
-            prev_block_root: args.prev_block_root,
+            prev_block_root: args.prev_block_root.into(),
             body_root,
-            data_count: body.data.len() as u32,
-            emissions_count: body.emissions.len() as u32,
-            proposer_pubkey: args.proposer_pubkey,
+            data_count,
+            emissions_count,
+            proposer_pubkey: args.proposer_pubkey.into(),
+            timestamp: args.timestamp,
+            extra_data: args.extra_data,
         };
+        header.validate_timestamp()?;
+        header.validate_extra_data()?;
 
         Ok(DigL2Block { header, body })
     }
 }
 
-/// Errors that can be returned by `DigL2Block` construction/validation.
-#[derive(Debug, Error)]
-pub enum BlockError {
-    /// Propagate header-level validation errors transparently.
-    #[error(transparent)]
-    Header(#[from] crate::header::HeaderError),
+/// Validates a sequence of blocks as a chain.
+///
+/// Every block must pass `DigL2Block::verify`, and each block must be a valid
+/// successor (`DigL2Block::validate_successor`) of the one before it. Returns
+/// the first failure.
+pub fn validate_chain(blocks: &[DigL2Block]) -> Result<(), BlockError> {
+    for block in blocks {
+        block.verify(None)?;
+    }
+    for pair in blocks.windows(2) {
+        pair[0].validate_successor(&pair[1])?;
+    }
+    Ok(())
+}
 
-    /// Propagate body-level errors transparently (not currently used, reserved for future checks).
-    #[error(transparent)]
-    Body(#[from] crate::body::BodyError),
+/// Verifies a header against its block root without the body, given the two
+/// body subroots (e.g. served alongside a data or emission inclusion proof).
+///
+/// Checks that `COMPUTE_BODY_ROOT_FOR_VERSION(header.version, data_root,
+/// emissions_root)` equals `header.body_root` (`BodyRootMismatch`) and that
+/// the header and that body root compose to `block_root`
+/// (`BlockRootMismatch`). For chunked-data versions `data_root` is the chunked
+/// root, as in `RootBreakdown::data_root`.
+pub fn verify_partial_block(
+    header: &L2BlockHeader,
+    data_root: &definitions::Hash32,
+    emissions_root: &definitions::Hash32,
+    block_root: &definitions::Hash32,
+) -> Result<(), BlockError> {
+    let body_root =
+        definitions::COMPUTE_BODY_ROOT_FOR_VERSION(header.version, data_root, emissions_root);
+    if !definitions::ct_eq_hash(&header.body_root, &body_root) {
+        return Err(BlockError::BodyRootMismatch {
+            header_body_root: header.body_root,
+            calculated: body_root,
+        });
+    }
+    let calculated = header.block_root_with_body_root(&body_root);
+    if !definitions::ct_eq_hash(block_root, &calculated) {
+        return Err(BlockError::BlockRootMismatch {
+            expected: *block_root,
+            calculated,
+        });
+    }
+    Ok(())
+}
 
-    /// The header's `body_root` does not match the calculated body root.
-    #[error("body_root mismatch: header {header_body_root:?} != calculated {calculated:?}")]
-    BodyRootMismatch {
-        header_body_root: [u8; 32],
-        calculated: [u8; 32],
-    },
+/// Computes `calculate_root` for each block, reusing the leaf and Merkle level
+/// buffers across the whole batch. Equal element-wise to calling
+/// `calculate_root` on every block.
+pub fn calculate_roots(blocks: &[DigL2Block]) -> Vec<definitions::Hash32> {
+    let mut leaves = Vec::new();
+    let mut scratch = Vec::new();
+    blocks
+        .iter()
+        .map(|block| {
+            let header_root = block.header.calculate_root();
+            let body_root = block.body.calculate_root_for_version_with(
+                &Sha256Hasher,
+                block.header.version,
+                &mut leaves,
+                &mut scratch,
+            );
+            definitions::COMPUTE_BLOCK_ROOT(&header_root, &body_root)
+        })
+        .collect()
+}
 
-    /// Propagate definition-level errors (e.g., invalid attester share policy).
-    #[error(transparent)]
-    Definitions(#[from] crate::dig_l2_definition::DefinitionError),
+/// Writes each block as one line of compact JSON (NDJSON), e.g. for an
+/// append-only block log readable with `stream_blocks_from_reader`.
+///
+/// Blocks are serialized one at a time straight into `w`; wrap files in a
+/// `std::io::BufWriter`.
+pub fn write_blocks_ndjson<W: Write>(
+    blocks: impl IntoIterator<Item = DigL2Block>,
+    mut w: W,
+) -> std::io::Result<()> {
+    for block in blocks {
+        serde_json::to_writer(&mut w, &block)?;
+        w.write_all(b"\n")?;
+    }
+    w.flush()
+}
 
-    /// Propagate configuration errors.
-    #[error(transparent)]
-    Config(#[from] crate::emission_config::EmissionConfigError),
+/// Reads blocks one at a time from a JSON array of blocks or from
+/// newline-delimited (or otherwise whitespace-separated) JSON blocks.
+///
+/// The format is chosen from the first non-whitespace byte: `[` starts an
+/// array, anything else a stream of values. Only one block is held in memory
+/// at a time. Input is read a byte at a time, so wrap files in a
+/// `std::io::BufReader`. The iterator stops after the first error.
+pub fn stream_blocks_from_reader<R: Read>(reader: R) -> BlockStream<R> {
+    BlockStream {
+        state: StreamState::Start(reader),
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::emission::Emission;
+/// Iterator returned by `stream_blocks_from_reader`.
+pub struct BlockStream<R: Read> {
+    state: StreamState<R>,
+}
 
-    fn make_body() -> L2BlockBody {
-        L2BlockBody {
-            data: vec![1, 2, 3],
-            emissions: vec![Emission {
-                pubkey: [5u8; 48],
-                weight: 10,
-            }],
+enum StreamState<R: Read> {
+    /// Nothing read yet.
+    Start(R),
+    /// Inside a JSON array; `first` until the first element has been read.
+    Array {
+        reader: R,
+        first: bool,
+    },
+    /// A sequence of top-level values.
+    Values(
+        Box<serde_json::StreamDeserializer<'static, serde_json::de::IoRead<Chain1<R>>, DigL2Block>>,
+    ),
+    Done,
+}
+
+/// A reader with one already-consumed byte put back in front.
+type Chain1<R> = std::io::Chain<Cursor<[u8; 1]>, R>;
+
+/// The next non-whitespace byte of `reader`, or `None` at end of input.
+fn next_non_ws<R: Read>(reader: &mut R) -> Result<Option<u8>, serde_json::Error> {
+    let mut byte = [0u8; 1];
+    loop {
+        if reader.read(&mut byte).map_err(serde_json::Error::io)? == 0 {
+            return Ok(None);
+        }
+        if !byte[0].is_ascii_whitespace() {
+            return Ok(Some(byte[0]));
         }
     }
+}
 
-    fn make_header_for_body(body: &L2BlockBody) -> L2BlockHeader {
-        let body_root = body.calculate_root();
-        L2BlockHeader {
-            version: 1,
-            network_id: [0xabu8; 32],
-            epoch: 7,
-            prev_block_root: [0u8; 32],
-            body_root,
-            data_count: body.data.len() as u32,
-            emissions_count: body.emissions.len() as u32,
-            proposer_pubkey: [9u8; 48],
+/// Decodes one block whose first byte `first` was already read from `reader`.
+fn read_block_from<R: Read>(first: u8, reader: &mut R) -> Result<DigL2Block, serde_json::Error> {
+    let mut de = serde_json::Deserializer::from_reader(Cursor::new([first]).chain(reader));
+    DigL2Block::deserialize(&mut de)
+}
+
+impl<R: Read> BlockStream<R> {
+    fn advance(&mut self) -> Result<Option<DigL2Block>, serde_json::Error> {
+        use serde::de::Error as _;
+        loop {
+            match std::mem::replace(&mut self.state, StreamState::Done) {
+                StreamState::Start(mut reader) => match next_non_ws(&mut reader)? {
+                    None => return Ok(None),
+                    Some(b'[') => {
+                        self.state = StreamState::Array {
+                            reader,
+                            first: true,
+                        }
+                    }
+                    Some(b) => {
+                        let de =
+                            serde_json::Deserializer::from_reader(Cursor::new([b]).chain(reader));
+                        self.state = StreamState::Values(Box::new(de.into_iter()));
+                    }
+                },
+                StreamState::Array { mut reader, first } => {
+                    let start = match (next_non_ws(&mut reader)?, first) {
+                        (Some(b']'), _) => return Ok(None),
+                        (Some(b','), false) => next_non_ws(&mut reader)?,
+                        (b, true) => b,
+                        (_, false) => {
+                            return Err(serde_json::Error::custom(
+                                "expected `,` or `]` after block",
+                            ));
+                        }
+                    };
+                    let Some(start) = start else {
+                        return Err(serde_json::Error::custom("unterminated block array"));
+                    };
+                    let block = read_block_from(start, &mut reader)?;
+                    self.state = StreamState::Array {
+                        reader,
+                        first: false,
+                    };
+                    return Ok(Some(block));
+                }
+                StreamState::Values(mut values) => {
+                    let next = values.next().transpose()?;
+                    if next.is_some() {
+                        self.state = StreamState::Values(values);
+                    }
+                    return Ok(next);
+                }
+                StreamState::Done => return Ok(None),
+            }
         }
     }
+}
 
-    #[test]
-    fn block_root_composition_matches_definitions() {
-        let body = make_body();
-        let header = make_header_for_body(&body);
-        let block = DigL2Block::new(header, body, Some(1)).unwrap();
+impl<R: Read> Iterator for BlockStream<R> {
+    type Item = Result<DigL2Block, serde_json::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().transpose()
+    }
+}
+
+/// JSON Schema (draft 2020-12) for the JSON form of `DigL2Block`, for
+/// validating inbound blocks before they reach Rust.
+///
+/// Byte fields are `string`s matching `^0x[0-9a-f]+$` with an exact length for
+/// the fixed-size arrays, and `^0x([0-9a-f]{2})*$` (whole bytes) for `data`;
+/// integers carry their Rust range. `timestamp` and `extra_data` are optional,
+/// as in serde.
+/// The schema describes the snake_case field names (not the camelCase aliases)
+/// and checks shape only; run `verify` for consistency.
+pub fn json_schema() -> String {
+    let hex = |bytes: u64| {
+        serde_json::json!({
+            "type": "string",
+            "pattern": "^0x[0-9a-f]+$",
+            "minLength": 2 + 2 * bytes,
+            "maxLength": 2 + 2 * bytes,
+        })
+    };
+    let uint = |max: u64| serde_json::json!({ "type": "integer", "minimum": 0, "maximum": max });
+    let schema = serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "DigL2Block",
+        "type": "object",
+        "required": ["header", "body"],
+        "properties": {
+            "header": { "$ref": "#/$defs/L2BlockHeader" },
+            "body": { "$ref": "#/$defs/L2BlockBody" },
+        },
+        "$defs": {
+            "Hex32": hex(32),
+            "Hex48": hex(48),
+            "L2BlockHeader": {
+                "type": "object",
+                "required": [
+                    "version", "network_id", "epoch", "prev_block_root", "body_root",
+                    "data_count", "emissions_count", "proposer_pubkey",
+                ],
+                "properties": {
+                    "version": uint(u32::MAX.into()),
+                    "network_id": { "$ref": "#/$defs/Hex32" },
+                    "epoch": uint(u64::MAX),
+                    "prev_block_root": { "$ref": "#/$defs/Hex32" },
+                    "body_root": { "$ref": "#/$defs/Hex32" },
+                    "data_count": uint(u32::MAX.into()),
+                    "emissions_count": uint(u32::MAX.into()),
+                    "proposer_pubkey": { "$ref": "#/$defs/Hex48" },
+                    "timestamp": uint(u64::MAX),
+                    "extra_data": { "$ref": "#/$defs/Hex32" },
+                },
+            },
+            "L2BlockBody": {
+                "type": "object",
+                "required": ["data", "emissions"],
+                "properties": {
+                    "data": { "type": "string", "pattern": "^0x([0-9a-f]{2})*$" },
+                    "emissions": { "type": "array", "items": { "$ref": "#/$defs/Emission" } },
+                },
+            },
+            "Emission": {
+                "type": "object",
+                "required": ["pubkey", "weight"],
+                "properties": {
+                    "pubkey": { "$ref": "#/$defs/Hex48" },
+                    "weight": uint(u64::MAX),
+                },
+            },
+        },
+    });
+    serde_json::to_string_pretty(&schema).expect("schema serializes")
+}
+
+/// Canonical JSON encoding of a block, for content addressing over JSON bytes.
+///
+/// Object keys are sorted lexicographically at every level, there is no
+/// insignificant whitespace, and byte fields are lowercase `0x` hex, so equal
+/// blocks always produce identical bytes. This is independent of the Merkle
+/// root: use `calculate_root` to identify a block by its commitment.
+pub fn to_canonical_json(block: &DigL2Block) -> String {
+    // Serializing these types to a `Value` cannot fail: all map keys are strings.
+    let value = serde_json::to_value(block).expect("block serializes to JSON");
+    let mut out = String::new();
+    crate::canonical_json::write_canonical_json(&value, &mut out);
+    out
+}
+
+/// CBOR encoding of a block (feature `cbor`): its serde form, with byte
+/// fields as CBOR byte strings rather than hex text.
+#[cfg(feature = "cbor")]
+pub fn to_cbor(block: &DigL2Block) -> Vec<u8> {
+    // Every block field has a CBOR encoding with a known length.
+    crate::cbor::to_vec(block).expect("block serializes to CBOR")
+}
+
+/// Decodes `to_cbor` output (feature `cbor`), failing with `Cbor` on malformed
+/// or trailing input. The block is decoded as-is; call `verify` to check it.
+#[cfg(feature = "cbor")]
+pub fn from_cbor(bytes: &[u8]) -> Result<DigL2Block, BlockError> {
+    crate::cbor::from_slice(bytes).map_err(BlockError::Cbor)
+}
+
+/// MessagePack encoding of a block (feature `msgpack`): its serde form, with
+/// byte fields as `bin` items rather than hex text.
+#[cfg(feature = "msgpack")]
+pub fn to_msgpack(block: &DigL2Block) -> Vec<u8> {
+    // Canonical lengths are `u32`, so every field fits a MessagePack length.
+    crate::msgpack::to_vec(block).expect("block serializes to MessagePack")
+}
+
+/// Decodes `to_msgpack` output (feature `msgpack`), failing with `Msgpack` on
+/// malformed or trailing input. The block is decoded as-is; call `verify` to
+/// check it.
+#[cfg(feature = "msgpack")]
+pub fn from_msgpack(bytes: &[u8]) -> Result<DigL2Block, BlockError> {
+    crate::msgpack::from_slice(bytes).map_err(BlockError::Msgpack)
+}
+
+/// Front-to-back reader over canonical bytes; every read fails past the end.
+struct CanonicalReader<'a>(&'a [u8]);
+
+impl<'a> CanonicalReader<'a> {
+    fn take_slice(&mut self, len: usize) -> Option<&'a [u8]> {
+        let (head, rest) = self.0.split_at_checked(len)?;
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take_slice(N).map(|s| s.try_into().unwrap())
+    }
+
+    fn take_u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn take_u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn take_header(&mut self) -> Option<L2BlockHeader> {
+        Some(L2BlockHeader {
+            version: self.take_u32()?,
+            network_id: self.take()?,
+            epoch: self.take_u64()?,
+            prev_block_root: self.take()?,
+            body_root: self.take()?,
+            data_count: self.take_u32()?,
+            emissions_count: self.take_u32()?,
+            proposer_pubkey: self.take()?,
+            timestamp: self.take_u64()?,
+            extra_data: self.take()?,
+        })
+    }
+}
+
+/// Decodes `DigL2Block` JSON like its `Deserialize` impl, failing as soon as
+/// the body holds more than `max_data` data bytes or `max_emissions` emissions.
+#[derive(Clone, Copy)]
+struct BoundedBlockSeed {
+    max_data: usize,
+    max_emissions: usize,
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for BoundedBlockSeed {
+    type Value = DigL2Block;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<DigL2Block, D::Error> {
+        d.deserialize_struct("DigL2Block", &["header", "body"], self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for BoundedBlockSeed {
+    type Value = DigL2Block;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("struct DigL2Block")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<DigL2Block, A::Error> {
+        use serde::de::Error as _;
+        let (mut header, mut body) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "header" if header.is_some() => return Err(A::Error::duplicate_field("header")),
+                "header" => header = Some(map.next_value::<L2BlockHeader>()?),
+                "body" if body.is_some() => return Err(A::Error::duplicate_field("body")),
+                "body" => body = Some(map.next_value_seed(BoundedBodySeed(self))?),
+                _ => {
+                    map.next_value::<serde::de::IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(DigL2Block {
+            header: header.ok_or_else(|| A::Error::missing_field("header"))?,
+            body: body.ok_or_else(|| A::Error::missing_field("body"))?,
+        })
+    }
+}
+
+/// The `L2BlockBody` half of `BoundedBlockSeed`.
+#[derive(Clone, Copy)]
+struct BoundedBodySeed(BoundedBlockSeed);
+
+impl<'de> serde::de::DeserializeSeed<'de> for BoundedBodySeed {
+    type Value = L2BlockBody;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<L2BlockBody, D::Error> {
+        d.deserialize_struct("L2BlockBody", &["data", "emissions"], self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for BoundedBodySeed {
+    type Value = L2BlockBody;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("struct L2BlockBody")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<L2BlockBody, A::Error> {
+        use serde::de::Error as _;
+        let (mut data, mut emissions) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "data" if data.is_some() => return Err(A::Error::duplicate_field("data")),
+                "data" => data = Some(map.next_value_seed(BoundedData(self.0.max_data))?),
+                "emissions" if emissions.is_some() => {
+                    return Err(A::Error::duplicate_field("emissions"));
+                }
+                "emissions" => {
+                    emissions = Some(map.next_value_seed(BoundedEmissions(self.0.max_emissions))?)
+                }
+                _ => {
+                    map.next_value::<serde::de::IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(L2BlockBody {
+            data: data.ok_or_else(|| A::Error::missing_field("data"))?,
+            emissions: emissions.ok_or_else(|| A::Error::missing_field("emissions"))?,
+        })
+    }
+}
+
+/// `0x` hex body data of at most `.0` bytes, checked before decoding.
+struct BoundedData(usize);
+
+impl<'de> serde::de::DeserializeSeed<'de> for BoundedData {
+    type Value = Vec<u8>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<Vec<u8>, D::Error> {
+        d.deserialize_str(self)
+    }
+}
+
+impl serde::de::Visitor<'_> for BoundedData {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "0x hex data of at most {} bytes", self.0)
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Vec<u8>, E> {
+        if s.len().saturating_sub(2) / 2 > self.0 {
+            return Err(E::custom(format!(
+                "body data exceeds declared data_count {}",
+                self.0
+            )));
+        }
+        crate::serde_hex::decode_hex_prefixed(s).map_err(|e| E::custom(e.to_string()))
+    }
+}
+
+/// A list of at most `.0` emissions; fails at the first one past the bound.
+struct BoundedEmissions(usize);
+
+impl<'de> serde::de::DeserializeSeed<'de> for BoundedEmissions {
+    type Value = Vec<Emission>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, d: D) -> Result<Vec<Emission>, D::Error> {
+        d.deserialize_seq(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for BoundedEmissions {
+    type Value = Vec<Emission>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "at most {} emissions", self.0)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<Vec<Emission>, A::Error> {
+        let mut out = Vec::new();
+        while let Some(e) = seq.next_element::<Emission>()? {
+            if out.len() == self.0 {
+                return Err(serde::de::Error::custom(format!(
+                    "body emissions exceed declared emissions_count {}",
+                    self.0
+                )));
+            }
+            out.push(e);
+        }
+        Ok(out)
+    }
+}
+
+/// Header/body consistency checks shared by `new` and `verify`.
+fn check_header_body(
+    header: &L2BlockHeader,
+    body: &L2BlockBody,
+    expected_version: Option<u32>,
+) -> Result<(), BlockError> {
+    if let Some(v) = expected_version {
+        header.validate_version(v)?;
+    }
+    header.validate_timestamp()?;
+    header.validate_extra_data()?;
+    // Compare roots first so that a mutated body triggers BodyRootMismatch
+    // which is typically the more informative error than counts mismatch.
+    check_body_root(header, body)?;
+    // Then validate counts for completeness.
+    header.validate_counts(body.data.len(), body.emissions.len())?;
+    Ok(())
+}
+
+/// `BodyRootMismatch` unless `header.body_root` commits to `body` under the
+/// rules of `header.version`; compared in constant time.
+fn check_body_root(header: &L2BlockHeader, body: &L2BlockBody) -> Result<(), BlockError> {
+    let calculated = body.calculate_root_for_version(header.version);
+    if definitions::ct_eq_hash(&header.body_root, &calculated) {
+        Ok(())
+    } else {
+        Err(BlockError::BodyRootMismatch {
+            header_body_root: header.body_root,
+            calculated,
+        })
+    }
+}
+
+/// The body's `(data_count, emissions_count)` as header `u32` counts.
+fn body_counts(body: &L2BlockBody) -> Result<(u32, u32), BlockError> {
+    Ok((
+        count_u32("data_count", body.data.len())?,
+        count_u32("emissions_count", body.emissions.len())?,
+    ))
+}
+
+/// Converts a body length to a header count, rejecting lengths that would truncate.
+fn count_u32(field: &'static str, len: usize) -> Result<u32, BlockError> {
+    u32::try_from(len).map_err(|_| BlockError::CountOverflow { field, len })
+}
+
+/// Optional body checks governed by `BlockPolicy`.
+fn check_policy(body: &L2BlockBody, policy: &BlockPolicy) -> Result<(), BlockError> {
+    if !policy.allow_duplicate_pubkeys
+        && let Some(pubkey) = body.find_duplicate_emission_pubkey()
+    {
+        return Err(BlockError::DuplicateEmissionPubkey { pubkey });
+    }
+    Ok(())
+}
+
+/// Errors that can be returned by `DigL2Block` construction/validation.
+#[derive(Debug, Error)]
+pub enum BlockError {
+    /// Propagate header-level validation errors transparently.
+    #[error(transparent)]
+    Header(#[from] crate::header::HeaderError),
+
+    /// Propagate emission-level validation errors transparently.
+    #[error(transparent)]
+    Emission(#[from] crate::emission::EmissionError),
+
+    /// A body-level error, e.g. `BodyError::WeightOverflow` when merging an
+    /// extra emission into a consensus one, or a per-pubkey weight total in
+    /// `validate_all`, overflows `u64`.
+    #[error(transparent)]
+    Body(#[from] crate::body::BodyError),
+
+    /// The header's `body_root` does not match the calculated body root.
+    #[error("body_root mismatch: header {header_body_root:?} != calculated {calculated:?}")]
+    BodyRootMismatch {
+        header_body_root: [u8; 32],
+        calculated: [u8; 32],
+    },
+
+    /// The header and body root do not compose to the expected block root.
+    #[error("block_root mismatch: expected {expected:?} != calculated {calculated:?}")]
+    BlockRootMismatch {
+        expected: [u8; 32],
+        calculated: [u8; 32],
+    },
+
+    /// The proposer pubkey is all zeros (see `BlockPolicy::allow_zero_proposer_pubkey`).
+    #[error("proposer_pubkey is all zeros")]
+    ZeroProposerPubkey,
+
+    /// The network ID is all zeros (see `BlockPolicy::allow_zero_network_id`).
+    #[error("network_id is all zeros")]
+    ZeroNetworkId,
+
+    /// A declared header count exceeds the `BlockLimits` in force.
+    #[error("{field} {count} exceeds limit {max}")]
+    LimitExceeded {
+        field: &'static str,
+        count: u32,
+        max: u32,
+    },
+
+    /// The input is not valid block JSON.
+    #[error("invalid block JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The input is not a valid CBOR-encoded block.
+    #[cfg(feature = "cbor")]
+    #[error("invalid CBOR block: {0}")]
+    Cbor(crate::cbor::FormatError),
+
+    /// The input is not a valid MessagePack-encoded block.
+    #[cfg(feature = "msgpack")]
+    #[error("invalid MessagePack block: {0}")]
+    Msgpack(crate::msgpack::FormatError),
+
+    /// Canonical bytes do not start with `CANONICAL_MAGIC`.
+    #[error("bad canonical block magic")]
+    BadMagic,
+
+    /// Canonical bytes use a format version this build cannot decode.
+    #[error("unsupported canonical format version {version}")]
+    UnsupportedFormatVersion { version: u8 },
+
+    /// Canonical bytes are truncated or have trailing data.
+    #[error("invalid canonical block length: {len} bytes")]
+    InvalidCanonicalLength { len: usize },
+
+    /// The mandatory part of a block does not fit `pack_to_budget`'s budget.
+    #[error("block needs {size} bytes, over the budget of {max}")]
+    OverBudget { size: usize, max: usize },
+
+    /// A body length does not fit the header's `u32` count field.
+    #[error("{field} overflow: body length {len} exceeds u32::MAX")]
+    CountOverflow { field: &'static str, len: usize },
+
+    /// A required builder input was not provided.
+    #[error("missing required field: {field}")]
+    MissingField { field: &'static str },
+
+    /// More than one emission pays the same pubkey.
+    #[error("duplicate emission pubkey 0x{}", hex::encode(.pubkey))]
+    DuplicateEmissionPubkey { pubkey: [u8; 48] },
+
+    /// A successor block belongs to a different network.
+    #[error("network_id mismatch: parent {parent:?} != child {child:?}")]
+    NetworkIdMismatch { parent: [u8; 32], child: [u8; 32] },
+
+    /// A successor block does not point at its parent's root.
+    #[error("prev_block_root mismatch: expected {expected:?}, found {found:?}")]
+    PrevBlockRootMismatch { expected: [u8; 32], found: [u8; 32] },
+
+    /// A successor block's epoch is not the parent epoch plus one.
+    #[error("epoch mismatch: expected {expected}, found {found}")]
+    EpochNotSuccessor { expected: u64, found: u64 },
+
+    /// The parent epoch is `u64::MAX`, so no successor epoch exists.
+    #[error("epoch overflow: parent epoch is u64::MAX")]
+    EpochOverflow,
+
+    /// An emission required by the consensus config is absent from the body.
+    #[error("missing consensus emission 0x{} with weight {weight}", hex::encode(.pubkey))]
+    MissingConsensusEmission { pubkey: [u8; 48], weight: u64 },
+
+    /// A successor block's timestamp is earlier than its parent's.
+    #[error("timestamp regression: parent {parent} > child {child}")]
+    TimestampRegression { parent: u64, child: u64 },
+
+    /// Summing emission weights for a role overflowed `u64`.
+    #[error("emission weight total for {role} overflows u64")]
+    WeightOverflow { role: &'static str },
+
+    /// Propagate definition-level errors (e.g., invalid attester share policy).
+    #[error(transparent)]
+    Definitions(#[from] crate::dig_l2_definition::DefinitionError),
+
+    /// Propagate configuration errors.
+    #[error(transparent)]
+    Config(#[from] crate::emission_config::EmissionConfigError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emission::Emission;
+
+    fn make_body() -> L2BlockBody {
+        L2BlockBody {
+            data: vec![1, 2, 3],
+            emissions: vec![Emission {
+                pubkey: [5u8; 48],
+                weight: 10,
+            }],
+        }
+    }
+
+    fn make_header_for_body(body: &L2BlockBody) -> L2BlockHeader {
+        let body_root = body.calculate_root();
+        L2BlockHeader {
+            version: 1,
+            network_id: [0xabu8; 32],
+            epoch: 7,
+            prev_block_root: [0u8; 32],
+            body_root,
+            data_count: body.data.len() as u32,
+            emissions_count: body.emissions.len() as u32,
+            proposer_pubkey: [9u8; 48],
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        }
+    }
+
+    #[test]
+    fn block_root_composition_matches_definitions() {
+        let body = make_body();
+        let header = make_header_for_body(&body);
+        let block = DigL2Block::new(header, body, Some(1)).unwrap();
         let h_root = block.header.calculate_root();
         let b_root = block.body.calculate_root();
         let expect = definitions::COMPUTE_BLOCK_ROOT(&h_root, &b_root);
         assert_eq!(block.calculate_root(), expect);
     }
 
+    #[test]
+    fn calculate_root_hex_parses_back_to_root() {
+        let body = make_body();
+        let header = make_header_for_body(&body);
+        let block = DigL2Block::new(header, body, Some(1)).unwrap();
+        let parsed: crate::types::HashHex = block.calculate_root_hex().parse().unwrap();
+        assert_eq!(parsed.0, block.calculate_root());
+        assert_eq!(
+            block.calculate_root_hex(),
+            format!("0x{}", hex::encode(block.calculate_root()))
+        );
+    }
+
     #[test]
     fn new_rejects_mismatched_counts() {
         let body = make_body();
@@ -221,14 +1782,16 @@ mod tests {
         let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 90);
         let build_block_args = BuildL2BlockArgs {
             version: 1,
-            network_id: [0xabu8; 32],
+            network_id: [0xabu8; 32].into(),
             epoch: 7,
-            prev_block_root: [0u8; 32],
-            proposer_pubkey: [9u8; 48],
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
             data,
             extra_emissions: extra.clone(),
             attester_pubkeys: &attesters,
             cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
         };
         let block = DigL2Block::build(&build_block_args).unwrap();
 
@@ -247,37 +1810,1789 @@ mod tests {
         let s = serde_json::to_string(&block).unwrap();
         let back: DigL2Block = serde_json::from_str(&s).unwrap();
         assert_eq!(block, back);
+        crate::testing::assert_root_stable(&block);
     }
 
     #[test]
-    fn build_block_zero_attesters_policy() {
-        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 0);
-        let bb_args = BuildL2BlockArgs {
+    fn reward_summary_totals_by_role() {
+        let attesters = vec![[0x11u8; 48], [0x22u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 80);
+        let args = BuildL2BlockArgs {
             version: 1,
-            network_id: [0xabu8; 32],
+            network_id: [0xabu8; 32].into(),
             epoch: 7,
-            prev_block_root: [0u8; 32],
-            proposer_pubkey: [9u8; 48],
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
             data: vec![],
-            extra_emissions: vec![],
-            attester_pubkeys: &[],
+            extra_emissions: vec![
+                Emission {
+                    pubkey: [0x33u8; 48],
+                    weight: 5,
+                },
+                Emission {
+                    pubkey: [0x44u8; 48],
+                    weight: 6,
+                },
+            ],
+            attester_pubkeys: &attesters,
             cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
         };
-        let b = DigL2Block::build(&bb_args).unwrap();
-        assert_eq!(b.body.emissions.len(), 1); // proposer only
+        let block = DigL2Block::build(&args).unwrap();
+        let summary = block.reward_summary(&attesters).unwrap();
+        assert_eq!(
+            summary,
+            RewardSummary {
+                proposer: 12,
+                attesters: 80,
+                extras: 11,
+            }
+        );
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["attesters"], 80);
+    }
 
-        // Now invalid: non-zero attester share but no attesters
-        let cfg_bad = crate::emission_config::ConsensusEmissionConfig::new(12, 1);
-        let bb_e_args = BuildL2BlockArgs {
+    #[test]
+    fn reward_summary_rejects_overflow() {
+        let mut body = make_body();
+        body.emissions = vec![
+            Emission {
+                pubkey: [1u8; 48],
+                weight: u64::MAX,
+            },
+            Emission {
+                pubkey: [2u8; 48],
+                weight: 1,
+            },
+        ];
+        let header = make_header_for_body(&body);
+        let block = DigL2Block { header, body };
+        match block.reward_summary(&[]).unwrap_err() {
+            BlockError::WeightOverflow { role } => assert_eq!(role, "extras"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    fn dup_args<'a>(
+        attesters: &'a [[u8; 48]],
+        cfg: &'a crate::emission_config::ConsensusEmissionConfig,
+        extra_pubkey: [u8; 48],
+    ) -> BuildL2BlockArgs<'a> {
+        BuildL2BlockArgs {
             version: 1,
-            network_id: [0u8; 32],
+            network_id: [0xabu8; 32].into(),
             epoch: 7,
-            prev_block_root: [0u8; 32],
-            proposer_pubkey: [1u8; 48],
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![1],
+            extra_emissions: vec![Emission {
+                pubkey: extra_pubkey,
+                weight: 3,
+            }],
+            attester_pubkeys: attesters,
+            cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        }
+    }
+
+    #[test]
+    fn build_rejects_duplicate_emission_pubkey() {
+        let attesters = [[0x11u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+
+        // Extra emission topping up the proposer duplicates its pubkey.
+        let err = DigL2Block::build(&dup_args(&attesters, &cfg, [9u8; 48])).unwrap_err();
+        match err {
+            BlockError::DuplicateEmissionPubkey { pubkey } => assert_eq!(pubkey, [9u8; 48]),
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        // Distinct pubkeys build fine.
+        DigL2Block::build(&dup_args(&attesters, &cfg, [0x33u8; 48])).unwrap();
+    }
+
+    #[test]
+    fn policy_allows_duplicate_emission_pubkey() {
+        let attesters = [[0x11u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let policy = BlockPolicy {
+            allow_duplicate_pubkeys: true,
+            ..BlockPolicy::default()
+        };
+        let block =
+            DigL2Block::build_with_policy(&dup_args(&attesters, &cfg, [9u8; 48]), &policy).unwrap();
+        assert!(block.verify_with_policy(Some(1), &policy).is_ok());
+        match block.verify(Some(1)).unwrap_err() {
+            BlockError::DuplicateEmissionPubkey { .. } => {}
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn zero_proposer_pubkey_is_rejected_unless_allowed() {
+        let attesters = [[0x11u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let zero = BuildL2BlockArgs {
+            proposer_pubkey: [0u8; 48].into(),
+            ..dup_args(&attesters, &cfg, [0x33u8; 48])
+        };
+        assert!(matches!(
+            DigL2Block::build(&zero),
+            Err(BlockError::ZeroProposerPubkey)
+        ));
+        let lenient = BlockPolicy {
+            allow_zero_proposer_pubkey: true,
+            ..BlockPolicy::default()
+        };
+        let block = DigL2Block::build_with_policy(&zero, &lenient).unwrap();
+        assert_eq!(block.header.proposer_pubkey, [0u8; 48]);
+
+        // A non-zero key builds under either setting.
+        let keyed = dup_args(&attesters, &cfg, [0x33u8; 48]);
+        assert!(DigL2Block::build(&keyed).is_ok());
+        assert!(DigL2Block::build_with_policy(&keyed, &lenient).is_ok());
+    }
+
+    #[test]
+    fn zero_network_id_is_rejected_unless_allowed() {
+        let attesters = [[0x11u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let zero = BuildL2BlockArgs {
+            network_id: [0u8; 32].into(),
+            ..dup_args(&attesters, &cfg, [0x33u8; 48])
+        };
+        assert!(matches!(
+            DigL2Block::build(&zero),
+            Err(BlockError::ZeroNetworkId)
+        ));
+        let lenient = BlockPolicy {
+            allow_zero_network_id: true,
+            ..BlockPolicy::default()
+        };
+        let block = DigL2Block::build_with_policy(&zero, &lenient).unwrap();
+        assert_eq!(block.header.network_id, [0u8; 32]);
+
+        let named = dup_args(&attesters, &cfg, [0x33u8; 48]);
+        assert!(DigL2Block::build(&named).is_ok());
+        assert!(DigL2Block::build_with_policy(&named, &lenient).is_ok());
+    }
+
+    #[test]
+    fn from_json_checked_enforces_declared_limits() {
+        let block = sample_block();
+        let limits = BlockLimits {
+            max_data_count: 16,
+            max_emissions_count: 4,
+        };
+        let json = serde_json::to_string(&block).unwrap();
+        assert_eq!(
+            DigL2Block::from_json_checked(&json, &limits).unwrap(),
+            block
+        );
+
+        // Huge declared counts are rejected from the header alone; the tiny
+        // body never gets decoded or hashed.
+        let mut crafted: serde_json::Value = serde_json::to_value(&block).unwrap();
+        crafted["header"]["data_count"] = serde_json::Value::from(u32::MAX);
+        crafted["body"] = serde_json::json!({ "data": "0x", "emissions": [] });
+        match DigL2Block::from_json_checked(&crafted.to_string(), &limits).unwrap_err() {
+            BlockError::LimitExceeded { field, count, max } => {
+                assert_eq!((field, count, max), ("data_count", u32::MAX, 16));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        // Within limits but declaring more than the body holds: the counts are
+        // compared before any root is computed.
+        crafted["header"]["data_count"] = serde_json::Value::from(3);
+        assert!(matches!(
+            DigL2Block::from_json_checked(&crafted.to_string(), &limits),
+            Err(BlockError::Header(
+                crate::header::HeaderError::CountMismatch {
+                    field: "data_count",
+                    ..
+                }
+            ))
+        ));
+
+        // Small declared counts cannot smuggle in a larger body: decoding
+        // stops at the first byte or emission past the declaration.
+        let mut oversized: serde_json::Value = serde_json::to_value(&block).unwrap();
+        oversized["body"]["data"] = serde_json::Value::from(format!("0x{}", "ab".repeat(17)));
+        let err = DigL2Block::from_json_checked(&oversized.to_string(), &limits).unwrap_err();
+        assert!(matches!(err, BlockError::Json(_)), "{err:?}");
+        assert!(err.to_string().contains("exceeds declared data_count"));
+
+        let mut oversized: serde_json::Value = serde_json::to_value(&block).unwrap();
+        let extra = oversized["body"]["emissions"][0].clone();
+        oversized["body"]["emissions"]
+            .as_array_mut()
+            .unwrap()
+            .push(extra);
+        let err = DigL2Block::from_json_checked(&oversized.to_string(), &limits).unwrap_err();
+        assert!(
+            err.to_string().contains("exceed declared emissions_count"),
+            "{err}"
+        );
+        assert!(matches!(
+            DigL2Block::from_json_checked("{", &limits),
+            Err(BlockError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn block_summary_carries_root_and_serializes_hex() {
+        let block = sample_block();
+        let summary = BlockSummary::from(&block);
+        assert_eq!(summary.block_root, block.calculate_root());
+        assert_eq!(summary.header, block.header);
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(
+            json["block_root"].as_str().unwrap(),
+            block.calculate_root_hex()
+        );
+        assert!(json.get("body").is_none());
+        let back: BlockSummary = serde_json::from_value(json).unwrap();
+        assert_eq!(back, summary);
+    }
+
+    #[test]
+    fn announcement_matches_only_the_announced_block() {
+        let block = sample_block();
+        let ann = block.announcement();
+        assert_eq!(ann.block_root, block.calculate_root());
+        assert_eq!(ann.epoch, 7);
+        assert!(ann.matches(&block));
+
+        let json = serde_json::to_value(&ann).unwrap();
+        assert_eq!(
+            json["block_root"].as_str().unwrap(),
+            block.calculate_root_hex()
+        );
+        let back: BlockAnnouncement = serde_json::from_value(json).unwrap();
+        assert_eq!(back, ann);
+
+        let mut tampered = block.clone();
+        tampered.body.data.push(9);
+        assert!(!ann.matches(&tampered));
+
+        // An honest root with a misstated field is not a match either.
+        let lying = BlockAnnouncement {
+            epoch: 8,
+            ..ann.clone()
+        };
+        assert!(!lying.matches(&block));
+    }
+
+    #[test]
+    fn pack_to_budget_keeps_consensus_and_a_prefix_of_extras() {
+        let attesters = [[0x11u8; 48], [0x12u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let args = || {
+            let mut args = dup_args(&attesters, &cfg, [0x20u8; 48]);
+            args.extra_emissions
+                .extend((0x21..0x25u8).map(|p| Emission {
+                    pubkey: [p; 48],
+                    weight: 1,
+                }));
+            args
+        };
+        let full = DigL2Block::build(&args()).unwrap();
+        assert_eq!(full.body.emissions.len(), 3 + 5);
+
+        // Unbounded and exact budgets keep everything.
+        for max in [usize::MAX, full.encoded_size()] {
+            assert_eq!(DigL2Block::pack_to_budget(args(), max).unwrap(), full);
+        }
+
+        // One byte short drops the last extra; tighter budgets drop more, in order.
+        for dropped in 1..=5 {
+            let max = full.encoded_size() - (dropped - 1) * CANONICAL_EMISSION_LEN - 1;
+            let packed = DigL2Block::pack_to_budget(args(), max).unwrap();
+            assert!(packed.encoded_size() <= max);
+            assert!(packed.verify(Some(1)).is_ok());
+            let kept = 5 - dropped;
+            assert_eq!(packed.body.emissions[..3], full.body.emissions[..3]);
+            assert_eq!(packed.body.emissions[3..], full.body.emissions[3..3 + kept]);
+        }
+
+        // Too tight for even the consensus emissions.
+        let base = full.encoded_size() - 5 * CANONICAL_EMISSION_LEN;
+        assert!(matches!(
+            DigL2Block::pack_to_budget(args(), base - 1),
+            Err(BlockError::OverBudget { size, max }) if size == base && max == base - 1
+        ));
+    }
+
+    #[test]
+    fn blocks_headers_and_bodies_hash_by_value() {
+        use std::collections::{HashMap, HashSet};
+        let a = sample_block();
+        let mut b = a.clone();
+        b.body.data.push(4);
+        b.reseal().unwrap();
+
+        let blocks: HashSet<DigL2Block> = [a.clone(), b.clone(), a.clone()].into_iter().collect();
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks.contains(&a) && blocks.contains(&b));
+
+        let bodies: HashSet<L2BlockBody> = [a.body.clone(), b.body.clone(), a.body.clone()]
+            .into_iter()
+            .collect();
+        assert_eq!(bodies.len(), 2);
+
+        let by_header: HashMap<L2BlockHeader, &DigL2Block> =
+            [(a.header.clone(), &a), (b.header.clone(), &b)]
+                .into_iter()
+                .collect();
+        assert_eq!(by_header.len(), 2);
+        assert_eq!(by_header[&a.header], &a);
+    }
+
+    #[test]
+    fn build_data_only_skips_consensus_emissions() {
+        let attesters = [[0x11u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let args = BuildL2BlockArgs {
+            extra_emissions: vec![],
+            ..dup_args(&attesters, &cfg, [0x33u8; 48])
+        };
+        let block = DigL2Block::build_data_only(&args).unwrap();
+        assert_eq!(block.body, L2BlockBody::data_only(args.data.clone()));
+        assert_eq!(block.header.data_count, 1);
+        assert_eq!(block.header.emissions_count, 0);
+        assert!(block.verify(Some(1)).is_ok());
+
+        let direct = DigL2Block::new(
+            L2BlockHeader {
+                body_root: L2BlockBody::data_only(vec![1]).calculate_root(),
+                ..block.header.clone()
+            },
+            L2BlockBody::data_only(vec![1]),
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(direct.calculate_root(), block.calculate_root());
+
+        // The default policy still builds the consensus emissions.
+        assert_eq!(DigL2Block::build(&args).unwrap().body.emissions.len(), 2);
+    }
+
+    #[test]
+    fn zero_weight_extras_follow_policy() {
+        let attesters = [[0x11u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let mut args = dup_args(&attesters, &cfg, [0x33u8; 48]);
+        args.extra_emissions.push(Emission {
+            pubkey: [0x44u8; 48],
+            weight: 0,
+        });
+        let with = |zero_weight_extras| BlockPolicy {
+            zero_weight_extras,
+            ..BlockPolicy::default()
+        };
+
+        let kept = DigL2Block::build(&args).unwrap();
+        assert_eq!(kept.body.emissions.len(), 4);
+
+        let filtered =
+            DigL2Block::build_with_policy(&args, &with(ZeroWeightPolicy::Filter)).unwrap();
+        assert_eq!(filtered.body.emissions.len(), 3);
+        assert!(filtered.body.emissions.iter().all(|e| e.weight > 0));
+        assert!(filtered.verify(Some(1)).is_ok());
+
+        match DigL2Block::build_with_policy(&args, &with(ZeroWeightPolicy::Reject)).unwrap_err() {
+            BlockError::Emission(crate::emission::EmissionError::ZeroWeight { pubkey }) => {
+                assert_eq!(pubkey, [0x44u8; 48]);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        // Filtering never hides a junk pubkey.
+        args.extra_emissions.push(Emission {
+            pubkey: [0u8; 48],
+            weight: 0,
+        });
+        assert!(matches!(
+            DigL2Block::build_with_policy(&args, &with(ZeroWeightPolicy::Filter)),
+            Err(BlockError::Emission(
+                crate::emission::EmissionError::ZeroPubkey
+            ))
+        ));
+    }
+
+    #[test]
+    fn root_breakdown_matches_calculate_calls() {
+        for version in [1, definitions::CHUNKED_DATA_VERSION] {
+            let mut block = sample_block();
+            block.header.version = version;
+            block.reseal().unwrap();
+            let b = block.root_breakdown();
+            let mode = definitions::DataRootMode::for_version(version);
+            assert_eq!(b.data_root, block.body.calculate_data_root_with_mode(mode));
+            assert_eq!(
+                b.emissions_root,
+                block
+                    .body
+                    .calculate_emissions_root_for_version(block.header.version)
+            );
+            assert_eq!(b.body_root, block.body.calculate_root_for_version(version));
+            assert_eq!(b.body_root, block.header.body_root);
+            assert_eq!(b.header_root, block.header.calculate_root());
+            assert_eq!(b.block_root, block.calculate_root());
+            assert_eq!(
+                definitions::COMPUTE_BLOCK_ROOT(&b.header_root, &b.body_root),
+                b.block_root
+            );
+        }
+    }
+
+    #[test]
+    fn merge_policy_folds_extras_into_consensus_records() {
+        let attesters = [[0x11u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        // Top up the proposer ([9; 48]) by 3 and pay one new recipient.
+        let mut args = dup_args(&attesters, &cfg, [9u8; 48]);
+        args.extra_emissions.push(Emission {
+            pubkey: [0x33u8; 48],
+            weight: 4,
+        });
+
+        // Appending duplicates the proposer record.
+        let lenient = BlockPolicy {
+            allow_duplicate_pubkeys: true,
+            ..BlockPolicy::default()
+        };
+        let appended = DigL2Block::build_with_merge(&args, &lenient, MergePolicy::Append).unwrap();
+        assert_eq!(appended.body.emissions.len(), 4);
+        assert!(matches!(
+            DigL2Block::build_with_merge(&args, &BlockPolicy::default(), MergePolicy::Append),
+            Err(BlockError::DuplicateEmissionPubkey { .. })
+        ));
+
+        // Merging keeps one proposer record and passes the strict policy.
+        let merged = DigL2Block::build_with_merge(
+            &args,
+            &BlockPolicy::default(),
+            MergePolicy::MergeIntoConsensus,
+        )
+        .unwrap();
+        assert_eq!(
+            merged.body.emissions,
+            vec![
+                Emission {
+                    pubkey: [9u8; 48],
+                    weight: 15,
+                },
+                Emission {
+                    pubkey: [0x11u8; 48],
+                    weight: 88,
+                },
+                Emission {
+                    pubkey: [0x33u8; 48],
+                    weight: 4,
+                },
+            ]
+        );
+        assert!(merged.verify(Some(1)).is_ok());
+        assert_eq!(
+            merged.body.emission_totals().unwrap(),
+            appended.body.emission_totals().unwrap()
+        );
+
+        args.extra_emissions[0].weight = u64::MAX;
+        assert!(matches!(
+            DigL2Block::build_with_merge(
+                &args,
+                &BlockPolicy::default(),
+                MergePolicy::MergeIntoConsensus
+            ),
+            Err(BlockError::Body(
+                crate::body::BodyError::WeightOverflow { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn validate_all_reports_every_failure() {
+        let mut block = sample_block();
+        assert!(block.validate_all(Some(1)).is_ok());
+
+        block.header.timestamp = 5;
+        block.header.emissions_count += 1;
+        block.body.data.push(0xff);
+        let dup = Emission {
+            pubkey: block.body.emissions[0].pubkey,
+            weight: u64::MAX,
+        };
+        block.body.emissions.push(dup.clone());
+
+        // `verify` stops at the first problem.
+        assert!(matches!(
+            block.verify(Some(2)),
+            Err(BlockError::Header(
+                crate::header::HeaderError::VersionMismatch { .. }
+            ))
+        ));
+        let errors = block.validate_all(Some(2)).unwrap_err();
+        assert_eq!(errors.len(), 6, "{errors:?}");
+        assert!(matches!(
+            errors[0],
+            BlockError::Header(crate::header::HeaderError::VersionMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            errors[1],
+            BlockError::Header(crate::header::HeaderError::UncommittedTimestamp { version: 1 })
+        ));
+        assert!(matches!(errors[2], BlockError::BodyRootMismatch { .. }));
+        assert!(matches!(
+            errors[3],
+            BlockError::Header(crate::header::HeaderError::CountMismatch {
+                field: "data_count",
+                ..
+            })
+        ));
+        assert!(matches!(
+            errors[4],
+            BlockError::DuplicateEmissionPubkey { pubkey } if pubkey == dup.pubkey
+        ));
+        assert!(matches!(
+            errors[5],
+            BlockError::Body(crate::body::BodyError::WeightOverflow { .. })
+        ));
+    }
+
+    /// Checks `value` against the subset of JSON Schema `json_schema` uses.
+    fn schema_accepts(
+        schema: &serde_json::Value,
+        node: &serde_json::Value,
+        value: &serde_json::Value,
+    ) -> bool {
+        if let Some(r) = node.get("$ref").and_then(|r| r.as_str()) {
+            let name = r.strip_prefix("#/$defs/").expect("local ref");
+            return schema_accepts(schema, &schema["$defs"][name], value);
+        }
+        let type_ok = match node["type"].as_str().unwrap() {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_u64() || value.is_i64(),
+            other => panic!("unsupported schema type {other}"),
+        };
+        if !type_ok {
+            return false;
+        }
+        if let Some(required) = node.get("required").and_then(|r| r.as_array())
+            && !required
+                .iter()
+                .all(|k| value.get(k.as_str().unwrap()).is_some())
+        {
+            return false;
+        }
+        if let Some(props) = node.get("properties").and_then(|p| p.as_object()) {
+            for (key, sub) in props {
+                if let Some(v) = value.get(key)
+                    && !schema_accepts(schema, sub, v)
+                {
+                    return false;
+                }
+            }
+        }
+        if let Some(items) = node.get("items") {
+            return value
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|v| schema_accepts(schema, items, v));
+        }
+        if let Some(s) = value.as_str() {
+            let len = s.len() as u64;
+            if node
+                .get("minLength")
+                .is_some_and(|m| len < m.as_u64().unwrap())
+                || node
+                    .get("maxLength")
+                    .is_some_and(|m| len > m.as_u64().unwrap())
+            {
+                return false;
+            }
+            let digits_ok = |d: &str| d.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+            let matched = match node.get("pattern").and_then(|p| p.as_str()) {
+                Some("^0x[0-9a-f]+$") => s
+                    .strip_prefix("0x")
+                    .is_some_and(|d| !d.is_empty() && digits_ok(d)),
+                Some("^0x([0-9a-f]{2})*$") => s
+                    .strip_prefix("0x")
+                    .is_some_and(|d| d.len() % 2 == 0 && digits_ok(d)),
+                Some(other) => panic!("unsupported pattern {other}"),
+                None => true,
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if value.is_number() {
+            let Some(n) = value.as_u64() else {
+                return node.get("minimum").is_none();
+            };
+            if node.get("maximum").is_some_and(|m| n > m.as_u64().unwrap()) {
+                return false;
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn json_schema_accepts_valid_blocks_only() {
+        let schema: serde_json::Value = serde_json::from_str(&json_schema()).unwrap();
+        let valid = serde_json::to_value(sample_block()).unwrap();
+        assert!(schema_accepts(&schema, &schema, &valid));
+
+        let mut optional = valid.clone();
+        let header = optional["header"].as_object_mut().unwrap();
+        header.remove("timestamp");
+        header.remove("extra_data");
+        assert!(schema_accepts(&schema, &schema, &optional));
+
+        type Mutation = fn(&mut serde_json::Value);
+        let invalid: [(&str, Mutation); 7] = [
+            ("missing body", |v| {
+                v.as_object_mut().unwrap().remove("body");
+            }),
+            ("short network_id", |v| {
+                v["header"]["network_id"] = "0xab".into()
+            }),
+            ("uppercase hex", |v| {
+                let upper = v["header"]["body_root"].as_str().unwrap().to_uppercase();
+                v["header"]["body_root"] = upper.replacen("0X", "0x", 1).into();
+            }),
+            ("negative weight", |v| {
+                v["body"]["emissions"][0]["weight"] = (-1).into()
+            }),
+            ("data_count over u32", |v| {
+                v["header"]["data_count"] = (1u64 << 32).into()
+            }),
+            ("epoch as string", |v| v["header"]["epoch"] = "7".into()),
+            ("odd-length data", |v| v["body"]["data"] = "0xabc".into()),
+        ];
+        for (what, mutate) in invalid {
+            let mut bad = valid.clone();
+            mutate(&mut bad);
+            assert!(!schema_accepts(&schema, &schema, &bad), "{what}");
+        }
+    }
+
+    fn stream_fixture() -> Vec<DigL2Block> {
+        (0..4u8)
+            .map(|i| {
+                let mut block = sample_block();
+                block.header.epoch = u64::from(i);
+                block.body.data.extend(std::iter::repeat_n(i, i as usize));
+                block.reseal().unwrap();
+                block
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stream_blocks_reads_ndjson_and_arrays() {
+        let blocks = stream_fixture();
+        let ndjson: String = blocks
+            .iter()
+            .map(|b| serde_json::to_string(b).unwrap() + "\n")
+            .collect();
+        let array = serde_json::to_string_pretty(&blocks).unwrap();
+
+        for (input, expect) in [
+            (ndjson, &blocks[..]),
+            (array, &blocks[..]),
+            (String::new(), &[][..]),
+            ("  [ ]\n".to_owned(), &[][..]),
+        ] {
+            let read: Vec<DigL2Block> =
+                stream_blocks_from_reader(std::io::BufReader::new(input.as_bytes()))
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+            assert_eq!(read, expect);
+            for (block, original) in read.iter().zip(expect) {
+                assert!(block.verify(None).is_ok());
+                assert_eq!(block.calculate_root(), original.calculate_root());
+            }
+        }
+    }
+
+    #[test]
+    fn ndjson_writer_round_trips_through_stream_reader() {
+        let blocks = stream_fixture();
+        let mut log = Vec::new();
+        write_blocks_ndjson(blocks.clone(), &mut log).unwrap();
+        let text = std::str::from_utf8(&log).unwrap();
+        assert_eq!(text.lines().count(), blocks.len());
+        assert!(text.ends_with('\n'));
+        for (line, block) in text.lines().zip(&blocks) {
+            assert_eq!(line, serde_json::to_string(block).unwrap());
+        }
+
+        let read: Vec<DigL2Block> = stream_blocks_from_reader(&log[..])
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, blocks);
+
+        let mut empty = Vec::new();
+        write_blocks_ndjson(std::iter::empty(), &mut empty).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn stream_blocks_stops_at_malformed_input() {
+        let one = serde_json::to_string(&sample_block()).unwrap();
+        for input in [
+            format!("[{one} {one}]"),
+            format!("[{one},"),
+            format!("{one}\n{{\"header\": 1}}\n{one}"),
+        ] {
+            let mut stream = stream_blocks_from_reader(input.as_bytes());
+            assert!(stream.next().unwrap().is_ok());
+            assert!(stream.next().unwrap().is_err(), "{input}");
+            assert!(stream.next().is_none());
+        }
+    }
+
+    #[test]
+    fn verify_runs_consistency_checks() {
+        let body = make_body();
+        let header = make_header_for_body(&body);
+        let mut block = DigL2Block { header, body };
+        assert!(block.verify(Some(1)).is_ok());
+        block.body.data.push(9);
+        match block.verify(None).unwrap_err() {
+            BlockError::BodyRootMismatch { .. } => {}
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn encoded_size_matches_canonical_bytes() {
+        let mut blocks = vec![DigL2Block {
+            header: make_header_for_body(&L2BlockBody {
+                data: vec![],
+                emissions: vec![],
+            }),
+            body: L2BlockBody {
+                data: vec![],
+                emissions: vec![],
+            },
+        }];
+        let body = make_body();
+        blocks.push(DigL2Block {
+            header: make_header_for_body(&body),
+            body,
+        });
+        let body = L2BlockBody {
+            data: (0..=255).collect(),
+            emissions: (0..5u8)
+                .map(|i| Emission {
+                    pubkey: [i; 48],
+                    weight: u64::from(i) << 40,
+                })
+                .collect(),
+        };
+        blocks.push(DigL2Block {
+            header: make_header_for_body(&body),
+            body,
+        });
+        for block in &blocks {
+            assert_eq!(block.encoded_size(), block.to_canonical_bytes().len());
+            assert_eq!(
+                encoded_size_for(block.body.data.len(), block.body.emissions.len()),
+                block.encoded_size()
+            );
+        }
+        assert_eq!(blocks[0].encoded_size(), 5 + 204 + 8);
+        assert_eq!(blocks[1].encoded_size(), 5 + 204 + 8 + 3 + 56);
+    }
+
+    #[test]
+    fn canonical_bytes_layout() {
+        let body = make_body();
+        let block = DigL2Block {
+            header: make_header_for_body(&body),
+            body,
+        };
+        let bytes = block.to_canonical_bytes();
+        assert_eq!(&bytes[..5], b"DIGB\x01");
+        let header = &bytes[CANONICAL_PREFIX_LEN..];
+        assert_eq!(&header[..4], &1u32.to_le_bytes());
+        assert_eq!(&header[4..36], &[0xabu8; 32]);
+        assert_eq!(&header[44..76], &[0u8; 32]);
+        assert_eq!(&header[76..108], &block.header.body_root);
+        let tail = &header[CANONICAL_HEADER_LEN..];
+        assert_eq!(&tail[..7], &[3, 0, 0, 0, 1, 2, 3]);
+        assert_eq!(&tail[7..11], &1u32.to_le_bytes());
+        assert_eq!(&tail[11..59], &[5u8; 48]);
+        assert_eq!(&tail[59..], &10u64.to_le_bytes());
+    }
+
+    #[test]
+    fn canonical_bytes_round_trip() {
+        let mut block = sample_block();
+        block.body.emissions.push(Emission {
+            pubkey: [6u8; 48],
+            weight: u64::MAX,
+        });
+        block.header.extra_data = [0x42u8; 32];
+        let bytes = block.to_canonical_bytes();
+        assert_eq!(DigL2Block::from_canonical_bytes(&bytes).unwrap(), block);
+
+        let empty = DigL2Block {
+            header: make_header_for_body(&L2BlockBody::data_only(vec![])),
+            body: L2BlockBody::data_only(vec![]),
+        };
+        let bytes = empty.to_canonical_bytes();
+        assert_eq!(DigL2Block::from_canonical_bytes(&bytes).unwrap(), empty);
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn cbor_round_trip_keeps_root_and_beats_json() {
+        let mut block = sample_block();
+        block.body.data = (0..=255).collect();
+        block.body.emissions.extend((6..9u8).map(|i| Emission {
+            pubkey: [i; 48],
+            weight: u64::from(i) << 40,
+        }));
+        block.reseal().unwrap();
+
+        let bytes = to_cbor(&block);
+        let decoded = from_cbor(&bytes).unwrap();
+        assert_eq!(decoded, block);
+        assert_eq!(decoded.calculate_root(), block.calculate_root());
+        decoded.verify(None).unwrap();
+
+        let json = serde_json::to_vec(&block).unwrap();
+        assert!(
+            bytes.len() < json.len(),
+            "{} >= {}",
+            bytes.len(),
+            json.len()
+        );
+        // Byte fields are raw byte strings: the 256 data bytes follow a 3-byte head.
+        let data_head = [0x59, 0x01, 0x00];
+        assert!(bytes.windows(3).any(|w| w == data_head));
+
+        assert!(matches!(
+            from_cbor(&bytes[..bytes.len() - 1]),
+            Err(BlockError::Cbor(crate::cbor::FormatError::UnexpectedEof))
+        ));
+        assert!(matches!(
+            from_cbor(&[bytes.as_slice(), &[0]].concat()),
+            Err(BlockError::Cbor(crate::cbor::FormatError::TrailingBytes {
+                len: 1
+            }))
+        ));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack_round_trip_keeps_root() {
+        let mut block = sample_block();
+        block.body.data = (0..=255).collect();
+        block.body.emissions.extend((6..9u8).map(|i| Emission {
+            pubkey: [i; 48],
+            weight: u64::from(i) << 40,
+        }));
+        block.reseal().unwrap();
+
+        let bytes = to_msgpack(&block);
+        let decoded = from_msgpack(&bytes).unwrap();
+        assert_eq!(decoded, block);
+        assert_eq!(decoded.calculate_root(), block.calculate_root());
+        decoded.verify(None).unwrap();
+        // Byte fields are `bin` items: the 256 data bytes follow a bin 16 header.
+        assert!(bytes.windows(3).any(|w| w == [0xc5, 0x01, 0x00]));
+
+        assert!(matches!(
+            from_msgpack(&bytes[..bytes.len() - 1]),
+            Err(BlockError::Msgpack(
+                crate::msgpack::FormatError::UnexpectedEof
+            ))
+        ));
+    }
+
+    #[test]
+    fn canonical_bytes_reject_bad_prefix_and_length() {
+        let bytes = sample_block().to_canonical_bytes();
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[..4].copy_from_slice(b"DIGX");
+        assert!(matches!(
+            DigL2Block::from_canonical_bytes(&wrong_magic),
+            Err(BlockError::BadMagic)
+        ));
+        assert!(matches!(
+            DigL2Block::from_canonical_bytes(b"DI"),
+            Err(BlockError::BadMagic)
+        ));
+
+        let mut future = bytes.clone();
+        future[4] = CANONICAL_FORMAT_VERSION + 1;
+        assert!(matches!(
+            DigL2Block::from_canonical_bytes(&future),
+            Err(BlockError::UnsupportedFormatVersion { version: 2 })
+        ));
+
+        for len in [bytes.len() - 1, CANONICAL_PREFIX_LEN + 10] {
+            assert!(matches!(
+                DigL2Block::from_canonical_bytes(&bytes[..len]),
+                Err(BlockError::InvalidCanonicalLength { len: l }) if l == len
+            ));
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            DigL2Block::from_canonical_bytes(&trailing),
+            Err(BlockError::InvalidCanonicalLength { .. })
+        ));
+    }
+
+    fn sample_block() -> DigL2Block {
+        let body = make_body();
+        DigL2Block {
+            header: make_header_for_body(&body),
+            body,
+        }
+    }
+
+    #[test]
+    fn dyn_root_matches_static_roots() {
+        use crate::hasher::{HashAlgorithm, Sha256State};
+        let block = sample_block();
+        let mut sha = Sha256State::default();
+        assert_eq!(block.calculate_root_dyn(&mut sha), block.calculate_root());
+        // The hasher is reset afterwards and can be reused.
+        assert_eq!(block.calculate_root_dyn(&mut sha), block.calculate_root());
+
+        let mut keccak = HashAlgorithm::Keccak256.runtime_hasher();
+        assert_eq!(
+            block.calculate_root_dyn(keccak.as_mut()),
+            block.calculate_root_keccak()
+        );
+        let mut blake3 = HashAlgorithm::Blake3.runtime_hasher();
+        let blake3_root = block.calculate_root_dyn(blake3.as_mut());
+        assert_ne!(blake3_root, block.calculate_root());
+        assert_eq!(block.calculate_root_dyn(blake3.as_mut()), blake3_root);
+
+        // Input absorbed before the call does not leak into the root.
+        for algorithm in [
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Keccak256,
+            HashAlgorithm::Blake3,
+        ] {
+            let mut fresh = algorithm.runtime_hasher();
+            let mut dirty = algorithm.runtime_hasher();
+            dirty.update(b"left over");
+            assert_eq!(
+                block.calculate_root_dyn(dirty.as_mut()),
+                block.calculate_root_dyn(fresh.as_mut()),
+                "{algorithm:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn big_endian_root_differs_but_is_stable() {
+        use crate::hasher::BigEndian;
+        let block = sample_block();
+        let leaf = definitions::emission_hash_with(
+            &BigEndian(Sha256Hasher),
+            definitions::FieldEncoding::Concatenated,
+            &[5u8; 48],
+            10,
+        );
+        let expect = Sha256Hasher.hash(&[b"dig:l2:emission:", &[5u8; 48], &10u64.to_be_bytes()]);
+        assert_eq!(leaf, expect);
+
+        let be = block.calculate_root_with(&BigEndian(Sha256Hasher));
+        assert_ne!(be, block.calculate_root());
+        assert_eq!(
+            be,
+            sample_block().calculate_root_with(&BigEndian(Sha256Hasher))
+        );
+        assert_eq!(
+            hex::encode(be),
+            "bdd579089a3de0d39ca2a9c954ecaa74efd8db70a240a7233b714421737dfc65"
+        );
+        assert_eq!(
+            block.calculate_root_with(&Sha256Hasher),
+            block.calculate_root()
+        );
+    }
+
+    #[test]
+    fn keccak_root_matches_solidity_vector() {
+        // Reproducible on-chain for `sample_block()`:
+        //
+        //     bytes32 emissionLeaf = keccak256(abi.encodePacked(
+        //         "dig:l2:emission:",
+        //         hex"050505050505050505050505050505050505050505050505050505050505050505050505050505050505050505050505",
+        //         hex"0a00000000000000" // weight 10, little-endian u64
+        //     ));
+        //     bytes32 blockRoot = keccak256(abi.encodePacked(
+        //         "dig:l2:block_root:",
+        //         hex"042d024006ad2d4fdd16ac30ed6417d8f239891c24d572919119938f4d5b0446", // headerRoot
+        //         hex"76f3eaf7a24d91abb83b44e6fb483bc96e067c60af4b833f0e0043cb80d0a03e"  // bodyRoot
+        //     ));
+        //
+        // The header leaves hash the stored (SHA-256) `body_root` field as-is.
+        let block = sample_block();
+        let leaf = definitions::emission_hash_with(
+            &Keccak256,
+            definitions::FieldEncoding::Concatenated,
+            &[5u8; 48],
+            10,
+        );
+        assert_eq!(
+            hex::encode(leaf),
+            "d284d9645aa4f9731e12dc5a5386edf0d562d9e67337bda7f2cb1f7b2faaad96"
+        );
+        let header_root = definitions::header_root_with(&Keccak256, &block.header);
+        let body_root = block.body.calculate_root_for_version_with(
+            &Keccak256,
+            block.header.version,
+            &mut Vec::new(),
+            &mut Vec::new(),
+        );
+        assert_eq!(
+            hex::encode(header_root),
+            "042d024006ad2d4fdd16ac30ed6417d8f239891c24d572919119938f4d5b0446"
+        );
+        assert_eq!(
+            hex::encode(body_root),
+            "76f3eaf7a24d91abb83b44e6fb483bc96e067c60af4b833f0e0043cb80d0a03e"
+        );
+        assert_eq!(
+            hex::encode(block.calculate_root_keccak()),
+            "d93bce786ad348f9ed1f3dac765ce0f87195040d354728f8a9a754eeff0d2eb3"
+        );
+        assert_ne!(block.calculate_root_keccak(), block.calculate_root());
+        assert_eq!(
+            block.calculate_root_with(&Sha256Hasher),
+            block.calculate_root()
+        );
+    }
+
+    #[test]
+    fn diff_of_identical_blocks_is_empty() {
+        let block = sample_block();
+        assert!(block.diff(&block.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_header_fields() {
+        let a = sample_block();
+        let mut b = a.clone();
+        b.header.epoch += 1;
+        b.header.proposer_pubkey = [1u8; 48];
+        assert_eq!(
+            a.diff(&b),
+            vec![
+                BlockFieldDiff::Header { field: "epoch" },
+                BlockFieldDiff::Header {
+                    field: "proposer_pubkey"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_reports_data() {
+        let a = sample_block();
+        let mut b = a.clone();
+        b.body.data.push(4);
+        assert_eq!(a.diff(&b), vec![BlockFieldDiff::Data]);
+    }
+
+    #[test]
+    fn diff_matches_emissions_by_pubkey() {
+        let a = sample_block();
+        let mut b = a.clone();
+        b.body.emissions[0].weight = 11;
+        b.body.emissions.push(Emission {
+            pubkey: [6u8; 48],
+            weight: 1,
+        });
+        assert_eq!(
+            a.diff(&b),
+            vec![
+                BlockFieldDiff::EmissionChanged {
+                    pubkey: [5u8; 48],
+                    old_weight: 10,
+                    new_weight: 11,
+                },
+                BlockFieldDiff::EmissionAdded {
+                    pubkey: [6u8; 48],
+                    weight: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            b.diff(&a),
+            vec![
+                BlockFieldDiff::EmissionChanged {
+                    pubkey: [5u8; 48],
+                    old_weight: 11,
+                    new_weight: 10,
+                },
+                BlockFieldDiff::EmissionRemoved {
+                    pubkey: [6u8; 48],
+                    weight: 1,
+                },
+            ]
+        );
+
+        // Reordering emissions is not a difference.
+        let mut c = b.clone();
+        c.body.emissions.reverse();
+        assert!(b.diff(&c).is_empty());
+    }
+
+    #[test]
+    fn canonical_json_is_byte_identical_for_equal_blocks() {
+        let a = sample_block();
+        let b = DigL2Block::new(make_header_for_body(&make_body()), make_body(), None).unwrap();
+        assert_eq!(a, b);
+        let json = to_canonical_json(&a);
+        assert_eq!(json, to_canonical_json(&b));
+        assert!(!json.contains(' ') && !json.contains('\n'));
+        assert!(json.starts_with(r#"{"body":{"data":"0x010203","emissions":[{"pubkey":"0x0505"#));
+        assert!(json.contains(r#""weight":10}]},"header":{"body_root":"0x"#));
+        let back: DigL2Block = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, a);
+        crate::testing::assert_root_stable(&a);
+    }
+
+    fn consensus_block(cfg: &crate::emission_config::ConsensusEmissionConfig) -> DigL2Block {
+        let attesters = [[0x11u8; 48], [0x22u8; 48]];
+        DigL2Block::build(&BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 7,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![1, 2, 3],
+            extra_emissions: vec![Emission {
+                pubkey: [0x33u8; 48],
+                weight: 7,
+            }],
+            attester_pubkeys: &attesters,
+            cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn verify_consensus_emissions_accepts_built_block() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let block = consensus_block(&cfg);
+        let attesters = [[0x11u8; 48], [0x22u8; 48]];
+        assert!(
+            block
+                .verify_consensus_emissions(&[9u8; 48], &attesters, &cfg)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn verify_consensus_emissions_rejects_tampered_proposer_weight() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let mut block = consensus_block(&cfg);
+        block.body.emissions[0].weight = 13;
+        let attesters = [[0x11u8; 48], [0x22u8; 48]];
+        let err = block
+            .verify_consensus_emissions(&[9u8; 48], &attesters, &cfg)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BlockError::MissingConsensusEmission { pubkey, weight: 12 } if pubkey == [9u8; 48]
+        ));
+    }
+
+    #[test]
+    fn verify_consensus_emissions_rejects_missing_attester() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let mut block = consensus_block(&cfg);
+        block.body.emissions.retain(|e| e.pubkey != [0x22u8; 48]);
+        let attesters = [[0x11u8; 48], [0x22u8; 48]];
+        let err = block
+            .verify_consensus_emissions(&[9u8; 48], &attesters, &cfg)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BlockError::MissingConsensusEmission { pubkey, weight: 44 } if pubkey == [0x22u8; 48]
+        ));
+    }
+
+    #[test]
+    fn validate_successor_rejects_earlier_timestamp() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 0);
+        let mut specs: Vec<definitions::BlockSpec> = (0..2u8)
+            .map(|i| definitions::BlockSpec {
+                version: definitions::TIMESTAMP_VERSION,
+                network_id: [0xabu8; 32],
+                proposer_pubkey: [i + 1; 48],
+                data: vec![i],
+                extra_emissions: vec![],
+                attester_pubkeys: vec![],
+                cfg: cfg.clone(),
+                timestamp: 100,
+                extra_data: [0u8; 32],
+            })
+            .collect();
+        let chain = definitions::build_chain(specs.clone()).unwrap();
+        assert!(validate_chain(&chain).is_ok());
+
+        specs[1].timestamp = 99;
+        let chain = definitions::build_chain(specs).unwrap();
+        match chain[0].validate_successor(&chain[1]).unwrap_err() {
+            BlockError::TimestampRegression { parent, child } => {
+                assert_eq!((parent, child), (100, 99));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn build_rejects_timestamp_before_timestamp_version() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 0);
+        let err = DigL2Block::build(&BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 0,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![],
+            extra_emissions: vec![],
+            attester_pubkeys: &[],
+            cfg: &cfg,
+            timestamp: 5,
+            extra_data: [0u8; 32],
+        })
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            BlockError::Header(crate::header::HeaderError::UncommittedTimestamp { version: 1 })
+        ));
+    }
+
+    #[test]
+    fn build_gates_extra_data_on_extra_data_version() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 0);
+        let args = |version| BuildL2BlockArgs {
+            version,
+            network_id: [0xabu8; 32].into(),
+            epoch: 0,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![],
+            extra_emissions: vec![],
+            attester_pubkeys: &[],
+            cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0x42u8; 32],
+        };
+        assert!(matches!(
+            DigL2Block::build(&args(definitions::TIMESTAMP_VERSION)).unwrap_err(),
+            BlockError::Header(crate::header::HeaderError::UncommittedExtraData { version: 3 })
+        ));
+        let block = DigL2Block::build(&args(definitions::EXTRA_DATA_VERSION)).unwrap();
+        assert_eq!(block.header.extra_data, [0x42u8; 32]);
+        assert!(block.verify(None).is_ok());
+        let roundtrip = block.to_canonical_bytes();
+        let header_end = CANONICAL_PREFIX_LEN + CANONICAL_HEADER_LEN;
+        assert_eq!(&roundtrip[header_end - 32..header_end], &[0x42u8; 32]);
+        assert_eq!(DigL2Block::from_canonical_bytes(&roundtrip).unwrap(), block);
+    }
+
+    #[test]
+    fn camel_case_block_json_has_same_root() {
+        let block = sample_block();
+        let h = &block.header;
+        let json = format!(
+            r#"{{"header":{{"version":{},"networkId":"0x{}","epoch":{},"prevBlockRoot":"0x{}","bodyRoot":"0x{}","dataCount":{},"emissionsCount":{},"proposerPubkey":"0x{}"}},"body":{{"data":"0x010203","emissions":[{{"pubkey":"0x{}","weight":10}}]}}}}"#,
+            h.version,
+            hex::encode(h.network_id),
+            h.epoch,
+            hex::encode(h.prev_block_root),
+            hex::encode(h.body_root),
+            h.data_count,
+            h.emissions_count,
+            hex::encode(h.proposer_pubkey),
+            hex::encode([5u8; 48]),
+        );
+        let back: DigL2Block = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, block);
+        assert_eq!(back.calculate_root(), block.calculate_root());
+    }
+
+    #[test]
+    fn calculate_roots_matches_per_block_roots() {
+        let mut blocks = chain_of(5);
+        let mut v2 = sample_block();
+        v2.header.version = definitions::CHUNKED_DATA_VERSION;
+        v2.body.data = (0..200).collect();
+        blocks.push(v2);
+        blocks.push(DigL2Block {
+            header: make_header_for_body(&L2BlockBody {
+                data: vec![],
+                emissions: vec![],
+            }),
+            body: L2BlockBody {
+                data: vec![],
+                emissions: vec![],
+            },
+        });
+        let expected: Vec<_> = blocks.iter().map(DigL2Block::calculate_root).collect();
+        assert_eq!(calculate_roots(&blocks), expected);
+        assert!(calculate_roots(&[]).is_empty());
+    }
+
+    #[test]
+    fn count_conversion_rejects_oversize_lengths() {
+        assert_eq!(count_u32("data_count", 7).unwrap(), 7);
+        assert_eq!(
+            count_u32("data_count", u32::MAX as usize).unwrap(),
+            u32::MAX
+        );
+        // A genuine 4 GiB body is impractical in tests; exercise the
+        // conversion with the first length that no longer fits.
+        #[cfg(target_pointer_width = "64")]
+        match count_u32("emissions_count", u32::MAX as usize + 1).unwrap_err() {
+            BlockError::CountOverflow { field, len } => {
+                assert_eq!(field, "emissions_count");
+                assert_eq!(len, 1 << 32);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn build_handles_empty_and_proposer_only_bodies() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 0);
+        for version in [1, definitions::CHUNKED_DATA_VERSION] {
+            let args = BuildL2BlockArgs {
+                version,
+                network_id: [0xabu8; 32].into(),
+                epoch: 0,
+                prev_block_root: [0u8; 32].into(),
+                proposer_pubkey: [9u8; 48].into(),
+                data: vec![],
+                extra_emissions: vec![],
+                attester_pubkeys: &[],
+                cfg: &cfg,
+                timestamp: 0,
+                extra_data: [0u8; 32],
+            };
+            let block = DigL2Block::build(&args).unwrap();
+            assert!(!block.body.is_empty());
+            assert_eq!(block.header.data_count, 0);
+            assert_eq!(block.header.emissions_count, 1);
+            let leaf = definitions::COMPUTE_EMISSION_HASH(&[9u8; 48], 12);
+            assert_eq!(
+                block.header.body_root,
+                definitions::COMPUTE_BODY_ROOT(
+                    &definitions::EMPTY_MERKLE_ROOT(),
+                    &definitions::MERKLE_ROOT(&[leaf])
+                )
+            );
+            assert!(block.verify(Some(version)).is_ok());
+
+            let policy = BlockPolicy {
+                skip_consensus_emissions: true,
+                ..BlockPolicy::default()
+            };
+            let empty = DigL2Block::build_with_policy(&args, &policy).unwrap();
+            assert!(empty.body.is_empty());
+            assert_eq!(
+                (empty.header.data_count, empty.header.emissions_count),
+                (0, 0)
+            );
+            assert_eq!(
+                empty.header.body_root,
+                definitions::COMPUTE_BODY_ROOT(
+                    &definitions::EMPTY_MERKLE_ROOT(),
+                    &definitions::EMPTY_MERKLE_ROOT()
+                )
+            );
+            assert!(empty.verify(Some(version)).is_ok());
+        }
+    }
+
+    #[test]
+    fn verify_header_commits_body_compares_body_root() {
+        let mut block = sample_block();
+        assert!(block.verify_header_commits_body());
+        // Counts are not part of the comparison.
+        block.header.data_count += 1;
+        assert!(block.verify_header_commits_body());
+        block.body.data.push(0xff);
+        assert!(!block.verify_header_commits_body());
+        assert!(matches!(
+            block.verify(None),
+            Err(BlockError::BodyRootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn reseal_repairs_stale_header() {
+        for version in [1, definitions::CHUNKED_DATA_VERSION] {
+            let mut block = sample_block();
+            block.header.version = version;
+            block.reseal().unwrap();
+            assert!(block.verify(Some(version)).is_ok());
+
+            let before = block.header.clone();
+            block.body.data.extend_from_slice(&[9, 9, 9]);
+            block.body.emissions.push(Emission {
+                pubkey: [6u8; 48],
+                weight: 3,
+            });
+            assert!(block.verify(None).is_err());
+            assert!(!block.verify_header_commits_body());
+
+            block.reseal().unwrap();
+            assert!(block.verify_header_commits_body());
+            assert!(block.verify(Some(version)).is_ok());
+            let rebuilt =
+                DigL2Block::new(block.header.clone(), block.body.clone(), Some(version)).unwrap();
+            assert_eq!(rebuilt, block);
+            assert_eq!(block.header.data_count, 6);
+            assert_eq!(block.header.emissions_count, 2);
+            // Only the body commitments moved.
+            let h = &block.header;
+            assert_eq!(
+                (h.version, h.network_id, h.epoch, h.prev_block_root),
+                (
+                    before.version,
+                    before.network_id,
+                    before.epoch,
+                    before.prev_block_root
+                )
+            );
+            assert_eq!(h.proposer_pubkey, before.proposer_pubkey);
+        }
+    }
+
+    fn chain_of(n: u8) -> Vec<DigL2Block> {
+        let specs = (0..n).map(|i| definitions::BlockSpec {
+            version: 1,
+            network_id: [0xabu8; 32],
+            proposer_pubkey: [i + 1; 48],
+            data: vec![i],
+            extra_emissions: vec![],
+            attester_pubkeys: vec![],
+            cfg: crate::emission_config::ConsensusEmissionConfig::new(12, 0),
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        });
+        definitions::build_chain(specs).unwrap()
+    }
+
+    #[test]
+    fn validate_successor_checks_links() {
+        let chain = chain_of(2);
+        assert!(chain[0].validate_successor(&chain[1]).is_ok());
+
+        let mut wrong_epoch = chain[1].clone();
+        wrong_epoch.header.epoch += 1;
+        assert!(matches!(
+            chain[0].validate_successor(&wrong_epoch),
+            Err(BlockError::EpochNotSuccessor { .. })
+        ));
+
+        let mut wrong_prev = chain[1].clone();
+        wrong_prev.header.prev_block_root = [1u8; 32];
+        assert!(matches!(
+            chain[0].validate_successor(&wrong_prev),
+            Err(BlockError::PrevBlockRootMismatch { .. })
+        ));
+
+        let mut wrong_network = chain[1].clone();
+        wrong_network.header.network_id = [2u8; 32];
+        assert!(matches!(
+            chain[0].validate_successor(&wrong_network),
+            Err(BlockError::NetworkIdMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn classify_emissions_separates_consensus_and_extras() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let attesters = [[2u8; 48], [3u8; 48]];
+        let extras = vec![
+            Emission {
+                pubkey: [7u8; 48],
+                weight: 5,
+            },
+            Emission {
+                pubkey: [9u8; 48],
+                weight: 3,
+            },
+        ];
+        let args = BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 4,
+            prev_block_root: [1u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![],
+            extra_emissions: extras.clone(),
+            attester_pubkeys: &attesters,
+            cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        };
+        let policy = BlockPolicy {
+            allow_duplicate_pubkeys: true,
+            ..BlockPolicy::default()
+        };
+
+        // Appended: the proposer's top-up stays a separate extra record.
+        let block = DigL2Block::build_with_policy(&args, &policy).unwrap();
+        let (consensus, extra) = block.classify_emissions(&[9u8; 48], &attesters, &cfg);
+        let body = &block.body.emissions;
+        assert_eq!(consensus, vec![&body[0], &body[1], &body[2]]);
+        assert_eq!(extra, vec![&extras[0], &extras[1]]);
+
+        // Merged: the topped-up proposer record still counts as consensus.
+        let merged =
+            DigL2Block::build_with_merge(&args, &policy, MergePolicy::MergeIntoConsensus).unwrap();
+        let (consensus, extra) = merged.classify_emissions(&[9u8; 48], &attesters, &cfg);
+        assert_eq!(consensus.len(), 3);
+        assert_eq!(consensus[0].pubkey, [9u8; 48]);
+        assert_eq!(consensus[0].weight, 12 + 3);
+        assert_eq!(extra, vec![&extras[0]]);
+
+        // An invalid config yields no consensus records.
+        let (consensus, extra) = block.classify_emissions(&[9u8; 48], &[], &cfg);
+        assert!(consensus.is_empty());
+        assert_eq!(extra.len(), body.len());
+    }
+
+    #[test]
+    fn compact_block_reconstructs_original() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let attesters = [[2u8; 48], [3u8; 48]];
+        let args = BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 4,
+            prev_block_root: [1u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![1, 2, 3],
+            extra_emissions: vec![Emission {
+                pubkey: [7u8; 48],
+                weight: 5,
+            }],
+            attester_pubkeys: &attesters,
+            cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        };
+        let block = DigL2Block::build(&args).unwrap();
+        let compact = block.to_compact(&attesters, &cfg).unwrap();
+        assert_eq!(compact.extra_emissions, args.extra_emissions);
+        assert_eq!(compact.attester_pubkeys, attesters.to_vec());
+
+        let rebuilt = compact.reconstruct(&cfg).unwrap();
+        assert_eq!(rebuilt, block);
+        assert_eq!(rebuilt.calculate_root(), block.calculate_root());
+
+        // A different config regenerates different consensus weights.
+        let other = crate::emission_config::ConsensusEmissionConfig::new(20, 80);
+        assert!(matches!(
+            compact.reconstruct(&other),
+            Err(BlockError::BodyRootMismatch { .. })
+        ));
+        assert!(matches!(
+            block.to_compact(&attesters[..1], &cfg),
+            Err(BlockError::MissingConsensusEmission { .. })
+        ));
+    }
+
+    #[test]
+    fn validate_successor_rejects_epoch_overflow() {
+        let mut parent = chain_of(1).remove(0);
+        parent.header.epoch = u64::MAX;
+        let mut child = chain_of(2).remove(1);
+        child.header.prev_block_root = parent.calculate_root();
+        child.header.epoch = 0;
+        assert!(matches!(
+            parent.validate_successor(&child),
+            Err(BlockError::EpochOverflow)
+        ));
+    }
+
+    #[test]
+    fn validate_chain_rejects_broken_link() {
+        let mut chain = chain_of(3);
+        assert!(validate_chain(&chain).is_ok());
+        chain.swap(1, 2);
+        assert!(validate_chain(&chain).is_err());
+    }
+
+    #[test]
+    fn header_block_root_with_body_root_matches_block_root() {
+        for version in [1, definitions::CHUNKED_DATA_VERSION] {
+            let mut block = sample_block();
+            block.header.version = version;
+            block.reseal().unwrap();
+            let body_root = block.body.calculate_root_for_version(version);
+            assert_eq!(
+                block.header.block_root_with_body_root(&body_root),
+                block.calculate_root()
+            );
+            // The supplied root is used as given, not the header field.
+            assert_ne!(
+                block.header.block_root_with_body_root(&[0u8; 32]),
+                block.calculate_root()
+            );
+        }
+    }
+
+    #[test]
+    fn verify_partial_block_checks_subroots_and_block_root() {
+        let block = chain_of(1).remove(0);
+        let roots = block.root_breakdown();
+        let header = &block.header;
+        assert!(
+            verify_partial_block(
+                header,
+                &roots.data_root,
+                &roots.emissions_root,
+                &roots.block_root
+            )
+            .is_ok()
+        );
+
+        let tampered = [0xeeu8; 32];
+        for (data_root, emissions_root) in [
+            (&tampered, &roots.emissions_root),
+            (&roots.data_root, &tampered),
+        ] {
+            assert!(matches!(
+                verify_partial_block(header, data_root, emissions_root, &roots.block_root),
+                Err(BlockError::BodyRootMismatch { .. })
+            ));
+        }
+        assert!(matches!(
+            verify_partial_block(header, &roots.data_root, &roots.emissions_root, &tampered),
+            Err(BlockError::BlockRootMismatch { expected, .. }) if expected == tampered
+        ));
+    }
+
+    #[test]
+    fn build_chunked_version_block_is_consistent() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 0);
+        let args = BuildL2BlockArgs {
+            version: definitions::CHUNKED_DATA_VERSION,
+            network_id: [0xabu8; 32].into(),
+            epoch: 7,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: (0..=255u8).collect(),
+            extra_emissions: vec![],
+            attester_pubkeys: &[],
+            cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        };
+        let block = DigL2Block::build(&args).unwrap();
+        assert_eq!(
+            block.header.body_root,
+            block
+                .body
+                .calculate_root_for_version(definitions::CHUNKED_DATA_VERSION)
+        );
+        assert_ne!(block.header.body_root, block.body.calculate_root());
+        let rebuilt = DigL2Block::new(block.header.clone(), block.body.clone(), Some(2)).unwrap();
+        assert_eq!(rebuilt.calculate_root(), block.calculate_root());
+    }
+
+    #[test]
+    fn data_permutation_moves_block_root_from_chunked_version() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 0);
+        let root_of = |version: u32, data: Vec<u8>| {
+            DigL2Block::build(&BuildL2BlockArgs {
+                version,
+                network_id: [0xabu8; 32].into(),
+                epoch: 7,
+                prev_block_root: [0u8; 32].into(),
+                proposer_pubkey: [9u8; 48].into(),
+                data,
+                extra_emissions: vec![],
+                attester_pubkeys: &[],
+                cfg: &cfg,
+                timestamp: 0,
+                extra_data: [0u8; 32],
+            })
+            .unwrap()
+            .calculate_root()
+        };
+        // The legacy sorted data root cannot tell the payloads apart...
+        assert_eq!(root_of(1, vec![1, 2, 3]), root_of(1, vec![3, 2, 1]));
+        // ...the position-aware one of every later version can.
+        for version in [
+            definitions::CHUNKED_DATA_VERSION,
+            definitions::ORDERED_EMISSIONS_VERSION,
+        ] {
+            assert_ne!(
+                root_of(version, vec![1, 2, 3]),
+                root_of(version, vec![3, 2, 1])
+            );
+        }
+    }
+
+    #[test]
+    fn build_block_zero_attesters_policy() {
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 0);
+        let bb_args = BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 7,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![],
+            extra_emissions: vec![],
+            attester_pubkeys: &[],
+            cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        };
+        let b = DigL2Block::build(&bb_args).unwrap();
+        assert_eq!(b.body.emissions.len(), 1); // proposer only
+
+        // Now invalid: non-zero attester share but no attesters
+        let cfg_bad = crate::emission_config::ConsensusEmissionConfig::new(12, 1);
+        let bb_e_args = BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 7,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [1u8; 48].into(),
             data: vec![],
             extra_emissions: vec![],
             attester_pubkeys: &[],
             cfg: &cfg_bad,
+            timestamp: 0,
+            extra_data: [0u8; 32],
         };
         let err = DigL2Block::build(&bb_e_args).unwrap_err();
         match err {
@@ -0,0 +1,472 @@
+//! Structural validation of compressed BLS12-381 G1 public keys.
+//!
+//! `validate_g1_pubkey` checks the 48-byte compressed encoding (ZCash
+//! serialization flags, canonical `x < p`), decompresses `y` from
+//! `y^2 = x^3 + 4`, and checks that the point lies in the prime-order subgroup
+//! (`[r]P` is the point at infinity). Only `feature = "bls"` builds it.
+//!
+//! Field arithmetic is Montgomery multiplication over six little-endian `u64`
+//! limbs, and curve arithmetic uses Jacobian coordinates. None of it is
+//! constant time; pubkeys are public.
+
+use std::cmp::Ordering;
+use thiserror::Error;
+
+type Limbs = [u64; 6];
+
+/// The BLS12-381 base field modulus.
+const P: Limbs = [
+    0xb9fe_ffff_ffff_aaab,
+    0x1eab_fffe_b153_ffff,
+    0x6730_d2a0_f6b0_f624,
+    0x6477_4b84_f385_12bf,
+    0x4b1b_a7b6_434b_acd7,
+    0x1a01_11ea_397f_e69a,
+];
+
+/// Order `r` of the G1 subgroup.
+const R: [u64; 4] = [
+    0xffff_ffff_0000_0001,
+    0x53bd_a402_fffe_5bfe,
+    0x3339_d808_09a1_d805,
+    0x73ed_a753_299d_7d48,
+];
+
+/// `2^768 mod p`, which maps a field element into Montgomery form.
+const R2: Limbs = [
+    0xf4df_1f34_1c34_1746,
+    0x0a76_e6a6_09d1_04f1,
+    0x8de5_476c_4c95_b6d5,
+    0x67eb_88a9_939d_83c0,
+    0x9a79_3e85_b519_952d,
+    0x1198_8fe5_92ca_e3aa,
+];
+
+/// `-p^-1 mod 2^64`, the Montgomery reduction constant.
+const INV: u64 = 0x89f3_fffc_fffc_fffd;
+
+const COMPRESSION_FLAG: u8 = 0x80;
+const INFINITY_FLAG: u8 = 0x40;
+const FLAG_MASK: u8 = 0xe0;
+
+/// Reasons a 48-byte value is not a usable compressed G1 public key.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PubkeyError {
+    /// The compression flag (top bit) is not set.
+    #[error("pubkey is not a compressed G1 point")]
+    NotCompressed,
+
+    /// The point at infinity (or a malformed infinity encoding).
+    #[error("pubkey is the point at infinity")]
+    Infinity,
+
+    /// The x-coordinate is not reduced modulo the field prime.
+    #[error("pubkey x-coordinate is not canonical")]
+    NonCanonicalX,
+
+    /// No curve point has this x-coordinate.
+    #[error("pubkey is not on the BLS12-381 G1 curve")]
+    NotOnCurve,
+
+    /// The point is on the curve but outside the prime-order subgroup.
+    #[error("pubkey is not in the BLS12-381 G1 subgroup")]
+    NotInSubgroup,
+}
+
+/// Checks that `pubkey` is a valid compressed G1 point (see module docs).
+pub fn validate_g1_pubkey(pubkey: &[u8; 48]) -> Result<(), PubkeyError> {
+    let flags = pubkey[0];
+    if flags & COMPRESSION_FLAG == 0 {
+        return Err(PubkeyError::NotCompressed);
+    }
+    if flags & INFINITY_FLAG != 0 {
+        return Err(PubkeyError::Infinity);
+    }
+    let mut x_bytes = *pubkey;
+    x_bytes[0] &= !FLAG_MASK;
+    let x = from_be_bytes(&x_bytes);
+    if cmp(&x, &P) != Ordering::Less {
+        return Err(PubkeyError::NonCanonicalX);
+    }
+    let x = to_mont(&x);
+    let rhs = add_mod(&mont_mul(&mont_mul(&x, &x), &x), &to_mont(&small(4)));
+    // p = 3 (mod 4), so a square root of `rhs`, if one exists, is
+    // `rhs^((p + 1) / 4)`.
+    let y = pow_mont(&rhs, &shr1(&shr1(&add_small(&P, 1))));
+    if mont_mul(&y, &y) != rhs {
+        return Err(PubkeyError::NotOnCurve);
+    }
+    // The sort flag only picks between `y` and `-y`, and the subgroup is
+    // closed under negation, so either root will do.
+    if !Jacobian::affine(x, y).mul_u256(&R).is_infinity() {
+        return Err(PubkeyError::NotInSubgroup);
+    }
+    Ok(())
+}
+
+/// A G1 point `(X / Z^2, Y / Z^3)`, coordinates in Montgomery form; `Z = 0`
+/// is the point at infinity.
+#[derive(Clone, Copy)]
+struct Jacobian {
+    x: Limbs,
+    y: Limbs,
+    z: Limbs,
+}
+
+impl Jacobian {
+    const INFINITY: Jacobian = Jacobian {
+        x: [0; 6],
+        y: [0; 6],
+        z: [0; 6],
+    };
+
+    fn affine(x: Limbs, y: Limbs) -> Self {
+        Jacobian {
+            x,
+            y,
+            z: to_mont(&small(1)),
+        }
+    }
+
+    fn is_infinity(&self) -> bool {
+        self.z == [0; 6]
+    }
+
+    /// `2 * self` (dbl-2009-l, for curves with `a = 0`).
+    fn double(&self) -> Self {
+        if self.is_infinity() {
+            return *self;
+        }
+        let a = mont_mul(&self.x, &self.x);
+        let b = mont_mul(&self.y, &self.y);
+        let c = mont_mul(&b, &b);
+        let xb = add_mod(&self.x, &b);
+        let d = sub_mod(&sub_mod(&mont_mul(&xb, &xb), &a), &c);
+        let d = add_mod(&d, &d);
+        let e = add_mod(&add_mod(&a, &a), &a);
+        let f = mont_mul(&e, &e);
+        let x = sub_mod(&sub_mod(&f, &d), &d);
+        let c8 = add_mod(&c, &c);
+        let c8 = add_mod(&c8, &c8);
+        let c8 = add_mod(&c8, &c8);
+        let y = sub_mod(&mont_mul(&e, &sub_mod(&d, &x)), &c8);
+        let yz = mont_mul(&self.y, &self.z);
+        Jacobian {
+            x,
+            y,
+            z: add_mod(&yz, &yz),
+        }
+    }
+
+    /// `self + q` for an affine `q` (`q.z = 1`; madd-2007-bl).
+    fn add_affine(&self, q: &Jacobian) -> Self {
+        if self.is_infinity() {
+            return *q;
+        }
+        let z1z1 = mont_mul(&self.z, &self.z);
+        let u2 = mont_mul(&q.x, &z1z1);
+        let s2 = mont_mul(&q.y, &mont_mul(&self.z, &z1z1));
+        let h = sub_mod(&u2, &self.x);
+        let r = sub_mod(&s2, &self.y);
+        if h == [0; 6] {
+            return if r == [0; 6] {
+                self.double()
+            } else {
+                Jacobian::INFINITY
+            };
+        }
+        let r = add_mod(&r, &r);
+        let hh = mont_mul(&h, &h);
+        let i = add_mod(&hh, &hh);
+        let i = add_mod(&i, &i);
+        let j = mont_mul(&h, &i);
+        let v = mont_mul(&self.x, &i);
+        let x = sub_mod(&sub_mod(&sub_mod(&mont_mul(&r, &r), &j), &v), &v);
+        let y1j = mont_mul(&self.y, &j);
+        let y = sub_mod(&mont_mul(&r, &sub_mod(&v, &x)), &add_mod(&y1j, &y1j));
+        let zh = add_mod(&self.z, &h);
+        let z = sub_mod(&sub_mod(&mont_mul(&zh, &zh), &z1z1), &hh);
+        Jacobian { x, y, z }
+    }
+
+    /// `[k] self` for an affine `self`, by double-and-add.
+    fn mul_u256(&self, k: &[u64; 4]) -> Self {
+        let mut acc = Jacobian::INFINITY;
+        for i in (0..256).rev() {
+            acc = acc.double();
+            if (k[i / 64] >> (i % 64)) & 1 == 1 {
+                acc = acc.add_affine(self);
+            }
+        }
+        acc
+    }
+}
+
+fn small(v: u64) -> Limbs {
+    [v, 0, 0, 0, 0, 0]
+}
+
+fn from_be_bytes(bytes: &[u8; 48]) -> Limbs {
+    let mut out = [0u64; 6];
+    for (i, chunk) in bytes.chunks_exact(8).enumerate() {
+        out[5 - i] = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    out
+}
+
+fn cmp(a: &Limbs, b: &Limbs) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+/// `a - b`, requiring `a >= b`.
+fn sub(a: &Limbs, b: &Limbs) -> Limbs {
+    let mut out = [0u64; 6];
+    let mut borrow = false;
+    for i in 0..6 {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        out[i] = d;
+        borrow = b1 || b2;
+    }
+    out
+}
+
+/// `a + b` without reduction; the caller keeps the sum below `2^384`.
+fn add(a: &Limbs, b: &Limbs) -> Limbs {
+    let mut out = [0u64; 6];
+    let mut carry = false;
+    for i in 0..6 {
+        let (s, c1) = a[i].overflowing_add(b[i]);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        out[i] = s;
+        carry = c1 || c2;
+    }
+    out
+}
+
+/// `a + v`, requiring no carry out of the top limb.
+fn add_small(a: &Limbs, v: u64) -> Limbs {
+    let mut out = *a;
+    let mut carry = v;
+    for limb in &mut out {
+        let (s, c) = limb.overflowing_add(carry);
+        *limb = s;
+        carry = c as u64;
+    }
+    out
+}
+
+fn shr1(a: &Limbs) -> Limbs {
+    let mut out = [0u64; 6];
+    for i in 0..6 {
+        out[i] = a[i] >> 1;
+        if i < 5 {
+            out[i] |= a[i + 1] << 63;
+        }
+    }
+    out
+}
+
+/// `(a + b) mod p` for `a, b < p`; `p < 2^381` so the sum cannot overflow.
+fn add_mod(a: &Limbs, b: &Limbs) -> Limbs {
+    let out = add(a, b);
+    if cmp(&out, &P) != Ordering::Less {
+        sub(&out, &P)
+    } else {
+        out
+    }
+}
+
+/// `(a - b) mod p` for `a, b < p`.
+fn sub_mod(a: &Limbs, b: &Limbs) -> Limbs {
+    if cmp(a, b) == Ordering::Less {
+        sub(&add(a, &P), b)
+    } else {
+        sub(a, b)
+    }
+}
+
+/// `a * b * 2^-384 mod p` for `a, b < p` (CIOS Montgomery multiplication).
+fn mont_mul(a: &Limbs, b: &Limbs) -> Limbs {
+    let mut t = [0u64; 8];
+    for &bi in b {
+        let mut carry = 0u64;
+        for j in 0..6 {
+            let uv = u128::from(t[j]) + u128::from(a[j]) * u128::from(bi) + u128::from(carry);
+            t[j] = uv as u64;
+            carry = (uv >> 64) as u64;
+        }
+        let uv = u128::from(t[6]) + u128::from(carry);
+        t[6] = uv as u64;
+        t[7] = (uv >> 64) as u64;
+
+        let m = t[0].wrapping_mul(INV);
+        let uv = u128::from(t[0]) + u128::from(m) * u128::from(P[0]);
+        let mut carry = (uv >> 64) as u64;
+        for j in 1..6 {
+            let uv = u128::from(t[j]) + u128::from(m) * u128::from(P[j]) + u128::from(carry);
+            t[j - 1] = uv as u64;
+            carry = (uv >> 64) as u64;
+        }
+        let uv = u128::from(t[6]) + u128::from(carry);
+        t[5] = uv as u64;
+        t[6] = t[7] + (uv >> 64) as u64;
+    }
+    // With `4p < 2^384` the result is below `2p` and fits six limbs.
+    let out: Limbs = t[..6].try_into().unwrap();
+    if cmp(&out, &P) != Ordering::Less {
+        sub(&out, &P)
+    } else {
+        out
+    }
+}
+
+/// `a` (below `p`) in Montgomery form, `a * 2^384 mod p`.
+fn to_mont(a: &Limbs) -> Limbs {
+    mont_mul(a, &R2)
+}
+
+fn bit(a: &Limbs, i: usize) -> bool {
+    (a[i / 64] >> (i % 64)) & 1 == 1
+}
+
+/// `base^exp` for a Montgomery-form `base`, in Montgomery form.
+fn pow_mont(base: &Limbs, exp: &Limbs) -> Limbs {
+    let mut acc = to_mont(&small(1));
+    for i in (0..384).rev() {
+        acc = mont_mul(&acc, &acc);
+        if bit(exp, i) {
+            acc = mont_mul(&acc, base);
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The standard compressed G1 generator.
+    const G1_GENERATOR: &str = "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb";
+
+    fn key(hex_str: &str) -> [u8; 48] {
+        hex::decode(hex_str).unwrap().try_into().unwrap()
+    }
+
+    /// `(a * b) mod p` by double-and-add, independent of the Montgomery code.
+    fn mul_reference(a: &Limbs, b: &Limbs) -> Limbs {
+        let mut acc = small(0);
+        for i in (0..384).rev() {
+            acc = add_mod(&acc, &acc);
+            if bit(b, i) {
+                acc = add_mod(&acc, a);
+            }
+        }
+        acc
+    }
+
+    fn from_mont(a: &Limbs) -> Limbs {
+        mont_mul(a, &small(1))
+    }
+
+    #[test]
+    fn generator_is_valid() {
+        assert_eq!(validate_g1_pubkey(&key(G1_GENERATOR)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_keys() {
+        assert_eq!(
+            validate_g1_pubkey(&[0u8; 48]),
+            Err(PubkeyError::NotCompressed)
+        );
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        assert_eq!(validate_g1_pubkey(&infinity), Err(PubkeyError::Infinity));
+
+        let mut too_big = [0xffu8; 48];
+        too_big[0] = 0x9f;
+        assert_eq!(
+            validate_g1_pubkey(&too_big),
+            Err(PubkeyError::NonCanonicalX)
+        );
+
+        // x + 1 of the generator has no curve point.
+        let mut off_curve = key(G1_GENERATOR);
+        off_curve[47] = 0xbc;
+        assert_eq!(validate_g1_pubkey(&off_curve), Err(PubkeyError::NotOnCurve));
+    }
+
+    #[test]
+    fn rejects_points_outside_the_subgroup() {
+        // (0, 2) is on the curve but has order 3, which divides the cofactor.
+        let mut order_three = [0u8; 48];
+        order_three[0] = COMPRESSION_FLAG;
+        assert_eq!(
+            validate_g1_pubkey(&order_three),
+            Err(PubkeyError::NotInSubgroup)
+        );
+        // So is the first x with a curve point besides 0, with either sign.
+        let mut x = 1u8;
+        let pubkey = loop {
+            let mut pubkey = [0u8; 48];
+            pubkey[0] = COMPRESSION_FLAG;
+            pubkey[47] = x;
+            if validate_g1_pubkey(&pubkey) != Err(PubkeyError::NotOnCurve) {
+                break pubkey;
+            }
+            x += 1;
+        };
+        assert_eq!(validate_g1_pubkey(&pubkey), Err(PubkeyError::NotInSubgroup));
+        let mut negated = pubkey;
+        negated[0] |= 0x20;
+        assert_eq!(
+            validate_g1_pubkey(&negated),
+            Err(PubkeyError::NotInSubgroup)
+        );
+    }
+
+    #[test]
+    fn field_arithmetic_sanity() {
+        let p_minus_1 = sub(&P, &small(1));
+        // (p - 1)^2 = 1 mod p
+        assert_eq!(mul_reference(&p_minus_1, &p_minus_1), small(1));
+        assert_eq!(add_mod(&p_minus_1, &small(1)), small(0));
+        assert_eq!(sub_mod(&small(0), &small(1)), p_minus_1);
+
+        // The Montgomery constants match their definitions.
+        assert_eq!(P[0].wrapping_mul(INV), u64::MAX);
+        let r_mod_p = (0..384).fold(small(1), |acc, _| add_mod(&acc, &acc));
+        assert_eq!(mul_reference(&r_mod_p, &r_mod_p), R2);
+
+        // Montgomery products agree with the reference multiplication.
+        let a = sub(&P, &small(12_345));
+        let b = shr1(&P);
+        assert_eq!(
+            from_mont(&mont_mul(&to_mont(&a), &to_mont(&b))),
+            mul_reference(&a, &b)
+        );
+        // Fermat: 2^(p-1) = 1 mod p
+        assert_eq!(
+            from_mont(&pow_mont(&to_mont(&small(2)), &p_minus_1)),
+            small(1)
+        );
+    }
+
+    #[test]
+    fn generator_has_order_r() {
+        let g = key(G1_GENERATOR);
+        let mut x_bytes = g;
+        x_bytes[0] &= !FLAG_MASK;
+        let x = to_mont(&from_be_bytes(&x_bytes));
+        let rhs = add_mod(&mont_mul(&mont_mul(&x, &x), &x), &to_mont(&small(4)));
+        let y = pow_mont(&rhs, &shr1(&shr1(&add_small(&P, 1))));
+        let point = Jacobian::affine(x, y);
+        assert!(point.mul_u256(&R).is_infinity());
+        // r - 1 lands on -G, not infinity.
+        let mut r_minus_1 = R;
+        r_minus_1[0] -= 1;
+        assert!(!point.mul_u256(&r_minus_1).is_infinity());
+        assert!(!point.mul_u256(&[1, 0, 0, 0]).is_infinity());
+    }
+}
@@ -1,19 +1,173 @@
 //! L2 block body: application data and standardized emissions.
 //!
 //! The body owns calculation of its `BODY_ROOT` by composing the two subroots:
-//! - `DATA_ROOT`: Merkle root of per-byte data item hashes.
-//! - `EMISSIONS_ROOT`: Merkle root of per-emission hashes.
+//! - `DATA_ROOT`: Merkle root of per-byte data item hashes (legacy) or of
+//!   position-aware data chunks (block versions >= `CHUNKED_DATA_VERSION`).
+//!   A position-tagged per-byte mode is also available via `DataRootMode`.
+//! - `EMISSIONS_ROOT`: Merkle root of per-emission hashes, sorted (legacy) or
+//!   in insertion order (block versions >= `ORDERED_EMISSIONS_VERSION`), see
+//!   `EmissionsRootMode`.
 //!
-//! Both collections are sorted by their hash for determinism (sorting is done on
-//! a local copy so `calculate_*` methods do not mutate the body).
+//! Per-byte data leaves and emission leaves are sorted by their hash for
+//! determinism (sorting is done on a local copy so `calculate_*` methods do not
+//! mutate the body). Emissions with equal hashes are ordered by `(pubkey,
+//! weight)`, so the emission order is reproducible even under a collision.
+//! Indexed and chunked data leaves keep payload order, so permuting `data`
+//! changes the root in those modes.
 
-use crate::dig_l2_definition as definitions;
+use crate::dig_l2_definition::{
+    self as definitions, DataRootMode, EmissionsRootMode, FieldEncoding,
+};
 use crate::emission::Emission;
+use crate::hasher::{RootHasher, Sha256Hasher};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use thiserror::Error;
 
+/// The legacy `DATA_ROOT` of `data` without building a body: the Merkle root of
+/// `COMPUTE_DATA_HASH(byte)` per byte, sorted by hash ascending.
+///
+/// Equal to `L2BlockBody::calculate_data_root` for a body holding `data`.
+pub fn compute_data_root(data: &[u8]) -> definitions::Hash32 {
+    let mut leaves = Vec::new();
+    data_leaves_into(
+        &Sha256Hasher,
+        data,
+        DataRootMode::PerByteSorted,
+        &mut leaves,
+    );
+    definitions::MERKLE_ROOT(&leaves)
+}
+
+/// The `EMISSIONS_ROOT` of `emissions` without building a body: the Merkle
+/// root of the per-emission hashes, sorted by hash ascending.
+///
+/// Equal to `L2BlockBody::calculate_emissions_root` for a body holding
+/// `emissions`.
+pub fn compute_emissions_root(emissions: &[Emission]) -> definitions::Hash32 {
+    let mut leaves = Vec::new();
+    emission_leaves_into(
+        &Sha256Hasher,
+        emissions,
+        FieldEncoding::Concatenated,
+        &mut leaves,
+    );
+    definitions::MERKLE_ROOT(&leaves)
+}
+
+/// `compute_data_root` over bytes from any source, e.g. a ring buffer or a
+/// chain of slices, without first copying them into one `Vec<u8>`.
+///
+/// Only the 32-byte leaves are collected (they must be sorted).
+pub fn compute_data_root_from_iter(data: impl Iterator<Item = u8>) -> definitions::Hash32 {
+    let mut leaves: Vec<definitions::Hash32> = data.map(definitions::COMPUTE_DATA_HASH).collect();
+    leaves.sort_unstable();
+    definitions::MERKLE_ROOT(&leaves)
+}
+
+/// `compute_emissions_root` over borrowed emissions from any source, without
+/// first collecting them into a `Vec<Emission>`.
+///
+/// Sorting the leaves alone suffices: emissions with equal leaf hashes are
+/// equal, so the `(pubkey, weight)` tie-break cannot change the root.
+pub fn compute_emissions_root_from_iter<'a>(
+    emissions: impl Iterator<Item = &'a Emission>,
+) -> definitions::Hash32 {
+    let mut leaves: Vec<definitions::Hash32> = emissions.map(Emission::calculate_root).collect();
+    leaves.sort_unstable();
+    definitions::MERKLE_ROOT(&leaves)
+}
+
+/// Replaces `out` with the `DATA_ROOT` leaves of `data` for `mode`, in tree order.
+fn data_leaves_into<H: RootHasher + ?Sized>(
+    hasher: &H,
+    data: &[u8],
+    mode: DataRootMode,
+    out: &mut Vec<definitions::Hash32>,
+) {
+    out.clear();
+    match mode {
+        DataRootMode::PerByteSorted => {
+            out.extend(data.iter().map(|b| definitions::data_hash_with(hasher, *b)));
+            out.sort_unstable();
+        }
+        DataRootMode::PerByteIndexed => out.extend(
+            data.iter()
+                .enumerate()
+                .map(|(i, b)| definitions::indexed_data_hash_with(hasher, i as u64, *b)),
+        ),
+        DataRootMode::Chunked => out.extend(
+            data.chunks(definitions::DATA_CHUNK_SIZE)
+                .enumerate()
+                .map(|(i, chunk)| definitions::data_chunk_hash_with(hasher, i as u64, chunk)),
+        ),
+    }
+}
+
+/// Replaces `out` with the `EMISSIONS_ROOT` leaves of `emissions` under
+/// `encoding`, in `merkle_order_with` order.
+fn emission_leaves_into<H: RootHasher + ?Sized>(
+    hasher: &H,
+    emissions: &[Emission],
+    encoding: FieldEncoding,
+    out: &mut Vec<definitions::Hash32>,
+) {
+    out.clear();
+    out.extend(
+        merkle_order_with(hasher, encoding, emissions)
+            .into_iter()
+            .map(|(leaf, _)| leaf),
+    );
+}
+
+/// Replaces `out` with the `EMISSIONS_ROOT` leaves of `emissions` for block
+/// `version`: sorted or insertion-order per `EmissionsRootMode::for_version`,
+/// fields joined per `FieldEncoding::for_version`.
+fn emission_leaves_for_version_into<H: RootHasher + ?Sized>(
+    hasher: &H,
+    emissions: &[Emission],
+    version: u32,
+    out: &mut Vec<definitions::Hash32>,
+) {
+    let encoding = FieldEncoding::for_version(version);
+    match EmissionsRootMode::for_version(version) {
+        EmissionsRootMode::Sorted => emission_leaves_into(hasher, emissions, encoding, out),
+        EmissionsRootMode::Ordered => {
+            out.clear();
+            out.extend(emissions.iter().enumerate().map(|(i, e)| {
+                definitions::indexed_emission_hash_with(
+                    hasher, encoding, i as u64, &e.pubkey, e.weight,
+                )
+            }));
+        }
+    }
+}
+
+/// `emissions` paired with their leaf hashes, in `EMISSIONS_ROOT` leaf order.
+///
+/// A stable sort keyed by `(hash, pubkey, weight)`: ties on the hash break by
+/// pubkey, then weight, so the order never depends on input order or on the
+/// sort implementation, even for a hasher with collisions.
+fn merkle_order_with<'a, H: RootHasher + ?Sized>(
+    hasher: &H,
+    encoding: FieldEncoding,
+    emissions: &'a [Emission],
+) -> Vec<(definitions::Hash32, &'a Emission)> {
+    let mut keyed: Vec<(definitions::Hash32, &Emission)> = emissions
+        .iter()
+        .map(|e| {
+            (
+                definitions::emission_hash_with(hasher, encoding, &e.pubkey, e.weight),
+                e,
+            )
+        })
+        .collect();
+    keyed.sort_by_key(|(leaf, e)| (*leaf, e.pubkey, e.weight));
+    keyed
+}
+
 /// Body of an L2 block: application data bytes and reward emissions.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct L2BlockBody {
     /// Application-specific data bytes. Serialized as `0x`-prefixed hex string.
     #[serde(with = "crate::serde_hex::hex_vec")]
@@ -23,33 +177,570 @@ pub struct L2BlockBody {
 }
 
 impl L2BlockBody {
+    /// A body carrying `data` and no emissions.
+    pub fn data_only(data: Vec<u8>) -> Self {
+        L2BlockBody {
+            data,
+            emissions: Vec::new(),
+        }
+    }
+
+    /// A body carrying `emissions` and no data.
+    pub fn emissions_only(emissions: Vec<Emission>) -> Self {
+        L2BlockBody {
+            data: Vec::new(),
+            emissions,
+        }
+    }
+
+    /// Creates a body, rejecting `data` longer than `max_data` bytes before any
+    /// leaves are hashed.
+    pub fn with_data_checked(
+        data: Vec<u8>,
+        emissions: Vec<Emission>,
+        max_data: usize,
+    ) -> Result<Self, BodyError> {
+        if data.len() > max_data {
+            return Err(BodyError::DataTooLarge {
+                len: data.len(),
+                max: max_data,
+            });
+        }
+        Ok(L2BlockBody { data, emissions })
+    }
+
     /// Computes the `DATA_ROOT` as the Merkle root of `COMPUTE_DATA_HASH(byte)`
     /// for each `byte` in `self.data`, sorted by hash ascending for determinism.
     pub fn calculate_data_root(&self) -> definitions::Hash32 {
-        let mut leaves: Vec<definitions::Hash32> = self
-            .data
-            .iter()
-            .map(|b| definitions::COMPUTE_DATA_HASH(*b))
-            .collect();
-        leaves.sort_unstable();
+        compute_data_root(&self.data)
+    }
+
+    /// Computes the `DATA_ROOT` using an explicit `DataRootMode`.
+    ///
+    /// `PerByteSorted` matches `calculate_data_root`. `PerByteIndexed` hashes
+    /// each byte with its index via `COMPUTE_INDEXED_DATA_HASH`. `Chunked` splits
+    /// `data` into `DATA_CHUNK_SIZE`-byte chunks hashed with their position via
+    /// `COMPUTE_DATA_CHUNK_HASH`. In both ordered modes any byte change or
+    /// reordering moves the root.
+    pub fn calculate_data_root_with_mode(&self, mode: DataRootMode) -> definitions::Hash32 {
+        let mut leaves = Vec::new();
+        self.data_leaves_into(&Sha256Hasher, mode, &mut leaves);
         definitions::MERKLE_ROOT(&leaves)
     }
 
     /// Computes the `EMISSIONS_ROOT` as the Merkle root of each emission's
     /// per-item hash, sorted by hash ascending for determinism.
     pub fn calculate_emissions_root(&self) -> definitions::Hash32 {
-        let mut leaves: Vec<definitions::Hash32> =
-            self.emissions.iter().map(|e| e.calculate_root()).collect();
-        leaves.sort_unstable();
+        compute_emissions_root(&self.emissions)
+    }
+
+    /// Computes the `EMISSIONS_ROOT` under the rules of the given block
+    /// version: from `LENGTH_PREFIXED_FIELDS_VERSION` each leaf is
+    /// `COMPUTE_EMISSION_HASH_FOR_VERSION`, length-prefixed, and from
+    /// `ORDERED_EMISSIONS_VERSION` the leaves are position-tagged and kept in
+    /// insertion order (`EmissionsRootMode::for_version`).
+    pub fn calculate_emissions_root_for_version(&self, version: u32) -> definitions::Hash32 {
+        let mut leaves = Vec::new();
+        emission_leaves_for_version_into(&Sha256Hasher, &self.emissions, version, &mut leaves);
         definitions::MERKLE_ROOT(&leaves)
     }
 
+    /// Computes the `EMISSIONS_ROOT` using an explicit `EmissionsRootMode`.
+    ///
+    /// `Sorted` matches `calculate_emissions_root`; `Ordered` matches
+    /// `calculate_emissions_root_ordered`.
+    pub fn calculate_emissions_root_with_mode(
+        &self,
+        mode: EmissionsRootMode,
+    ) -> definitions::Hash32 {
+        match mode {
+            EmissionsRootMode::Sorted => self.calculate_emissions_root(),
+            EmissionsRootMode::Ordered => self.calculate_emissions_root_ordered(),
+        }
+    }
+
+    /// Computes an insertion-order `EMISSIONS_ROOT`: the Merkle root of
+    /// `COMPUTE_INDEXED_EMISSION_HASH(i, ..)` for each emission `i`, unsorted,
+    /// so reordering the emissions changes the root.
+    ///
+    /// Blocks from `ORDERED_EMISSIONS_VERSION` on commit to this order, with
+    /// the leaf fields also length-prefixed (see
+    /// `calculate_emissions_root_for_version`). It lets applications commit to
+    /// a proposer-chosen emission order (e.g. priority).
+    pub fn calculate_emissions_root_ordered(&self) -> definitions::Hash32 {
+        let leaves: Vec<definitions::Hash32> = self
+            .emissions
+            .iter()
+            .enumerate()
+            .map(|(i, e)| definitions::COMPUTE_INDEXED_EMISSION_HASH(i as u64, &e.pubkey, e.weight))
+            .collect();
+        definitions::MERKLE_ROOT(&leaves)
+    }
+
+    /// The sorted `EMISSIONS_ROOT` leaves together with their root, so proofs
+    /// can be built with `MERKLE_PROOF(&leaves, i)`.
+    ///
+    /// Uses the leaves of block versions before `LENGTH_PREFIXED_FIELDS_VERSION`;
+    /// see `emissions_tree_for_version` for later blocks.
+    pub fn emissions_tree(&self) -> (Vec<definitions::Hash32>, definitions::Hash32) {
+        let mut leaves = Vec::new();
+        self.emission_leaves_into(&Sha256Hasher, FieldEncoding::Concatenated, &mut leaves);
+        let root = definitions::MERKLE_ROOT(&leaves);
+        (leaves, root)
+    }
+
+    /// The `EMISSIONS_ROOT` leaves of block `version`, in tree order, together
+    /// with their root (`calculate_emissions_root_for_version(version)`).
+    ///
+    /// From `ORDERED_EMISSIONS_VERSION` leaf `i` is emission `i` in insertion
+    /// order, so proofs index `emissions` directly.
+    pub fn emissions_tree_for_version(
+        &self,
+        version: u32,
+    ) -> (Vec<definitions::Hash32>, definitions::Hash32) {
+        let mut leaves = Vec::new();
+        emission_leaves_for_version_into(&Sha256Hasher, &self.emissions, version, &mut leaves);
+        let root = definitions::MERKLE_ROOT(&leaves);
+        (leaves, root)
+    }
+
+    /// The emissions in `EMISSIONS_ROOT` leaf order (ascending by per-emission
+    /// hash, ties by pubkey then weight), so leaf `i` of the tree is
+    /// `emissions_in_merkle_order()[i].calculate_root()`.
+    ///
+    /// This is the order of block versions before `LENGTH_PREFIXED_FIELDS_VERSION`;
+    /// see `emissions_in_merkle_order_for_version`.
+    pub fn emissions_in_merkle_order(&self) -> Vec<&Emission> {
+        merkle_order_with(&Sha256Hasher, FieldEncoding::Concatenated, &self.emissions)
+            .into_iter()
+            .map(|(_, e)| e)
+            .collect()
+    }
+
+    /// The emissions in the `EMISSIONS_ROOT` leaf order of block `version`, so
+    /// leaf `i` of `emissions_tree_for_version(version)` is emission `i` here.
+    ///
+    /// Before `ORDERED_EMISSIONS_VERSION` this is the hash order of the
+    /// version's leaves (which changes at `LENGTH_PREFIXED_FIELDS_VERSION`);
+    /// from it on, insertion order.
+    pub fn emissions_in_merkle_order_for_version(&self, version: u32) -> Vec<&Emission> {
+        match EmissionsRootMode::for_version(version) {
+            EmissionsRootMode::Sorted => merkle_order_with(
+                &Sha256Hasher,
+                FieldEncoding::for_version(version),
+                &self.emissions,
+            )
+            .into_iter()
+            .map(|(_, e)| e)
+            .collect(),
+            EmissionsRootMode::Ordered => self.emissions.iter().collect(),
+        }
+    }
+
+    /// Replaces `out` with the `DATA_ROOT` leaves for `mode`, in tree order.
+    pub(crate) fn data_leaves_into<H: RootHasher + ?Sized>(
+        &self,
+        hasher: &H,
+        mode: DataRootMode,
+        out: &mut Vec<definitions::Hash32>,
+    ) {
+        data_leaves_into(hasher, &self.data, mode, out);
+    }
+
+    /// Replaces `out` with the `EMISSIONS_ROOT` leaves under `encoding`, sorted by hash.
+    pub(crate) fn emission_leaves_into<H: RootHasher + ?Sized>(
+        &self,
+        hasher: &H,
+        encoding: FieldEncoding,
+        out: &mut Vec<definitions::Hash32>,
+    ) {
+        emission_leaves_into(hasher, &self.emissions, encoding, out);
+    }
+
+    /// Index in `emissions` (insertion order) of the first emission paying
+    /// `pubkey`.
+    ///
+    /// This is not the `EMISSIONS_ROOT` leaf position, which follows the sorted
+    /// order of `emissions_in_merkle_order`; map through that before building a
+    /// proof.
+    pub fn emission_index_by_pubkey(&self, pubkey: &[u8; 48]) -> Option<usize> {
+        self.emissions.iter().position(|e| e.pubkey == *pubkey)
+    }
+
+    /// The `EMISSIONS_ROOT` of block `version` as if every emission paying
+    /// `pubkey` were removed, e.g. to gauge the effect of slashing a recipient.
+    /// The body is unchanged; an absent `pubkey` yields
+    /// `calculate_emissions_root_for_version(version)`.
+    ///
+    /// Under insertion-order roots the emissions after a removed one move up a
+    /// position, so their leaves change too.
+    pub fn emissions_root_without(&self, pubkey: &[u8; 48], version: u32) -> definitions::Hash32 {
+        L2BlockBody::emissions_only(
+            self.emissions
+                .iter()
+                .filter(|e| e.pubkey != *pubkey)
+                .cloned()
+                .collect(),
+        )
+        .calculate_emissions_root_for_version(version)
+    }
+
+    /// Whether the body has neither data nor emissions.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty() && self.emissions.is_empty()
+    }
+
+    /// `data` as a borrowed slice.
+    pub fn data_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The first emission (in insertion order) paying `pubkey`.
+    pub fn get_emission(&self, pubkey: &[u8; 48]) -> Option<&Emission> {
+        self.emission_index_by_pubkey(pubkey)
+            .map(|i| &self.emissions[i])
+    }
+
+    /// Returns the first pubkey that appears in more than one emission, if any.
+    ///
+    /// Scans `emissions` in insertion order by pubkey (not by leaf hash), so two
+    /// records for the same key are found even when their weights differ.
+    pub fn find_duplicate_emission_pubkey(&self) -> Option<[u8; 48]> {
+        let mut seen = std::collections::HashSet::with_capacity(self.emissions.len());
+        self.emissions
+            .iter()
+            .find(|e| !seen.insert(e.pubkey))
+            .map(|e| e.pubkey)
+    }
+
+    /// Total emission weight per pubkey, summing repeated recipients (e.g. a
+    /// proposer topped up by `extra_emissions`). Ordered by pubkey.
+    pub fn emission_totals(&self) -> Result<BTreeMap<[u8; 48], u64>, BodyError> {
+        let mut totals = BTreeMap::new();
+        for e in &self.emissions {
+            let total: &mut u64 = totals.entry(e.pubkey).or_default();
+            *total = total
+                .checked_add(e.weight)
+                .ok_or(BodyError::WeightOverflow { pubkey: e.pubkey })?;
+        }
+        Ok(totals)
+    }
+
     /// Computes the overall `BODY_ROOT` from the two subroots.
+    ///
+    /// Uses the legacy per-byte data root; equal to `calculate_root_for_version(1)`.
     pub fn calculate_root(&self) -> definitions::Hash32 {
         let d = self.calculate_data_root();
         let e = self.calculate_emissions_root();
         definitions::COMPUTE_BODY_ROOT(&d, &e)
     }
+
+    /// `calculate_root_for_version(version)` as a `0x`-prefixed lowercase hex
+    /// string, so it matches the `body_root` of a header at that version.
+    pub fn calculate_root_hex(&self, version: u32) -> String {
+        crate::serde_hex::encode_lower_hex_prefixed(&self.calculate_root_for_version(version))
+    }
+
+    /// Computes the `BODY_ROOT` under the rules of the given block version: its
+    /// data root mode, from `LABELED_BODY_ROOT_VERSION` labeled subroots, from
+    /// `LENGTH_PREFIXED_FIELDS_VERSION` length-prefixed leaf fields, and from
+    /// `ORDERED_EMISSIONS_VERSION` an insertion-order emissions root.
+    pub fn calculate_root_for_version(&self, version: u32) -> definitions::Hash32 {
+        self.calculate_root_for_version_with(
+            &Sha256Hasher,
+            version,
+            &mut Vec::new(),
+            &mut Vec::new(),
+        )
+    }
+
+    /// `calculate_root_for_version` under `hasher`, using caller-owned leaf and
+    /// Merkle level buffers so batches of bodies reuse the same allocations.
+    pub(crate) fn calculate_root_for_version_with<H: RootHasher + ?Sized>(
+        &self,
+        hasher: &H,
+        version: u32,
+        leaves: &mut Vec<definitions::Hash32>,
+        scratch: &mut Vec<definitions::Hash32>,
+    ) -> definitions::Hash32 {
+        self.data_leaves_into(hasher, DataRootMode::for_version(version), leaves);
+        let d = definitions::merkle_root_in(hasher, leaves, scratch);
+        emission_leaves_for_version_into(hasher, &self.emissions, version, leaves);
+        let e = definitions::merkle_root_in(hasher, leaves, scratch);
+        definitions::body_root_for_version_with(hasher, version, &d, &e)
+    }
+}
+
+/// Incremental body builder that hashes leaves as items are pushed.
+///
+/// Leaves follow the rules of the builder's block version (`DataRootMode`,
+/// `EmissionsRootMode` and `FieldEncoding::for_version`); `new` builds under
+/// version 1. Chunked data is hashed one `DATA_CHUNK_SIZE` chunk at a time as
+/// it fills. `finish` only hashes a trailing partial chunk, sorts the leaves
+/// where the version sorts them and Merkleizes them, so the data and emissions
+/// are never hashed a second time. The resulting root equals
+/// `L2BlockBody::calculate_root_for_version(version)` for the same contents.
+#[derive(Clone, Debug)]
+pub struct L2BlockBodyBuilder {
+    version: u32,
+    data: Vec<u8>,
+    emissions: Vec<Emission>,
+    data_leaves: Vec<definitions::Hash32>,
+    emission_leaves: Vec<definitions::Hash32>,
+}
+
+impl L2BlockBodyBuilder {
+    /// Create an empty builder for version 1 bodies, whose root is
+    /// `L2BlockBody::calculate_root()`.
+    pub fn new() -> Self {
+        Self::for_version(1)
+    }
+
+    /// Create an empty builder hashing leaves under the rules of block
+    /// `version`.
+    pub fn for_version(version: u32) -> Self {
+        L2BlockBodyBuilder {
+            version,
+            data: Vec::new(),
+            emissions: Vec::new(),
+            data_leaves: Vec::new(),
+            emission_leaves: Vec::new(),
+        }
+    }
+
+    /// The block version whose rules the builder applies.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Append one data byte.
+    pub fn push_data_byte(&mut self, byte: u8) -> &mut Self {
+        self.extend_data(&[byte])
+    }
+
+    /// Append every byte of `bytes`.
+    pub fn extend_data(&mut self, bytes: &[u8]) -> &mut Self {
+        let start = self.data.len();
+        self.data.extend_from_slice(bytes);
+        let hasher = &Sha256Hasher;
+        match DataRootMode::for_version(self.version) {
+            DataRootMode::PerByteSorted => self.data_leaves.extend(
+                bytes
+                    .iter()
+                    .map(|b| definitions::data_hash_with(hasher, *b)),
+            ),
+            DataRootMode::PerByteIndexed => {
+                self.data_leaves
+                    .extend(bytes.iter().enumerate().map(|(i, b)| {
+                        definitions::indexed_data_hash_with(hasher, (start + i) as u64, *b)
+                    }))
+            }
+            DataRootMode::Chunked => {
+                // Hash every chunk this call completed; a partial tail waits.
+                let full = self.data.len() / definitions::DATA_CHUNK_SIZE;
+                for i in self.data_leaves.len()..full {
+                    let chunk = &self.data
+                        [i * definitions::DATA_CHUNK_SIZE..(i + 1) * definitions::DATA_CHUNK_SIZE];
+                    self.data_leaves
+                        .push(definitions::data_chunk_hash_with(hasher, i as u64, chunk));
+                }
+            }
+        }
+        self
+    }
+
+    /// Append one emission.
+    pub fn push_emission(&mut self, emission: Emission) -> &mut Self {
+        let encoding = FieldEncoding::for_version(self.version);
+        let leaf = match EmissionsRootMode::for_version(self.version) {
+            EmissionsRootMode::Sorted => definitions::emission_hash_with(
+                &Sha256Hasher,
+                encoding,
+                &emission.pubkey,
+                emission.weight,
+            ),
+            EmissionsRootMode::Ordered => definitions::indexed_emission_hash_with(
+                &Sha256Hasher,
+                encoding,
+                self.emissions.len() as u64,
+                &emission.pubkey,
+                emission.weight,
+            ),
+        };
+        self.emission_leaves.push(leaf);
+        self.emissions.push(emission);
+        self
+    }
+
+    /// Produce the body and its `BODY_ROOT` from the running leaf hashes.
+    pub fn finish(self) -> (L2BlockBody, definitions::Hash32) {
+        let mut data_leaves = self.data_leaves;
+        let mut emission_leaves = self.emission_leaves;
+        match DataRootMode::for_version(self.version) {
+            DataRootMode::PerByteSorted => data_leaves.sort_unstable(),
+            DataRootMode::PerByteIndexed => {}
+            DataRootMode::Chunked => {
+                let done = data_leaves.len() * definitions::DATA_CHUNK_SIZE;
+                if done < self.data.len() {
+                    data_leaves.push(definitions::data_chunk_hash_with(
+                        &Sha256Hasher,
+                        data_leaves.len() as u64,
+                        &self.data[done..],
+                    ));
+                }
+            }
+        }
+        // Sorting the leaves alone suffices, as in `compute_emissions_root_from_iter`.
+        if EmissionsRootMode::for_version(self.version) == EmissionsRootMode::Sorted {
+            emission_leaves.sort_unstable();
+        }
+        let root = definitions::COMPUTE_BODY_ROOT_FOR_VERSION(
+            self.version,
+            &definitions::MERKLE_ROOT(&data_leaves),
+            &definitions::MERKLE_ROOT(&emission_leaves),
+        );
+        let body = L2BlockBody {
+            data: self.data,
+            emissions: self.emissions,
+        };
+        (body, root)
+    }
+}
+
+impl Default for L2BlockBodyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An `EMISSIONS_ROOT` tree that stays current as emissions are pushed, for
+/// packing a body one emission at a time under a size budget.
+///
+/// Leaves follow the rules of the tree's block version and every level is
+/// retained. `push` places the new leaf and rehashes only the nodes whose span
+/// starts at or after it; nodes to its left are never touched. `root()` is
+/// then a lookup.
+///
+/// From `ORDERED_EMISSIONS_VERSION` leaves are in insertion order, so a push
+/// appends and rehashes a single path: O(log n). Before it, leaves are sorted
+/// by hash, so a new leaf lands at a uniformly random position and shifts every
+/// leaf after it. No fixed-position layout exists for that tree, and an
+/// average push rehashes about half of it: O(n) per push and O(n²) for n
+/// emissions, against about 2n hashes for one root at the end. For those
+/// versions, use this tree only when the root is needed after each push.
+#[derive(Clone, Debug)]
+pub struct IncrementalEmissionsTree {
+    version: u32,
+    /// Emission leaves in tree order.
+    leaves: Vec<definitions::Hash32>,
+    /// `levels[0]` holds hashed leaf nodes; the last level holds the root.
+    /// Odd levels are stored unpadded, as in `MerkleTree`.
+    levels: Vec<Vec<definitions::Hash32>>,
+}
+
+impl IncrementalEmissionsTree {
+    /// Create an empty tree for version 1 bodies, whose root is
+    /// `calculate_emissions_root`.
+    pub fn new() -> Self {
+        Self::for_version(1)
+    }
+
+    /// Create an empty tree whose root is
+    /// `calculate_emissions_root_for_version(version)`.
+    pub fn for_version(version: u32) -> Self {
+        IncrementalEmissionsTree {
+            version,
+            leaves: Vec::new(),
+            levels: Vec::new(),
+        }
+    }
+
+    /// Add `emission` and update the affected nodes.
+    ///
+    /// Rehashes every node from the new leaf's position rightward: one per
+    /// level when it is appended (always, from `ORDERED_EMISSIONS_VERSION`),
+    /// n/2 nodes on average for a sorted insert.
+    pub fn push(&mut self, emission: &Emission) {
+        let encoding = FieldEncoding::for_version(self.version);
+        let (leaf, pos) = match EmissionsRootMode::for_version(self.version) {
+            EmissionsRootMode::Sorted => {
+                let leaf = definitions::emission_hash_with(
+                    &Sha256Hasher,
+                    encoding,
+                    &emission.pubkey,
+                    emission.weight,
+                );
+                (leaf, self.leaves.partition_point(|l| *l <= leaf))
+            }
+            EmissionsRootMode::Ordered => {
+                let index = self.leaves.len();
+                let leaf = definitions::indexed_emission_hash_with(
+                    &Sha256Hasher,
+                    encoding,
+                    index as u64,
+                    &emission.pubkey,
+                    emission.weight,
+                );
+                (leaf, index)
+            }
+        };
+        self.leaves.insert(pos, leaf);
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        self.levels[0].insert(pos, definitions::hash_leaf(&leaf));
+
+        let mut start = pos;
+        let mut depth = 0;
+        while self.levels[depth].len() > 1 {
+            if self.levels.len() == depth + 1 {
+                self.levels.push(Vec::new());
+            }
+            let (lower, upper) = self.levels.split_at_mut(depth + 1);
+            let (level, parents) = (&lower[depth], &mut upper[0]);
+            let first = start / 2;
+            parents.truncate(first);
+            parents.extend(
+                level[2 * first..]
+                    .chunks(2)
+                    .map(|pair| definitions::hash_node(&pair[0], pair.get(1).unwrap_or(&pair[0]))),
+            );
+            start = first;
+            depth += 1;
+        }
+        self.levels.truncate(depth + 1);
+    }
+
+    /// The current `EMISSIONS_ROOT`; equal to
+    /// `calculate_emissions_root_for_version` over the pushed emissions.
+    pub fn root(&self) -> definitions::Hash32 {
+        match self.levels.last() {
+            Some(top) => top[0],
+            None => definitions::EMPTY_MERKLE_ROOT(),
+        }
+    }
+
+    /// The leaves in tree order, i.e. `emissions_tree_for_version(version).0`
+    /// for the pushed emissions.
+    pub fn leaves(&self) -> &[definitions::Hash32] {
+        &self.leaves
+    }
+
+    /// Number of pushed emissions.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Whether no emission has been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+}
+
+impl Default for IncrementalEmissionsTree {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Errors that can be returned by body-level operations.
@@ -58,6 +749,14 @@ pub enum BodyError {
     /// Placeholder for future validation errors.
     #[error("body error: {0}")]
     Generic(String),
+
+    /// The data payload exceeds the caller's size limit.
+    #[error("data too large: {len} bytes exceeds limit of {max}")]
+    DataTooLarge { len: usize, max: usize },
+
+    /// Summing the emission weights of one pubkey overflowed `u64`.
+    #[error("emission weight total for 0x{} overflows u64", hex::encode(.pubkey))]
+    WeightOverflow { pubkey: [u8; 48] },
 }
 
 #[cfg(test)]
@@ -66,7 +765,495 @@ mod tests {
     use crate::emission::Emission;
 
     #[test]
-    fn data_root_does_not_depend_on_input_order() {
+    fn emissions_root_without_drops_recipient() {
+        let e = |p: u8, weight: u64| Emission {
+            pubkey: [p; 48],
+            weight,
+        };
+        let body = L2BlockBody::emissions_only(vec![e(1, 10), e(2, 20), e(1, 5)]);
+        let full = body.calculate_emissions_root();
+        let without = body.emissions_root_without(&[1u8; 48], 1);
+        assert_ne!(without, full);
+        assert_eq!(without, compute_emissions_root(&[e(2, 20)]));
+        assert_eq!(body.emissions_root_without(&[9u8; 48], 1), full);
+        assert_eq!(body.emissions.len(), 3);
+
+        for v in [
+            definitions::LENGTH_PREFIXED_FIELDS_VERSION,
+            definitions::ORDERED_EMISSIONS_VERSION,
+        ] {
+            let full = body.calculate_emissions_root_for_version(v);
+            assert_eq!(body.emissions_root_without(&[9u8; 48], v), full);
+            assert_eq!(
+                body.emissions_root_without(&[1u8; 48], v),
+                L2BlockBody::emissions_only(vec![e(2, 20)]).calculate_emissions_root_for_version(v)
+            );
+        }
+    }
+
+    #[test]
+    fn labeled_body_root_is_gated_by_version() {
+        let body = L2BlockBody {
+            data: vec![1, 2, 3],
+            emissions: vec![Emission {
+                pubkey: [4u8; 48],
+                weight: 5,
+            }],
+        };
+        let d = body.calculate_data_root_with_mode(DataRootMode::Chunked);
+        let e = body.calculate_emissions_root();
+        let v = definitions::LABELED_BODY_ROOT_VERSION;
+        assert_eq!(
+            body.calculate_root_for_version(v - 1),
+            definitions::COMPUTE_BODY_ROOT(&d, &e)
+        );
+        assert_eq!(
+            body.calculate_root_for_version(v),
+            definitions::COMPUTE_BODY_ROOT_LABELED(&d, &e)
+        );
+    }
+
+    #[test]
+    fn from_iter_roots_match_slice_roots() {
+        let data: Vec<u8> = (0..200u16).map(|i| (i * 7 % 256) as u8).collect();
+        let (head, tail) = data.split_at(77);
+        // A non-slice source: two buffers chained, yielding owned bytes.
+        let chained = head.iter().chain(tail).copied();
+        assert_eq!(
+            compute_data_root_from_iter(chained),
+            compute_data_root(&data)
+        );
+        assert_eq!(
+            compute_data_root_from_iter(data.iter().copied()),
+            L2BlockBody::data_only(data.clone()).calculate_data_root()
+        );
+        assert_eq!(
+            compute_data_root_from_iter(std::iter::empty()),
+            definitions::EMPTY_MERKLE_ROOT()
+        );
+
+        let emissions: Vec<Emission> = (0..9u8)
+            .map(|i| Emission {
+                pubkey: [i % 4; 48],
+                weight: u64::from(i),
+            })
+            .collect();
+        let body = L2BlockBody::emissions_only(emissions.clone());
+        let reversed = emissions.iter().rev();
+        assert_eq!(
+            compute_emissions_root_from_iter(reversed),
+            body.calculate_emissions_root()
+        );
+        assert_eq!(
+            compute_emissions_root_from_iter(emissions.iter()),
+            compute_emissions_root(&emissions)
+        );
+    }
+
+    #[test]
+    fn empty_body_roots() {
+        let empty = L2BlockBody::data_only(vec![]);
+        assert!(empty.is_empty());
+        assert!(!L2BlockBody::data_only(vec![0]).is_empty());
+        let emission = Emission {
+            pubkey: [1u8; 48],
+            weight: 0,
+        };
+        assert!(!L2BlockBody::emissions_only(vec![emission]).is_empty());
+
+        let empty_root = definitions::EMPTY_MERKLE_ROOT();
+        assert_eq!(empty.calculate_data_root(), empty_root);
+        assert_eq!(
+            empty.calculate_data_root_with_mode(DataRootMode::Chunked),
+            empty_root
+        );
+        assert_eq!(empty.calculate_emissions_root(), empty_root);
+        assert_eq!(
+            empty.calculate_root(),
+            definitions::COMPUTE_BODY_ROOT(&empty_root, &empty_root)
+        );
+    }
+
+    #[test]
+    fn data_bytes_borrows_data() {
+        let body = L2BlockBody::data_only(vec![1, 2, 3]);
+        assert_eq!(body.data_bytes(), &[1, 2, 3]);
+        assert!(std::ptr::eq(body.data_bytes().as_ptr(), body.data.as_ptr()));
+    }
+
+    #[test]
+    fn single_sided_constructors_match_direct_bodies() {
+        let data = L2BlockBody::data_only(vec![1, 2, 3]);
+        let direct = L2BlockBody {
+            data: vec![1, 2, 3],
+            emissions: vec![],
+        };
+        assert_eq!((data.data.len(), data.emissions.len()), (3, 0));
+        assert_eq!(data.calculate_root(), direct.calculate_root());
+        assert_eq!(
+            data.calculate_emissions_root(),
+            definitions::EMPTY_MERKLE_ROOT()
+        );
+
+        let emissions = vec![Emission {
+            pubkey: [4u8; 48],
+            weight: 2,
+        }];
+        let only = L2BlockBody::emissions_only(emissions.clone());
+        let direct = L2BlockBody {
+            data: vec![],
+            emissions,
+        };
+        assert_eq!((only.data.len(), only.emissions.len()), (0, 1));
+        assert_eq!(only.calculate_root(), direct.calculate_root());
+        assert_eq!(only.calculate_data_root(), definitions::EMPTY_MERKLE_ROOT());
+    }
+
+    #[test]
+    fn free_root_functions_match_body_methods() {
+        let data = vec![3u8, 1, 4, 1, 5, 9, 2, 6];
+        let emissions = vec![
+            Emission {
+                pubkey: [2u8; 48],
+                weight: 7,
+            },
+            Emission {
+                pubkey: [1u8; 48],
+                weight: 9,
+            },
+        ];
+        for (data, emissions) in [
+            (data.clone(), emissions.clone()),
+            (Vec::new(), Vec::new()),
+            (data, Vec::new()),
+            (Vec::new(), emissions),
+        ] {
+            let body = L2BlockBody {
+                data: data.clone(),
+                emissions: emissions.clone(),
+            };
+            assert_eq!(compute_data_root(&data), body.calculate_data_root());
+            assert_eq!(
+                compute_emissions_root(&emissions),
+                body.calculate_emissions_root()
+            );
+            assert_eq!(compute_emissions_root(&emissions), body.emissions_tree().1);
+        }
+    }
+
+    #[test]
+    fn incremental_emissions_tree_matches_batch_root() {
+        let empty = IncrementalEmissionsTree::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.root(), compute_emissions_root(&[]));
+
+        for seed in 1..=6u64 {
+            let mut rng = crate::testing::TestRng::new(seed);
+            let mut tree = IncrementalEmissionsTree::new();
+            let mut pushed = Vec::new();
+            for _ in 0..40 {
+                let r = rng.next_u64();
+                let e = Emission {
+                    pubkey: [(r % 16) as u8; 48],
+                    // Small weight range so identical emissions recur.
+                    weight: (r >> 8) % 4,
+                };
+                tree.push(&e);
+                pushed.push(e);
+                assert_eq!(tree.root(), compute_emissions_root(&pushed), "seed {seed}");
+            }
+            assert_eq!(tree.len(), pushed.len());
+            assert_eq!(
+                tree.leaves(),
+                L2BlockBody::emissions_only(pushed).emissions_tree().0
+            );
+        }
+    }
+
+    #[test]
+    fn incremental_emissions_tree_follows_block_version() {
+        for version in [
+            definitions::LENGTH_PREFIXED_FIELDS_VERSION,
+            definitions::ORDERED_EMISSIONS_VERSION,
+        ] {
+            let mut rng = crate::testing::TestRng::new(u64::from(version));
+            let mut tree = IncrementalEmissionsTree::for_version(version);
+            assert_eq!(tree.root(), definitions::EMPTY_MERKLE_ROOT());
+            let mut body = L2BlockBody::emissions_only(vec![]);
+            for _ in 0..40 {
+                let r = rng.next_u64();
+                let e = Emission {
+                    pubkey: [(r % 16) as u8; 48],
+                    weight: (r >> 8) % 4,
+                };
+                tree.push(&e);
+                body.emissions.push(e);
+                assert_eq!(
+                    tree.root(),
+                    body.calculate_emissions_root_for_version(version),
+                    "v{version}"
+                );
+            }
+            assert_eq!(tree.leaves(), body.emissions_tree_for_version(version).0);
+        }
+    }
+
+    #[test]
+    fn with_data_checked_enforces_limit() {
+        let body = L2BlockBody::with_data_checked(vec![7u8; 16], vec![], 16).unwrap();
+        assert_eq!(body.data.len(), 16);
+        assert!(L2BlockBody::with_data_checked(vec![], vec![], 0).is_ok());
+        match L2BlockBody::with_data_checked(vec![7u8; 17], vec![], 16) {
+            Err(BodyError::DataTooLarge { len, max }) => assert_eq!((len, max), (17, 16)),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn emission_lookup_by_pubkey_uses_insertion_order() {
+        let body = L2BlockBody::emissions_only(vec![
+            Emission {
+                pubkey: [3u8; 48],
+                weight: 1,
+            },
+            Emission {
+                pubkey: [1u8; 48],
+                weight: 2,
+            },
+            Emission {
+                pubkey: [3u8; 48],
+                weight: 4,
+            },
+        ]);
+        assert_eq!(body.emission_index_by_pubkey(&[1u8; 48]), Some(1));
+        assert_eq!(body.get_emission(&[1u8; 48]), Some(&body.emissions[1]));
+        // Duplicates resolve to the first record.
+        assert_eq!(body.emission_index_by_pubkey(&[3u8; 48]), Some(0));
+        assert_eq!(body.get_emission(&[3u8; 48]).map(|e| e.weight), Some(1));
+        assert_eq!(body.emission_index_by_pubkey(&[2u8; 48]), None);
+        assert_eq!(body.get_emission(&[2u8; 48]), None);
+    }
+
+    #[test]
+    fn emission_totals_sum_by_pubkey() {
+        let body = L2BlockBody {
+            data: vec![],
+            emissions: vec![
+                Emission {
+                    pubkey: [2u8; 48],
+                    weight: 5,
+                },
+                Emission {
+                    pubkey: [1u8; 48],
+                    weight: 12,
+                },
+                Emission {
+                    pubkey: [2u8; 48],
+                    weight: 7,
+                },
+            ],
+        };
+        let totals = body.emission_totals().unwrap();
+        assert_eq!(
+            totals.into_iter().collect::<Vec<_>>(),
+            vec![([1u8; 48], 12), ([2u8; 48], 12)]
+        );
+    }
+
+    #[test]
+    fn emissions_in_merkle_order_matches_leaf_sort() {
+        let body = L2BlockBody {
+            data: vec![],
+            emissions: (0..16u8)
+                .map(|i| Emission {
+                    pubkey: [i; 48],
+                    weight: u64::from(i),
+                })
+                .collect(),
+        };
+        let ordered = body.emissions_in_merkle_order();
+        let ordered_leaves: Vec<definitions::Hash32> =
+            ordered.iter().map(|e| e.calculate_root()).collect();
+        let mut leaves = Vec::new();
+        body.emission_leaves_into(&Sha256Hasher, FieldEncoding::Concatenated, &mut leaves);
+        assert_eq!(ordered_leaves, leaves);
+        assert_eq!(
+            definitions::MERKLE_ROOT(&ordered_leaves),
+            body.calculate_emissions_root()
+        );
+        assert_ne!(
+            ordered.iter().map(|e| e.pubkey[0]).collect::<Vec<_>>(),
+            (0..16u8).collect::<Vec<_>>(),
+            "hash order should differ from insertion order for this sample"
+        );
+    }
+
+    #[test]
+    fn emissions_in_merkle_order_follows_block_version() {
+        let body = L2BlockBody::emissions_only(
+            (0..16u8)
+                .map(|i| Emission {
+                    pubkey: [i; 48],
+                    weight: u64::from(i),
+                })
+                .collect(),
+        );
+        for version in [
+            1,
+            definitions::LENGTH_PREFIXED_FIELDS_VERSION,
+            definitions::ORDERED_EMISSIONS_VERSION,
+        ] {
+            let encoding = FieldEncoding::for_version(version);
+            let ordered = body.emissions_in_merkle_order_for_version(version);
+            let leaves: Vec<definitions::Hash32> = ordered
+                .iter()
+                .enumerate()
+                .map(|(i, e)| match EmissionsRootMode::for_version(version) {
+                    EmissionsRootMode::Sorted => definitions::emission_hash_with(
+                        &Sha256Hasher,
+                        encoding,
+                        &e.pubkey,
+                        e.weight,
+                    ),
+                    EmissionsRootMode::Ordered => definitions::indexed_emission_hash_with(
+                        &Sha256Hasher,
+                        encoding,
+                        i as u64,
+                        &e.pubkey,
+                        e.weight,
+                    ),
+                })
+                .collect();
+            assert_eq!(
+                leaves,
+                body.emissions_tree_for_version(version).0,
+                "v{version}"
+            );
+        }
+        assert_eq!(
+            body.emissions_in_merkle_order_for_version(1),
+            body.emissions_in_merkle_order()
+        );
+        assert_eq!(
+            body.emissions_in_merkle_order_for_version(definitions::ORDERED_EMISSIONS_VERSION),
+            body.emissions.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn merkle_order_breaks_hash_ties_by_pubkey_then_weight() {
+        // Every emission hashes to the same leaf under this hasher.
+        struct Colliding;
+        impl RootHasher for Colliding {
+            fn hash(&self, _parts: &[&[u8]]) -> definitions::Hash32 {
+                [0u8; 32]
+            }
+        }
+        let e = |p: u8, weight: u64| Emission {
+            pubkey: [p; 48],
+            weight,
+        };
+        let expected = vec![e(1, 2), e(1, 5), e(2, 1), e(3, 0)];
+        for input in [
+            vec![e(3, 0), e(1, 5), e(2, 1), e(1, 2)],
+            vec![e(1, 5), e(1, 2), e(3, 0), e(2, 1)],
+            expected.clone(),
+        ] {
+            let ordered: Vec<Emission> =
+                merkle_order_with(&Colliding, FieldEncoding::Concatenated, &input)
+                    .into_iter()
+                    .map(|(_, e)| e.clone())
+                    .collect();
+            assert_eq!(ordered, expected);
+        }
+    }
+
+    #[test]
+    fn emissions_tree_leaves_reproduce_root() {
+        let body = L2BlockBody {
+            data: vec![],
+            emissions: (0..5u8)
+                .map(|i| Emission {
+                    pubkey: [i; 48],
+                    weight: 1,
+                })
+                .collect(),
+        };
+        let (leaves, root) = body.emissions_tree();
+        assert!(leaves.is_sorted());
+        assert_eq!(definitions::MERKLE_ROOT(&leaves), root);
+        assert_eq!(body.calculate_emissions_root(), root);
+        let proof = definitions::MERKLE_PROOF(&leaves, 3).unwrap();
+        assert!(definitions::MERKLE_VERIFY(&leaves[3], &proof, &root));
+        assert_eq!(body.emissions_tree_for_version(1), (leaves, root));
+    }
+
+    #[test]
+    fn emissions_tree_for_version_proves_v7_block_emissions() {
+        let v = definitions::ORDERED_EMISSIONS_VERSION;
+        let attesters = [[2u8; 48], [3u8; 48]];
+        let cfg = crate::emission_config::ConsensusEmissionConfig::new(12, 88);
+        let block = crate::block::DigL2Block::build(&crate::block::BuildL2BlockArgs {
+            version: v,
+            network_id: [0xabu8; 32].into(),
+            epoch: 1,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![1, 2, 3],
+            extra_emissions: vec![],
+            attester_pubkeys: &attesters,
+            cfg: &cfg,
+            timestamp: 1,
+            extra_data: [0u8; 32],
+        })
+        .unwrap();
+        let body = &block.body;
+
+        let (leaves, root) = body.emissions_tree_for_version(v);
+        assert_eq!(root, body.calculate_emissions_root_for_version(v));
+        assert_ne!(root, body.emissions_tree().1);
+        for (i, e) in body.emissions.iter().enumerate() {
+            let leaf = definitions::indexed_emission_hash_with(
+                &Sha256Hasher,
+                FieldEncoding::for_version(v),
+                i as u64,
+                &e.pubkey,
+                e.weight,
+            );
+            assert_eq!(leaves[i], leaf);
+            let proof = definitions::MERKLE_PROOF(&leaves, i).unwrap();
+            assert!(definitions::MERKLE_VERIFY(&leaf, &proof, &root));
+        }
+        assert_eq!(block.root_breakdown().emissions_root, root);
+    }
+
+    #[test]
+    fn emission_totals_overflow() {
+        let body = L2BlockBody {
+            data: vec![],
+            emissions: vec![
+                Emission {
+                    pubkey: [3u8; 48],
+                    weight: u64::MAX,
+                },
+                Emission {
+                    pubkey: [4u8; 48],
+                    weight: 1,
+                },
+                Emission {
+                    pubkey: [3u8; 48],
+                    weight: 1,
+                },
+            ],
+        };
+        assert!(matches!(
+            body.emission_totals(),
+            Err(BodyError::WeightOverflow { pubkey }) if pubkey == [3u8; 48]
+        ));
+    }
+
+    #[test]
+    fn legacy_data_root_does_not_depend_on_input_order() {
         let b1 = L2BlockBody {
             data: vec![3, 1, 2],
             emissions: vec![],
@@ -75,9 +1262,37 @@ mod tests {
             data: vec![2, 3, 1],
             emissions: vec![],
         };
+        assert_eq!(
+            b1.calculate_data_root_with_mode(DataRootMode::PerByteSorted),
+            b2.calculate_data_root_with_mode(DataRootMode::PerByteSorted)
+        );
         assert_eq!(b1.calculate_data_root(), b2.calculate_data_root());
     }
 
+    #[test]
+    fn ordered_data_roots_depend_on_input_order() {
+        let b1 = L2BlockBody {
+            data: vec![1, 2, 3],
+            emissions: vec![],
+        };
+        let b2 = L2BlockBody {
+            data: vec![3, 2, 1],
+            emissions: vec![],
+        };
+        for mode in [DataRootMode::PerByteIndexed, DataRootMode::Chunked] {
+            assert_ne!(
+                b1.calculate_data_root_with_mode(mode),
+                b2.calculate_data_root_with_mode(mode),
+                "{mode:?} ignored byte order"
+            );
+        }
+        let v = definitions::CHUNKED_DATA_VERSION;
+        assert_ne!(
+            b1.calculate_root_for_version(v),
+            b2.calculate_root_for_version(v)
+        );
+    }
+
     #[test]
     fn emissions_root_does_not_depend_on_input_order() {
         let e1 = Emission {
@@ -103,6 +1318,254 @@ mod tests {
         assert_eq!(b1.calculate_emissions_root(), b2.calculate_emissions_root());
     }
 
+    #[test]
+    fn ordered_emissions_root_depends_on_input_order() {
+        let a = Emission {
+            pubkey: [1u8; 48],
+            weight: 5,
+        };
+        let b = Emission {
+            pubkey: [2u8; 48],
+            weight: 9,
+        };
+        let ab = L2BlockBody::emissions_only(vec![a.clone(), b.clone()]);
+        let ba = L2BlockBody::emissions_only(vec![b, a]);
+        assert_ne!(
+            ab.calculate_emissions_root_ordered(),
+            ba.calculate_emissions_root_ordered()
+        );
+        assert_eq!(ab.calculate_emissions_root(), ba.calculate_emissions_root());
+        assert_ne!(
+            ab.calculate_emissions_root_ordered(),
+            ab.calculate_emissions_root()
+        );
+        for mode in [EmissionsRootMode::Sorted, EmissionsRootMode::Ordered] {
+            let expect = match mode {
+                EmissionsRootMode::Sorted => ab.calculate_emissions_root(),
+                EmissionsRootMode::Ordered => ab.calculate_emissions_root_ordered(),
+            };
+            assert_eq!(ab.calculate_emissions_root_with_mode(mode), expect);
+        }
+    }
+
+    #[test]
+    fn ordered_emissions_version_commits_order_into_body_root() {
+        let a = Emission {
+            pubkey: [1u8; 48],
+            weight: 5,
+        };
+        let b = Emission {
+            pubkey: [2u8; 48],
+            weight: 9,
+        };
+        let ab = L2BlockBody::emissions_only(vec![a.clone(), b.clone()]);
+        let ba = L2BlockBody::emissions_only(vec![b, a]);
+        let v = definitions::ORDERED_EMISSIONS_VERSION;
+        assert_eq!(
+            EmissionsRootMode::for_version(v - 1),
+            EmissionsRootMode::Sorted
+        );
+        assert_eq!(
+            EmissionsRootMode::for_version(v),
+            EmissionsRootMode::Ordered
+        );
+
+        // Before the gate order is ignored; from it the body root commits to it.
+        assert_eq!(
+            ab.calculate_root_for_version(v - 1),
+            ba.calculate_root_for_version(v - 1)
+        );
+        assert_ne!(
+            ab.calculate_root_for_version(v),
+            ba.calculate_root_for_version(v)
+        );
+
+        let leaves: Vec<definitions::Hash32> = ab
+            .emissions
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                definitions::indexed_emission_hash_with(
+                    &Sha256Hasher,
+                    FieldEncoding::LengthPrefixed,
+                    i as u64,
+                    &e.pubkey,
+                    e.weight,
+                )
+            })
+            .collect();
+        let emissions_root = definitions::MERKLE_ROOT(&leaves);
+        assert_eq!(ab.calculate_emissions_root_for_version(v), emissions_root);
+        let data_root = ab.calculate_data_root_with_mode(DataRootMode::for_version(v));
+        assert_eq!(
+            ab.calculate_root_for_version(v),
+            definitions::COMPUTE_BODY_ROOT_FOR_VERSION(v, &data_root, &emissions_root)
+        );
+    }
+
+    #[test]
+    fn chunked_data_root_changes_when_any_byte_changes() {
+        let data: Vec<u8> = (0..200u32).map(|i| (i % 7) as u8).collect();
+        let base = L2BlockBody {
+            data: data.clone(),
+            emissions: vec![],
+        };
+        let base_root = base.calculate_data_root_with_mode(DataRootMode::Chunked);
+        for i in 0..data.len() {
+            let mut changed = base.clone();
+            changed.data[i] ^= 0x01;
+            assert_ne!(
+                changed.calculate_data_root_with_mode(DataRootMode::Chunked),
+                base_root,
+                "byte {i} did not affect the root"
+            );
+        }
+    }
+
+    #[test]
+    fn chunked_data_root_leaves_are_chunks() {
+        let size = definitions::DATA_CHUNK_SIZE;
+        let data: Vec<u8> = (0..(size * 2 + 5)).map(|i| i as u8).collect();
+        let body = L2BlockBody {
+            data: data.clone(),
+            emissions: vec![],
+        };
+        let leaves = [
+            definitions::COMPUTE_DATA_CHUNK_HASH(0, &data[..size]),
+            definitions::COMPUTE_DATA_CHUNK_HASH(1, &data[size..size * 2]),
+            definitions::COMPUTE_DATA_CHUNK_HASH(2, &data[size * 2..]),
+        ];
+        assert_eq!(
+            body.calculate_data_root_with_mode(DataRootMode::Chunked),
+            definitions::MERKLE_ROOT(&leaves)
+        );
+    }
+
+    #[test]
+    fn root_for_version_selects_data_mode() {
+        let body = L2BlockBody {
+            data: vec![1, 2, 3],
+            emissions: vec![],
+        };
+        assert_eq!(body.calculate_root_for_version(1), body.calculate_root());
+        assert_ne!(
+            body.calculate_root_for_version(definitions::CHUNKED_DATA_VERSION),
+            body.calculate_root()
+        );
+    }
+
+    #[test]
+    fn find_duplicate_emission_pubkey_by_pubkey() {
+        let distinct = L2BlockBody {
+            data: vec![],
+            emissions: vec![
+                Emission {
+                    pubkey: [1u8; 48],
+                    weight: 5,
+                },
+                Emission {
+                    pubkey: [2u8; 48],
+                    weight: 5,
+                },
+            ],
+        };
+        assert_eq!(distinct.find_duplicate_emission_pubkey(), None);
+
+        let mut dup = distinct.clone();
+        dup.emissions.push(Emission {
+            pubkey: [1u8; 48],
+            weight: 9,
+        });
+        assert_eq!(dup.find_duplicate_emission_pubkey(), Some([1u8; 48]));
+    }
+
+    #[test]
+    fn body_root_hex_matches_root() {
+        let body = L2BlockBody {
+            data: vec![1, 2],
+            emissions: vec![],
+        };
+        assert_eq!(
+            body.calculate_root_hex(1),
+            format!("0x{}", hex::encode(body.calculate_root()))
+        );
+        let v = definitions::ORDERED_EMISSIONS_VERSION;
+        assert_eq!(
+            body.calculate_root_hex(v),
+            format!("0x{}", hex::encode(body.calculate_root_for_version(v)))
+        );
+        assert_ne!(body.calculate_root_hex(v), body.calculate_root_hex(1));
+    }
+
+    #[test]
+    fn body_builder_matches_direct_construction() {
+        let emissions: Vec<Emission> = (0..5u8)
+            .map(|i| Emission {
+                pubkey: [i; 48],
+                weight: i as u64 * 3,
+            })
+            .collect();
+        let cases: Vec<(Vec<u8>, Vec<Emission>)> = vec![
+            (vec![], vec![]),
+            (vec![7], vec![]),
+            (vec![], emissions[..1].to_vec()),
+            ((0..100u8).collect(), emissions.clone()),
+        ];
+        for (data, emissions) in cases {
+            let mut builder = L2BlockBodyBuilder::new();
+            if let Some((first, rest)) = data.split_first() {
+                builder.push_data_byte(*first).extend_data(rest);
+            }
+            for e in &emissions {
+                builder.push_emission(e.clone());
+            }
+            let (body, root) = builder.finish();
+            let direct = L2BlockBody { data, emissions };
+            assert_eq!(body, direct);
+            assert_eq!(root, direct.calculate_root());
+        }
+    }
+
+    #[test]
+    fn body_builder_follows_block_version() {
+        let emissions: Vec<Emission> = (0..5u8)
+            .rev()
+            .map(|i| Emission {
+                pubkey: [i; 48],
+                weight: u64::from(i) + 1,
+            })
+            .collect();
+        // Chunk-aligned, partial and multi-chunk payloads, pushed in pieces
+        // that straddle chunk boundaries.
+        let payloads: [Vec<u8>; 4] = [
+            vec![],
+            vec![7],
+            vec![3; definitions::DATA_CHUNK_SIZE],
+            (0..=200u8).collect(),
+        ];
+        for version in [
+            1,
+            definitions::CHUNKED_DATA_VERSION,
+            definitions::LENGTH_PREFIXED_FIELDS_VERSION,
+            definitions::ORDERED_EMISSIONS_VERSION,
+        ] {
+            for data in &payloads {
+                let mut builder = L2BlockBodyBuilder::for_version(version);
+                assert_eq!(builder.version(), version);
+                for piece in data.chunks(50) {
+                    builder.extend_data(piece);
+                }
+                for e in &emissions {
+                    builder.push_emission(e.clone());
+                }
+                let (body, root) = builder.finish();
+                assert_eq!(body.data, *data);
+                assert_eq!(body.emissions, emissions);
+                assert_eq!(root, body.calculate_root_for_version(version), "v{version}");
+            }
+        }
+    }
+
     #[test]
     fn body_root_changes_when_subroots_change() {
         let e = Emission {
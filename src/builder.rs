@@ -0,0 +1,255 @@
+//! Fluent builder for `DigL2Block`.
+//!
+//! `DigL2BlockBuilder` collects the same inputs as `BuildL2BlockArgs` one call at
+//! a time and delegates to `DigL2Block::build_with_policy`, so blocks built
+//! either way are identical. Missing required fields surface as
+//! `BlockError::MissingField`.
+//!
+//! Setters take the typed wrappers from `types` (`NetworkId`, `BlockRoot`,
+//! `Pubkey`) so swapped arguments fail to compile; raw arrays convert with
+//! `.into()`.
+
+use crate::block::{BlockError, BlockPolicy, BuildL2BlockArgs, DigL2Block};
+use crate::emission::Emission;
+use crate::emission_config::ConsensusEmissionConfig;
+use crate::types::{BlockRoot, NetworkId, Pubkey};
+
+/// Fluent, one-stop construction API for `DigL2Block`.
+///
+/// Required: `version`, `network_id`, `epoch`, `prev_block_root`, `proposer`,
+/// `config`. Optional: `timestamp` (0), `extra_data` (zeros), `data` (empty),
+/// `add_emission` (none), `attesters` (none), `policy` (`BlockPolicy::default()`).
+#[derive(Clone, Debug, Default)]
+pub struct DigL2BlockBuilder {
+    version: Option<u32>,
+    network_id: Option<NetworkId>,
+    epoch: Option<u64>,
+    prev_block_root: Option<BlockRoot>,
+    proposer_pubkey: Option<Pubkey>,
+    timestamp: u64,
+    extra_data: [u8; 32],
+    data: Vec<u8>,
+    extra_emissions: Vec<Emission>,
+    attester_pubkeys: Vec<[u8; 48]>,
+    cfg: Option<ConsensusEmissionConfig>,
+    policy: BlockPolicy,
+}
+
+impl DigL2BlockBuilder {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the block version.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Set the network ID.
+    pub fn network_id(mut self, network_id: NetworkId) -> Self {
+        self.network_id = Some(network_id);
+        self
+    }
+
+    /// Set the epoch.
+    pub fn epoch(mut self, epoch: u64) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    /// Set the previous block root.
+    pub fn prev_block_root(mut self, prev_block_root: BlockRoot) -> Self {
+        self.prev_block_root = Some(prev_block_root);
+        self
+    }
+
+    /// Set the proposer public key.
+    pub fn proposer(mut self, proposer_pubkey: Pubkey) -> Self {
+        self.proposer_pubkey = Some(proposer_pubkey);
+        self
+    }
+
+    /// Set the block timestamp (unix seconds).
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Set the header extra data (committed from `EXTRA_DATA_VERSION` on).
+    pub fn extra_data(mut self, extra_data: [u8; 32]) -> Self {
+        self.extra_data = extra_data;
+        self
+    }
+
+    /// Set the application data, replacing any previous value.
+    pub fn data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Append an extra (non-consensus) emission.
+    pub fn add_emission(mut self, emission: Emission) -> Self {
+        self.extra_emissions.push(emission);
+        self
+    }
+
+    /// Set the attester public keys, replacing any previous value.
+    pub fn attesters(mut self, attester_pubkeys: Vec<Pubkey>) -> Self {
+        self.attester_pubkeys = attester_pubkeys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the consensus emission config.
+    pub fn config(mut self, cfg: ConsensusEmissionConfig) -> Self {
+        self.cfg = Some(cfg);
+        self
+    }
+
+    /// Set the optional validation policy.
+    pub fn policy(mut self, policy: BlockPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Validate the inputs and assemble the block.
+    pub fn build(self) -> Result<DigL2Block, BlockError> {
+        let cfg = self
+            .cfg
+            .ok_or(BlockError::MissingField { field: "config" })?;
+        let args = BuildL2BlockArgs {
+            version: self
+                .version
+                .ok_or(BlockError::MissingField { field: "version" })?,
+            network_id: self.network_id.ok_or(BlockError::MissingField {
+                field: "network_id",
+            })?,
+            epoch: self
+                .epoch
+                .ok_or(BlockError::MissingField { field: "epoch" })?,
+            prev_block_root: self.prev_block_root.ok_or(BlockError::MissingField {
+                field: "prev_block_root",
+            })?,
+            proposer_pubkey: self
+                .proposer_pubkey
+                .ok_or(BlockError::MissingField { field: "proposer" })?,
+            data: self.data,
+            extra_emissions: self.extra_emissions,
+            attester_pubkeys: &self.attester_pubkeys,
+            cfg: &cfg,
+            timestamp: self.timestamp,
+            extra_data: self.extra_data,
+        };
+        DigL2Block::build_with_policy(&args, &self.policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete() -> DigL2BlockBuilder {
+        DigL2Block::builder()
+            .version(1)
+            .network_id([0xabu8; 32].into())
+            .epoch(7)
+            .prev_block_root([0u8; 32].into())
+            .proposer([9u8; 48].into())
+            .data(vec![1, 2, 3])
+            .add_emission(Emission {
+                pubkey: [0x33u8; 48],
+                weight: 7,
+            })
+            .attesters(vec![[0x11u8; 48].into(), [0x22u8; 48].into()])
+            .config(ConsensusEmissionConfig::new(12, 88))
+    }
+
+    #[test]
+    fn builder_matches_build_args() {
+        let block = complete().build().unwrap();
+        let attesters = [[0x11u8; 48], [0x22u8; 48]];
+        let cfg = ConsensusEmissionConfig::new(12, 88);
+        let args = BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 7,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![1, 2, 3],
+            extra_emissions: vec![Emission {
+                pubkey: [0x33u8; 48],
+                weight: 7,
+            }],
+            attester_pubkeys: &attesters,
+            cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        };
+        assert_eq!(block, DigL2Block::build(&args).unwrap());
+        assert!(block.verify(Some(1)).is_ok());
+    }
+
+    #[test]
+    fn builder_reports_missing_fields() {
+        let err = DigL2BlockBuilder::new().build().unwrap_err();
+        match err {
+            BlockError::MissingField { field } => assert_eq!(field, "config"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        let err = DigL2BlockBuilder::new()
+            .config(ConsensusEmissionConfig::new(12, 0))
+            .version(1)
+            .network_id([1u8; 32].into())
+            .epoch(0)
+            .prev_block_root([0u8; 32].into())
+            .build()
+            .unwrap_err();
+        match err {
+            BlockError::MissingField { field } => assert_eq!(field, "proposer"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn builder_rejects_zero_proposer_unless_policy_allows() {
+        let err = complete().proposer([0u8; 48].into()).build().unwrap_err();
+        assert!(matches!(err, BlockError::ZeroProposerPubkey));
+        let block = complete()
+            .proposer([0u8; 48].into())
+            .policy(BlockPolicy {
+                allow_zero_proposer_pubkey: true,
+                ..BlockPolicy::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(block.header.proposer_pubkey, [0u8; 48]);
+    }
+
+    #[test]
+    fn builder_rejects_zero_network_id_unless_policy_allows() {
+        let err = complete().network_id([0u8; 32].into()).build().unwrap_err();
+        assert!(matches!(err, BlockError::ZeroNetworkId));
+        let block = complete()
+            .network_id([0u8; 32].into())
+            .policy(BlockPolicy {
+                allow_zero_network_id: true,
+                ..BlockPolicy::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(block.header.network_id, [0u8; 32]);
+        assert!(complete().build().is_ok());
+    }
+
+    #[test]
+    fn builder_surfaces_config_errors() {
+        let err = complete()
+            .attesters(vec![])
+            .config(ConsensusEmissionConfig::new(12, 1))
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, BlockError::Config(_)));
+    }
+}
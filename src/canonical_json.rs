@@ -0,0 +1,39 @@
+//! Canonical JSON writer shared by `block::to_canonical_json` and
+//! `dig_l2_definition::merkle_root_of`.
+//!
+//! Object keys are sorted at every level and no whitespace is emitted, so
+//! equal values always produce identical bytes.
+
+use serde_json::Value;
+
+/// Writes `value` with sorted keys; does not rely on `serde_json`'s map ordering,
+/// which changes under its `preserve_order` feature.
+pub(crate) fn write_canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(item, out);
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
@@ -0,0 +1,209 @@
+//! CBOR (RFC 8949) encoding of the block types (feature `cbor`).
+//!
+//! Values go through their serde implementations, which see a binary
+//! (non-human-readable) format: byte fields become CBOR byte strings (major
+//! type 2) instead of `0x` hex text, and structs become maps keyed by field
+//! name. Heads use the shortest encoding, strings, arrays and maps are always
+//! definite-length, and floats are written at their own width.
+//!
+//! The decoder accepts any definite-length head but rejects tags,
+//! indefinite-length items, half-precision floats and simple values other than
+//! `false`, `true` and `null` with `FormatError::Unsupported`. A decoded block is
+//! not checked; call `DigL2Block::verify`.
+
+pub use crate::binary_serde::FormatError;
+use crate::binary_serde::{self, Head, Wire, length, take_array};
+use serde::{Deserialize, Serialize};
+
+/// Encodes `value` as CBOR.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FormatError> {
+    binary_serde::to_vec::<Cbor, T>(value)
+}
+
+/// Decodes exactly one CBOR-encoded `T` from `bytes`.
+pub fn from_slice<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, FormatError> {
+    binary_serde::from_slice::<Cbor, T>(bytes)
+}
+
+/// CBOR item heads.
+struct Cbor;
+
+impl Cbor {
+    /// Appends major type `major` with argument `n` in its shortest form.
+    fn write_argument(out: &mut Vec<u8>, major: u8, n: u64) {
+        let major = major << 5;
+        if n < 24 {
+            out.push(major | n as u8);
+        } else if let Ok(n) = u8::try_from(n) {
+            out.extend_from_slice(&[major | 24, n]);
+        } else if let Ok(n) = u16::try_from(n) {
+            out.push(major | 25);
+            out.extend_from_slice(&n.to_be_bytes());
+        } else if let Ok(n) = u32::try_from(n) {
+            out.push(major | 26);
+            out.extend_from_slice(&n.to_be_bytes());
+        } else {
+            out.push(major | 27);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+impl Wire for Cbor {
+    fn write_head(out: &mut Vec<u8>, head: Head) -> Result<(), FormatError> {
+        match head {
+            Head::Unsigned(n) => Self::write_argument(out, 0, n),
+            // CBOR stores a negative `v` as `-1 - v`, which is `!v`.
+            Head::Negative(v) => Self::write_argument(out, 1, !v as u64),
+            Head::Bytes(len) => Self::write_argument(out, 2, len as u64),
+            Head::Text(len) => Self::write_argument(out, 3, len as u64),
+            Head::Array(len) => Self::write_argument(out, 4, len as u64),
+            Head::Map(len) => Self::write_argument(out, 5, len as u64),
+            Head::Bool(false) => out.push(0xf4),
+            Head::Bool(true) => out.push(0xf5),
+            Head::Null => out.push(0xf6),
+            Head::F32(v) => {
+                out.push(0xfa);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Head::F64(v) => {
+                out.push(0xfb);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    fn read_head(input: &mut &[u8]) -> Result<Head, FormatError> {
+        let [byte] = take_array(input)?;
+        let (major, info) = (byte >> 5, byte & 0x1f);
+        if major == 7 {
+            return match info {
+                20 => Ok(Head::Bool(false)),
+                21 => Ok(Head::Bool(true)),
+                22 => Ok(Head::Null),
+                26 => Ok(Head::F32(f32::from_be_bytes(take_array(input)?))),
+                27 => Ok(Head::F64(f64::from_be_bytes(take_array(input)?))),
+                _ => Err(FormatError::Unsupported { byte }),
+            };
+        }
+        let n = match info {
+            0..=23 => u64::from(info),
+            24 => u8::from_be_bytes(take_array(input)?).into(),
+            25 => u16::from_be_bytes(take_array(input)?).into(),
+            26 => u32::from_be_bytes(take_array(input)?).into(),
+            27 => u64::from_be_bytes(take_array(input)?),
+            _ => return Err(FormatError::Unsupported { byte }),
+        };
+        match major {
+            0 => Ok(Head::Unsigned(n)),
+            1 => i64::try_from(n)
+                .map(|n| Head::Negative(!n))
+                .map_err(|_| FormatError::IntegerOutOfRange),
+            2 => Ok(Head::Bytes(length(n)?)),
+            3 => Ok(Head::Text(length(n)?)),
+            4 => Ok(Head::Array(length(n)?)),
+            5 => Ok(Head::Map(length(n)?)),
+            _ => Err(FormatError::Unsupported { byte }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Encodes `value`, checks the bytes against `expected` hex (examples
+    /// from RFC 8949 Appendix A) and decodes them back.
+    fn check<T>(value: T, expected: &str)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+    {
+        let bytes = to_vec(&value).unwrap();
+        assert_eq!(hex::encode(&bytes), expected, "{value:?}");
+        assert_eq!(from_slice::<T>(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn encodes_rfc_8949_examples() {
+        check(0u64, "00");
+        check(23u8, "17");
+        check(24u8, "1818");
+        check(1000u16, "1903e8");
+        check(1_000_000u32, "1a000f4240");
+        check(u64::MAX, "1bffffffffffffffff");
+        check(-1i8, "20");
+        check(-1000i64, "3903e7");
+        check(i64::MIN, "3b7fffffffffffffff");
+        check(1.1f64, "fb3ff199999999999a");
+        check(false, "f4");
+        check(true, "f5");
+        check(None::<u8>, "f6");
+        check("IETF".to_owned(), "6449455446");
+        check(vec![1u8, 2, 3], "83010203");
+        check(BTreeMap::from([("a".to_owned(), 1u8)]), "a1616101");
+        check(Bytes(vec![1, 2, 3, 4]), "4401020304");
+    }
+
+    /// A `Vec<u8>` carried through `serde_hex`, i.e. as a CBOR byte string.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Bytes(#[serde(with = "crate::serde_hex::hex_vec")] Vec<u8>);
+
+    #[test]
+    fn enums_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum E {
+            Unit,
+            Newtype(u8),
+            Tuple(u8, u8),
+            Struct { a: u8 },
+        }
+        check(E::Unit, "64556e6974");
+        check(E::Newtype(1), "a1674e65777479706501");
+        for e in [E::Tuple(1, 2), E::Struct { a: 3 }] {
+            assert_eq!(from_slice::<E>(&to_vec(&e).unwrap()).unwrap(), e);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(from_slice::<u8>(&[]), Err(FormatError::UnexpectedEof));
+        assert_eq!(
+            from_slice::<u8>(&[0x01, 0x02]),
+            Err(FormatError::TrailingBytes { len: 1 })
+        );
+        // A byte string claiming more bytes than remain.
+        assert_eq!(
+            from_slice::<Bytes>(&[0x44, 1, 2]),
+            Err(FormatError::UnexpectedEof)
+        );
+        // Tag 0, indefinite-length bytes, half float.
+        for byte in [0xc0, 0x5f, 0xf9] {
+            assert_eq!(
+                from_slice::<u8>(&[byte, 0, 0]),
+                Err(FormatError::Unsupported { byte })
+            );
+        }
+        assert_eq!(
+            from_slice::<i64>(&hex::decode("3bffffffffffffffff").unwrap()),
+            Err(FormatError::IntegerOutOfRange)
+        );
+        assert_eq!(
+            from_slice::<String>(&[0x61, 0xff]),
+            Err(FormatError::InvalidUtf8)
+        );
+        // A forged array count must not be trusted for allocation.
+        assert_eq!(
+            from_slice::<Vec<u8>>(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+            Err(FormatError::UnexpectedEof)
+        );
+        let deep = [vec![0x81; binary_serde::MAX_DEPTH + 1], vec![0x00]].concat();
+        assert_eq!(
+            from_slice::<serde::de::IgnoredAny>(&deep),
+            Err(FormatError::TooDeep {
+                max: binary_serde::MAX_DEPTH
+            })
+        );
+    }
+}
@@ -5,21 +5,44 @@
 //!
 //! Contents:
 //! - Domain constants used for SHA-256 domain separation
-//! - `COMPUTE_DATA_HASH`
+//! - `COMPUTE_DATA_HASH`, `COMPUTE_INDEXED_DATA_HASH`, `COMPUTE_DATA_CHUNK_HASH` and `DataRootMode`
+//! - `COMPUTE_INDEXED_EMISSION_HASH` and `EmissionsRootMode` (ordered from
+//!   `ORDERED_EMISSIONS_VERSION`)
 //! - `COMPUTE_EMISSION_HASH`
-//! - `MERKLE_ROOT`
-//! - `COMPUTE_BODY_ROOT`
-//! - `COMPUTE_HEADER_ROOT`
+//! - `FieldEncoding` and the version-aware `COMPUTE_EMISSION_HASH_FOR_VERSION`
+//!   (length-prefixed leaf fields)
+//! - `MERKLE_ROOT`, `MERKLE_ROOT_CHECKED`, `EMPTY_MERKLE_ROOT`
+//! - `merkle_root_of` (Merkle roots of arbitrary serializable items)
+//! - `MERKLE_ROOT_ARITY` (trees grouping `arity` nodes per parent)
+//! - `MerkleTree`, `MERKLE_PROOF`, `MERKLE_VERIFY` (inclusion proofs)
+//! - `MERKLE_VERIFY_BOUNDED` (`MERKLE_VERIFY` with a proof depth cap)
+//! - `ct_eq_hash` (constant-time root comparison)
+//! - `encode_proof`, `decode_proof` (compact binary proof encoding)
+//! - `MERKLE_MULTIPROOF`, `verify_multiproof` (shared proofs for several leaves)
+//! - `COMPUTE_BODY_ROOT`, `COMPUTE_BODY_ROOT_LABELED` and the version dispatch
+//!   `COMPUTE_BODY_ROOT_FOR_VERSION`
+//! - `COMPUTE_HEADER_ROOT_FIELDS`, `COMPUTE_HEADER_ROOT_HEADER` (and the
+//!   deprecated `COMPUTE_HEADER_ROOT`)
 //! - `COMPUTE_BLOCK_ROOT`
 //! - `BUILD_CONSENSUS_EMISSIONS` (returns simple tuples for later conversion)
+//! - `BUILD_CONSENSUS_EMISSIONS_SPEC` (the same records as named `EmissionSpec`s)
+//! - `build_chain` (test/simulation helper producing a linked chain of blocks)
+//!
+//! Every domain tag carries the `DOMAIN_VERSION` suffix: `domain` returns the
+//! suffixed tag, and the internal hashing helpers append the same suffix
+//! without allocating.
 //!
 //! All functions are deterministic and documented. Merkle construction uses
 //! classic odd-leaf duplication and distinct leaf/node domains.
 
 #![allow(non_snake_case)]
 
+use crate::block::{BlockError, BuildL2BlockArgs, DigL2Block};
+use crate::emission::Emission;
+use crate::emission_config::ConsensusEmissionConfig;
+use crate::hasher::{RootHasher, Sha256Hasher};
 use crate::header::L2BlockHeader;
-use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
 use thiserror::Error;
 
 /// 32-byte hash type used across the spec.
@@ -27,12 +50,20 @@ pub type Hash32 = [u8; 32];
 
 /// Domain separation for individual header fields.
 pub const HEADER_FIELD_DOMAIN: &[u8] = b"dig:l2:header_field:";
+/// Domain separation for the labeled body subroots (`LABELED_BODY_ROOT_VERSION`).
+pub const BODY_FIELD_DOMAIN: &[u8] = b"dig:l2:body_field:";
 /// Domain separation for the block root composition.
 pub const BLOCK_ROOT_DOMAIN: &[u8] = b"dig:l2:block_root:";
 /// Domain separation for application data items (single byte per spec here).
 pub const DATA_HASH_DOMAIN: &[u8] = b"dig:l2:data:";
+/// Domain separation for position-aware data chunks (chunked data root mode).
+pub const DATA_CHUNK_DOMAIN: &[u8] = b"dig:l2:data_chunk:";
 /// Domain separation for standardized emissions.
 pub const EMISSION_HASH_DOMAIN: &[u8] = b"dig:l2:emission:";
+/// Domain separation for position-tagged emissions (ordered emissions root mode).
+pub const INDEXED_EMISSION_DOMAIN: &[u8] = b"dig:l2:emission_indexed:";
+/// Domain separation for items hashed into leaves by `merkle_root_of`.
+pub const MERKLE_ITEM_DOMAIN: &[u8] = b"dig:l2:merkle:item:";
 /// Domain separation for Merkle leaf nodes.
 pub const MERKLE_LEAF_DOMAIN: &[u8] = b"dig:l2:merkle:leaf:";
 /// Domain separation for Merkle internal nodes.
@@ -40,36 +71,336 @@ pub const MERKLE_NODE_DOMAIN: &[u8] = b"dig:l2:merkle:node:";
 /// Domain for the empty Merkle root.
 pub const MERKLE_EMPTY_DOMAIN: &[u8] = b"dig:l2:merkle:empty:";
 
+/// Size in bytes of each data chunk in `DataRootMode::Chunked`; the final chunk may be shorter.
+pub const DATA_CHUNK_SIZE: usize = 64;
+
+/// First block version whose `DATA_ROOT` is computed over position-aware chunks.
+pub const CHUNKED_DATA_VERSION: u32 = 2;
+
+/// First block version whose `HEADER_ROOT` includes a `timestamp` leaf.
+pub const TIMESTAMP_VERSION: u32 = 3;
+
+/// First block version whose `HEADER_ROOT` includes an `extra_data` leaf.
+pub const EXTRA_DATA_VERSION: u32 = 4;
+
+/// First block version whose `BODY_ROOT` labels its two subroots
+/// (`COMPUTE_BODY_ROOT_LABELED`).
+pub const LABELED_BODY_ROOT_VERSION: u32 = 5;
+
+/// First block version whose labeled and emission leaves length-prefix each
+/// field (`FieldEncoding::LengthPrefixed`).
+///
+/// Data leaves keep their encoding: a chunk leaf has a fixed-width index and
+/// the chunk as its only variable field, so it has no boundary to shift.
+pub const LENGTH_PREFIXED_FIELDS_VERSION: u32 = 6;
+
+/// First block version whose `EMISSIONS_ROOT` keeps emissions in insertion
+/// order (`EmissionsRootMode::Ordered`).
+pub const ORDERED_EMISSIONS_VERSION: u32 = 7;
+
+/// How the `DATA_ROOT` is derived from body data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataRootMode {
+    /// Legacy mode: one leaf per byte via `COMPUTE_DATA_HASH`, leaves sorted by hash.
+    PerByteSorted,
+    /// One leaf per byte via `COMPUTE_INDEXED_DATA_HASH`, kept in payload order.
+    PerByteIndexed,
+    /// `DATA_CHUNK_SIZE`-byte chunks via `COMPUTE_DATA_CHUNK_HASH`, kept in payload order.
+    Chunked,
+}
+
+impl DataRootMode {
+    /// Mode used by a given block version: `Chunked` from `CHUNKED_DATA_VERSION` on.
+    ///
+    /// No version selects `PerByteIndexed`: `Chunked` preserves byte order too,
+    /// with one leaf per `DATA_CHUNK_SIZE` bytes instead of one per byte. It
+    /// stays available through `L2BlockBody::calculate_data_root_with_mode` for
+    /// callers that need per-byte leaves.
+    pub fn for_version(version: u32) -> Self {
+        if version >= CHUNKED_DATA_VERSION {
+            DataRootMode::Chunked
+        } else {
+            DataRootMode::PerByteSorted
+        }
+    }
+}
+
+/// How the `EMISSIONS_ROOT` orders its leaves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EmissionsRootMode {
+    /// Spec mode: one leaf per emission via `COMPUTE_EMISSION_HASH`, sorted by
+    /// hash, so the root ignores emission order.
+    #[default]
+    Sorted,
+    /// One leaf per emission via `COMPUTE_INDEXED_EMISSION_HASH`, kept in
+    /// insertion order, so the root commits to a proposer-chosen order.
+    Ordered,
+}
+
+impl EmissionsRootMode {
+    /// Mode used by a given block version: `Ordered` from
+    /// `ORDERED_EMISSIONS_VERSION` on.
+    pub fn for_version(version: u32) -> Self {
+        if version >= ORDERED_EMISSIONS_VERSION {
+            EmissionsRootMode::Ordered
+        } else {
+            EmissionsRootMode::Sorted
+        }
+    }
+}
+
+/// How the fields of a leaf (e.g. a header label and its value) are joined
+/// before hashing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldEncoding {
+    /// Legacy encoding: the bare concatenation of the fields.
+    #[default]
+    Concatenated,
+    /// Each field preceded by its length as a `u32` (in the hasher's
+    /// endianness), so bytes cannot shift between adjacent fields (e.g. label
+    /// `data_count` vs label `data` with a value starting `_count`).
+    LengthPrefixed,
+}
+
+impl FieldEncoding {
+    /// Encoding used by a given block version: `LengthPrefixed` from
+    /// `LENGTH_PREFIXED_FIELDS_VERSION` on.
+    pub fn for_version(version: u32) -> Self {
+        if version >= LENGTH_PREFIXED_FIELDS_VERSION {
+            FieldEncoding::LengthPrefixed
+        } else {
+            FieldEncoding::Concatenated
+        }
+    }
+}
+
 /// Errors for definition-level functions.
 #[derive(Debug, Error)]
 pub enum DefinitionError {
     /// Attempted to assign non-zero attester share with zero attesters; division is undefined.
     #[error("attester_reward_share is non-zero but no attesters provided")]
     NoAttestersForNonZeroShare,
+
+    /// A Merkle input has more leaves than `MAX_MERKLE_LEAVES`.
+    #[error("too many Merkle leaves: {count} exceeds {max}")]
+    TooManyLeaves { count: usize, max: usize },
+
+    /// An encoded proof's length does not match its declared depth.
+    #[error("encoded proof length mismatch: expected {expected} bytes, got {actual}")]
+    ProofLengthMismatch { expected: usize, actual: usize },
+
+    /// An encoded proof sets direction bits beyond its depth.
+    #[error("encoded proof has non-zero padding bits")]
+    ProofPaddingBits,
+
+    /// A proof is deeper than the caller's `max_depth`.
+    #[error("proof depth {depth} exceeds max {max}")]
+    ProofTooDeep { depth: usize, max: usize },
+
+    /// An item passed to `merkle_root_of` could not be encoded as JSON.
+    #[error("cannot encode Merkle leaf: {0}")]
+    LeafEncoding(#[from] serde_json::Error),
+
+    /// A pubkey slice is not exactly 48 bytes.
+    #[error("bad pubkey length: expected 48 bytes, got {len}")]
+    BadPubkeyLength { len: usize },
+}
+
+/// Version of the hashing scheme, applied to every domain tag via `domain`.
+///
+/// Version 0 is the original scheme and leaves tags unchanged, so existing
+/// roots stay valid. Bumping it changes every root at once.
+pub const DOMAIN_VERSION: u8 = 0;
+
+/// The domain tag actually hashed for `tag` under `DOMAIN_VERSION`:
+/// `tag` itself for version 0, otherwise `tag || version`.
+pub fn domain(tag: &[u8]) -> Vec<u8> {
+    domain_for(DOMAIN_VERSION, tag)
+}
+
+/// `domain` under an explicit domain `version`.
+fn domain_for(version: u8, tag: &[u8]) -> Vec<u8> {
+    let version = [version];
+    [tag, domain_suffix(&version)].concat()
+}
+
+/// The bytes appended to every domain tag: none for version 0, otherwise the
+/// version byte. `domain` and `hash_domain` both use it, so they cannot drift.
+fn domain_suffix(version: &[u8; 1]) -> &[u8] {
+    if version[0] == 0 { &[] } else { version }
+}
+
+/// `SHA256(domain(tag) || parts...)` without allocating the tag.
+fn sha256_domain(tag: &[u8], parts: &[&[u8]]) -> Hash32 {
+    hash_domain(&Sha256Hasher, tag, parts)
 }
 
-fn sha256_concat(parts: &[&[u8]]) -> Hash32 {
-    let mut hasher = Sha256::new();
-    for p in parts {
-        hasher.update(p);
+/// Longest `parts` list passed to `hash_domain` by any spec function.
+const MAX_DOMAIN_PARTS: usize = 6;
+
+/// `H(domain(tag) || parts...)` for any `RootHasher`, without allocating the tag.
+fn hash_domain<H: RootHasher + ?Sized>(hasher: &H, tag: &[u8], parts: &[&[u8]]) -> Hash32 {
+    assert!(parts.len() <= MAX_DOMAIN_PARTS, "too many hash parts");
+    let suffix = domain_suffix(&[DOMAIN_VERSION]);
+    let mut all: [&[u8]; MAX_DOMAIN_PARTS + 2] = [&[]; MAX_DOMAIN_PARTS + 2];
+    all[0] = tag;
+    let mut n = 1;
+    if !suffix.is_empty() {
+        all[1] = suffix;
+        n = 2;
     }
-    hasher.finalize().into()
+    all[n..n + parts.len()].copy_from_slice(parts);
+    hasher.hash(&all[..n + parts.len()])
+}
+
+/// `hash_domain` over `parts` joined as `encoding` specifies.
+fn hash_fields<H: RootHasher + ?Sized>(
+    hasher: &H,
+    encoding: FieldEncoding,
+    tag: &[u8],
+    parts: &[&[u8]],
+) -> Hash32 {
+    if encoding == FieldEncoding::Concatenated {
+        return hash_domain(hasher, tag, parts);
+    }
+    const MAX_FIELDS: usize = MAX_DOMAIN_PARTS / 2;
+    assert!(parts.len() <= MAX_FIELDS, "too many hash fields");
+    let mut lengths = [[0u8; 4]; MAX_FIELDS];
+    for (len, part) in lengths.iter_mut().zip(parts) {
+        let n = u32::try_from(part.len()).expect("hash field longer than u32::MAX");
+        *len = hasher.endianness().u32_bytes(n);
+    }
+    let mut framed: [&[u8]; MAX_DOMAIN_PARTS] = [&[]; MAX_DOMAIN_PARTS];
+    for (i, part) in parts.iter().enumerate() {
+        framed[2 * i] = &lengths[i];
+        framed[2 * i + 1] = part;
+    }
+    hash_domain(hasher, tag, &framed[..2 * parts.len()])
 }
 
 /// Compute the hash for a single data item (a single byte for this chain).
 ///
 /// Per spec: `SHA256(DATA_HASH_DOMAIN || item.data)`.
 pub fn COMPUTE_DATA_HASH(data_byte: u8) -> Hash32 {
+    data_hash_with(&Sha256Hasher, data_byte)
+}
+
+/// `COMPUTE_DATA_HASH` under an arbitrary hasher.
+pub(crate) fn data_hash_with<H: RootHasher + ?Sized>(hasher: &H, data_byte: u8) -> Hash32 {
+    hash_domain(hasher, DATA_HASH_DOMAIN, &[&[data_byte]])
+}
+
+/// Compute the position-tagged hash for the data byte at `index`.
+///
+/// Per spec: `SHA256(DATA_HASH_DOMAIN || index_le || byte)`. Unlike
+/// `COMPUTE_DATA_HASH`, equal bytes at different positions yield distinct leaves.
+pub fn COMPUTE_INDEXED_DATA_HASH(index: u64, data_byte: u8) -> Hash32 {
+    indexed_data_hash_with(&Sha256Hasher, index, data_byte)
+}
+
+/// `COMPUTE_INDEXED_DATA_HASH` under an arbitrary hasher.
+pub(crate) fn indexed_data_hash_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    index: u64,
+    data_byte: u8,
+) -> Hash32 {
+    let i = hasher.endianness().u64_bytes(index);
     let b = [data_byte];
-    sha256_concat(&[DATA_HASH_DOMAIN, &b])
+    hash_domain(hasher, DATA_HASH_DOMAIN, &[&i, &b])
+}
+
+/// Compute the hash for a data chunk at position `index` (chunked data root mode).
+///
+/// Per spec: `SHA256(DATA_CHUNK_DOMAIN || index_le || chunk)`. The index makes
+/// leaves position-aware, so reordering chunks changes the root.
+pub fn COMPUTE_DATA_CHUNK_HASH(index: u64, chunk: &[u8]) -> Hash32 {
+    data_chunk_hash_with(&Sha256Hasher, index, chunk)
+}
+
+/// `COMPUTE_DATA_CHUNK_HASH` under an arbitrary hasher.
+pub(crate) fn data_chunk_hash_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    index: u64,
+    chunk: &[u8],
+) -> Hash32 {
+    let i = hasher.endianness().u64_bytes(index);
+    hash_domain(hasher, DATA_CHUNK_DOMAIN, &[&i, chunk])
 }
 
 /// Compute the hash for a single emission.
 ///
 /// Per spec: `SHA256(EMISSION_HASH_DOMAIN || emission.pubkey || emission.weight_le)`.
 pub fn COMPUTE_EMISSION_HASH(pubkey: &[u8; 48], weight: u64) -> Hash32 {
-    let w = weight.to_le_bytes();
-    sha256_concat(&[EMISSION_HASH_DOMAIN, pubkey, &w])
+    emission_hash_with(&Sha256Hasher, FieldEncoding::Concatenated, pubkey, weight)
+}
+
+/// `COMPUTE_EMISSION_HASH` with the `FieldEncoding` of block `version`: from
+/// `LENGTH_PREFIXED_FIELDS_VERSION` the pubkey and weight are length-prefixed.
+pub fn COMPUTE_EMISSION_HASH_FOR_VERSION(version: u32, pubkey: &[u8; 48], weight: u64) -> Hash32 {
+    emission_hash_with(
+        &Sha256Hasher,
+        FieldEncoding::for_version(version),
+        pubkey,
+        weight,
+    )
+}
+
+/// Compute the position-tagged hash for the emission at `index`.
+///
+/// Per spec: `SHA256(INDEXED_EMISSION_DOMAIN || index_le || pubkey || weight_le)`.
+/// Unlike `COMPUTE_EMISSION_HASH`, equal emissions at different positions
+/// yield distinct leaves.
+pub fn COMPUTE_INDEXED_EMISSION_HASH(index: u64, pubkey: &[u8; 48], weight: u64) -> Hash32 {
+    indexed_emission_hash_with(
+        &Sha256Hasher,
+        FieldEncoding::Concatenated,
+        index,
+        pubkey,
+        weight,
+    )
+}
+
+/// `COMPUTE_INDEXED_EMISSION_HASH` under an arbitrary hasher and field
+/// encoding; the leaf of `ORDERED_EMISSIONS_VERSION` blocks.
+pub(crate) fn indexed_emission_hash_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    encoding: FieldEncoding,
+    index: u64,
+    pubkey: &[u8; 48],
+    weight: u64,
+) -> Hash32 {
+    let endianness = hasher.endianness();
+    let i = endianness.u64_bytes(index);
+    let w = endianness.u64_bytes(weight);
+    hash_fields(hasher, encoding, INDEXED_EMISSION_DOMAIN, &[&i, pubkey, &w])
+}
+
+/// `COMPUTE_EMISSION_HASH` for a pubkey held in a slice (e.g. a `Vec<u8>` or
+/// deserialized buffer), rejecting anything but 48 bytes with `BadPubkeyLength`.
+pub fn compute_emission_hash_slice(pubkey: &[u8], weight: u64) -> Result<Hash32, DefinitionError> {
+    let pubkey: &[u8; 48] = pubkey
+        .try_into()
+        .map_err(|_| DefinitionError::BadPubkeyLength { len: pubkey.len() })?;
+    Ok(COMPUTE_EMISSION_HASH(pubkey, weight))
+}
+
+/// `COMPUTE_EMISSION_HASH` under an arbitrary hasher and field encoding.
+pub(crate) fn emission_hash_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    encoding: FieldEncoding,
+    pubkey: &[u8; 48],
+    weight: u64,
+) -> Hash32 {
+    let w = hasher.endianness().u64_bytes(weight);
+    hash_fields(hasher, encoding, EMISSION_HASH_DOMAIN, &[pubkey, &w])
+}
+
+/// Root of an empty Merkle tree: `SHA256(MERKLE_EMPTY_DOMAIN)`.
+///
+/// Computed once and cached; `MERKLE_ROOT(&[])` returns this value.
+pub fn EMPTY_MERKLE_ROOT() -> Hash32 {
+    static EMPTY: OnceLock<Hash32> = OnceLock::new();
+    *EMPTY.get_or_init(|| sha256_domain(MERKLE_EMPTY_DOMAIN, &[]))
 }
 
 /// Compute a Merkle root from a slice of leaves.
@@ -77,84 +408,813 @@ pub fn COMPUTE_EMISSION_HASH(pubkey: &[u8; 48], weight: u64) -> Hash32 {
 /// - Leaves are first converted to domain-separated leaf nodes: `H = SHA256(MERKLE_LEAF_DOMAIN || leaf)`
 /// - Internal nodes are `SHA256(MERKLE_NODE_DOMAIN || left || right)`
 /// - Odd number of nodes duplicates the last one to make a pair.
-/// - Empty slice returns `SHA256(MERKLE_EMPTY_DOMAIN)`.
+/// - Empty slice returns `EMPTY_MERKLE_ROOT()`, i.e. `SHA256(MERKLE_EMPTY_DOMAIN)`.
+///
+/// With the `parallel` feature enabled, the node hashes of each level are
+/// computed across threads. The result is bit-identical to the sequential path.
 pub fn MERKLE_ROOT(leaves: &[Hash32]) -> Hash32 {
+    merkle_root_with_scratch(leaves, &mut Vec::new())
+}
+
+/// Merkle root of arbitrary serializable items, with the same tree
+/// construction as the spec roots.
+///
+/// Each item is encoded as canonical JSON (sorted object keys, no whitespace,
+/// as in `block::to_canonical_json`), hashed as
+/// `SHA256(MERKLE_ITEM_DOMAIN || encoding)`, and the hashes are passed to
+/// `MERKLE_ROOT`, which wraps each in the leaf domain as usual. The root is
+/// only as stable as that per-item encoding: types whose `Serialize` output
+/// changes (renamed fields, different number formatting) change the root.
+/// Fails with `LeafEncoding` if an item has no JSON encoding, e.g. a map with
+/// non-string keys.
+pub fn merkle_root_of<T: serde::Serialize>(items: &[T]) -> Result<Hash32, DefinitionError> {
+    let mut leaves = Vec::with_capacity(items.len());
+    let mut encoded = String::new();
+    for item in items {
+        encoded.clear();
+        crate::canonical_json::write_canonical_json(&serde_json::to_value(item)?, &mut encoded);
+        leaves.push(sha256_domain(MERKLE_ITEM_DOMAIN, &[encoded.as_bytes()]));
+    }
+    Ok(MERKLE_ROOT(&leaves))
+}
+
+/// Compute a Merkle root whose parents each hash a group of `arity` nodes.
+///
+/// - Leaves become leaf nodes exactly as in `MERKLE_ROOT`.
+/// - For `arity > 2`, each parent is
+///   `SHA256(domain(MERKLE_NODE_DOMAIN) || arity_le_u32 || child_0 || ... || child_{arity-1})`;
+///   committing to the arity keeps trees of different arities from colliding.
+/// - A short last group is padded by repeating its final node.
+/// - `arity == 2` is exactly `MERKLE_ROOT` (its node layout carries no arity).
+/// - Empty slice returns `EMPTY_MERKLE_ROOT()`.
+///
+/// # Panics
+/// If `arity < 2`.
+pub fn MERKLE_ROOT_ARITY(leaves: &[Hash32], arity: usize) -> Hash32 {
+    assert!(arity >= 2, "Merkle arity must be at least 2");
+    if arity == 2 || leaves.is_empty() {
+        return MERKLE_ROOT(leaves);
+    }
+    let tag = domain(MERKLE_NODE_DOMAIN);
+    let arity_le = u32::try_from(arity)
+        .expect("Merkle arity exceeds u32::MAX")
+        .to_le_bytes();
+    let mut level: Vec<Hash32> = leaves.iter().map(hash_leaf).collect();
+    while level.len() > 1 {
+        let mut parts: Vec<&[u8]> = Vec::with_capacity(arity + 2);
+        level = level
+            .chunks(arity)
+            .map(|group| {
+                let last = group.last().unwrap();
+                parts.clear();
+                parts.push(&tag);
+                parts.push(&arity_le);
+                parts.extend(group.iter().map(|n| n.as_slice()));
+                parts.extend(std::iter::repeat_n(last.as_slice(), arity - group.len()));
+                Sha256Hasher.hash(&parts)
+            })
+            .collect();
+    }
+    level[0]
+}
+
+/// Largest leaf count `MERKLE_ROOT_CHECKED` accepts: the hashed level (plus
+/// one duplicated odd node) must fit in an allocation of at most `isize::MAX`
+/// bytes, which bounds it on 32-bit targets.
+pub const MAX_MERKLE_LEAVES: usize = isize::MAX as usize / std::mem::size_of::<Hash32>() - 1;
+
+/// Fallible `MERKLE_ROOT` that rejects leaf counts whose level buffer could not
+/// be allocated, instead of panicking on capacity overflow.
+pub fn MERKLE_ROOT_CHECKED(leaves: &[Hash32]) -> Result<Hash32, DefinitionError> {
+    check_leaf_count(leaves.len())?;
+    Ok(MERKLE_ROOT(leaves))
+}
+
+fn check_leaf_count(count: usize) -> Result<(), DefinitionError> {
+    if count > MAX_MERKLE_LEAVES {
+        return Err(DefinitionError::TooManyLeaves {
+            count,
+            max: MAX_MERKLE_LEAVES,
+        });
+    }
+    Ok(())
+}
+
+/// `MERKLE_ROOT` computed in a caller-owned buffer, which is overwritten.
+///
+/// Reusing one `scratch` across calls avoids a level allocation per root.
+pub(crate) fn merkle_root_with_scratch(leaves: &[Hash32], scratch: &mut Vec<Hash32>) -> Hash32 {
     if leaves.is_empty() {
-        return sha256_concat(&[MERKLE_EMPTY_DOMAIN]);
+        return EMPTY_MERKLE_ROOT();
     }
+    merkle_root_in(&Sha256Hasher, leaves, scratch)
+}
 
-    let mut level: Vec<Hash32> = leaves
+/// `MERKLE_ROOT` under an arbitrary hasher, computed in `scratch`.
+pub(crate) fn merkle_root_in<H: RootHasher + ?Sized>(
+    hasher: &H,
+    leaves: &[Hash32],
+    scratch: &mut Vec<Hash32>,
+) -> Hash32 {
+    if leaves.is_empty() {
+        return hash_domain(hasher, MERKLE_EMPTY_DOMAIN, &[]);
+    }
+
+    scratch.clear();
+    scratch.extend(leaves.iter().map(|leaf| hash_leaf_with(hasher, leaf)));
+
+    #[cfg(feature = "parallel")]
+    while scratch.len() / 2 >= PARALLEL_MIN_PAIRS {
+        if scratch.len() % 2 == 1 {
+            let last = *scratch.last().unwrap();
+            scratch.push(last);
+        }
+        *scratch = hash_level_parallel(hasher, scratch);
+    }
+
+    fold_in_place(hasher, scratch)
+}
+
+pub(crate) fn hash_leaf(leaf: &Hash32) -> Hash32 {
+    hash_leaf_with(&Sha256Hasher, leaf)
+}
+
+pub(crate) fn hash_node(left: &Hash32, right: &Hash32) -> Hash32 {
+    hash_node_with(&Sha256Hasher, left, right)
+}
+
+fn hash_leaf_with<H: RootHasher + ?Sized>(hasher: &H, leaf: &Hash32) -> Hash32 {
+    hash_domain(hasher, MERKLE_LEAF_DOMAIN, &[leaf])
+}
+
+fn hash_node_with<H: RootHasher + ?Sized>(hasher: &H, left: &Hash32, right: &Hash32) -> Hash32 {
+    hash_domain(hasher, MERKLE_NODE_DOMAIN, &[left, right])
+}
+
+/// Folds a non-empty level up to the root, reusing `nodes` for every level.
+///
+/// Parent `i` overwrites slot `i` only after children `2i` and `2i + 1` were
+/// read, so no unread node is clobbered. An odd last node pairs with itself.
+fn fold_in_place<H: RootHasher + ?Sized>(hasher: &H, nodes: &mut [Hash32]) -> Hash32 {
+    let mut len = nodes.len();
+    while len > 1 {
+        let parents = len.div_ceil(2);
+        for i in 0..parents {
+            let left = nodes[2 * i];
+            let right = if 2 * i + 1 < len {
+                nodes[2 * i + 1]
+            } else {
+                left
+            };
+            nodes[i] = hash_node_with(hasher, &left, &right);
+        }
+        len = parents;
+    }
+    nodes[0]
+}
+
+/// Minimum number of pairs in a level before it is worth spawning threads.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_PAIRS: usize = 256;
+
+/// Hashes an even-length level into its parents across scoped threads.
+#[cfg(feature = "parallel")]
+fn hash_level_parallel<H: RootHasher + ?Sized>(hasher: &H, level: &[Hash32]) -> Vec<Hash32> {
+    let pairs = level.len() / 2;
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if threads < 2 {
+        return level
+            .chunks(2)
+            .map(|pair| hash_node_with(hasher, &pair[0], &pair[1]))
+            .collect();
+    }
+
+    // Each thread owns a contiguous run of parents, so output order matches the
+    // sequential path exactly.
+    let pairs_per_thread = pairs.div_ceil(threads);
+    let mut next = vec![[0u8; 32]; pairs];
+    std::thread::scope(|scope| {
+        for (out, input) in next
+            .chunks_mut(pairs_per_thread)
+            .zip(level.chunks(pairs_per_thread * 2))
+        {
+            scope.spawn(move || {
+                for (slot, pair) in out.iter_mut().zip(input.chunks_exact(2)) {
+                    *slot = hash_node_with(hasher, &pair[0], &pair[1]);
+                }
+            });
+        }
+    });
+    next
+}
+
+/// A Merkle tree with every level retained, used to produce inclusion proofs.
+///
+/// Construction follows `MERKLE_ROOT` exactly, so `MerkleTree::new(leaves).root()`
+/// always equals `MERKLE_ROOT(leaves)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// Leaves as supplied by the caller (before leaf-domain hashing).
+    leaves: Vec<Hash32>,
+    /// `levels[0]` holds hashed leaf nodes; the last level holds the root.
+    /// Odd levels are stored unpadded; the duplicated node is implied.
+    levels: Vec<Vec<Hash32>>,
+}
+
+impl MerkleTree {
+    /// Build a tree over `leaves`.
+    pub fn new(leaves: &[Hash32]) -> Self {
+        let mut levels = Vec::new();
+        if !leaves.is_empty() {
+            let mut level: Vec<Hash32> = leaves.iter().map(hash_leaf).collect();
+            while level.len() > 1 {
+                let next = level
+                    .chunks(2)
+                    .map(|pair| hash_node(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                    .collect();
+                levels.push(level);
+                level = next;
+            }
+            levels.push(level);
+        }
+        Self {
+            leaves: leaves.to_vec(),
+            levels,
+        }
+    }
+
+    /// The Merkle root; equal to `MERKLE_ROOT` over the same leaves.
+    pub fn root(&self) -> Hash32 {
+        match self.levels.last() {
+            Some(top) => top[0],
+            None => EMPTY_MERKLE_ROOT(),
+        }
+    }
+
+    /// The leaves the tree was built from.
+    pub fn leaves(&self) -> &[Hash32] {
+        &self.leaves
+    }
+
+    /// Number of leaves.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Whether the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Inclusion proof for the leaf at `index`, or `None` if out of range.
+    ///
+    /// Each entry is `(sibling, sibling_is_right)`, ordered from the leaf level
+    /// upwards. An odd node's sibling is the node itself (duplication rule).
+    /// A single-leaf tree has no pairing, so its proof is empty.
+    pub fn proof(&self, index: usize) -> Option<Vec<(Hash32, bool)>> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+        if self.leaves.len() == 1 {
+            // The root is the hashed leaf itself; there are no siblings.
+            return Some(Vec::new());
+        }
+        let mut proof = Vec::with_capacity(self.levels.len().saturating_sub(1));
+        let mut idx = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling_is_right = idx.is_multiple_of(2);
+            let sibling_idx = if sibling_is_right { idx + 1 } else { idx - 1 };
+            let sibling = *level.get(sibling_idx).unwrap_or(&level[idx]);
+            proof.push((sibling, sibling_is_right));
+            idx /= 2;
+        }
+        Some(proof)
+    }
+
+    /// Multiproof for several leaves; see `MERKLE_MULTIPROOF`.
+    pub fn multiproof(&self, indices: &[usize]) -> Option<MultiProof> {
+        let mut known: Vec<usize> = indices.to_vec();
+        known.sort_unstable();
+        known.dedup();
+        if known.is_empty() || *known.last().unwrap() >= self.leaves.len() {
+            return None;
+        }
+        let proven = known.clone();
+        let mut siblings = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            let mut parents = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                let idx = known[i];
+                if idx % 2 == 1 {
+                    // Left sibling is unknown, otherwise it would have consumed idx.
+                    siblings.push(level[idx - 1]);
+                } else if idx + 1 < level.len() {
+                    if known.get(i + 1) == Some(&(idx + 1)) {
+                        i += 1;
+                    } else {
+                        siblings.push(level[idx + 1]);
+                    }
+                }
+                // else: odd tail, paired with itself; nothing to send.
+                parents.push(idx / 2);
+                i += 1;
+            }
+            known = parents;
+        }
+        Some(MultiProof {
+            leaf_count: self.leaves.len(),
+            indices: proven,
+            siblings,
+        })
+    }
+
+    /// Locate the first leaf equal to `leaf` and return its index and proof.
+    ///
+    /// Returns `None` if the leaf is absent. Duplicate leaves resolve to the
+    /// lowest index.
+    pub fn proof_for_leaf(&self, leaf: &Hash32) -> Option<(usize, Vec<(Hash32, bool)>)> {
+        let index = self.leaves.iter().position(|l| l == leaf)?;
+        self.proof(index).map(|p| (index, p))
+    }
+}
+
+/// Produce an inclusion proof for `leaves[index]`, or `None` if out of range.
+///
+/// See `MerkleTree::proof` for the proof layout.
+pub fn MERKLE_PROOF(leaves: &[Hash32], index: usize) -> Option<Vec<(Hash32, bool)>> {
+    MerkleTree::new(leaves).proof(index)
+}
+
+/// Proof that several leaves are included under one root.
+///
+/// Siblings shared between the individual paths, or derivable from other proven
+/// leaves, are included only once (or not at all).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiProof {
+    /// Number of leaves in the tree the proof was built from.
+    pub leaf_count: usize,
+    /// Proven leaf indices, sorted ascending and de-duplicated.
+    pub indices: Vec<usize>,
+    /// Sibling hashes in the order the verifier consumes them: level by level
+    /// from the leaves up, left to right within a level.
+    pub siblings: Vec<Hash32>,
+}
+
+/// Produce a multiproof for `leaves[i]` for every `i` in `indices`.
+///
+/// Indices may be given in any order and may repeat; the proof records them
+/// sorted and de-duplicated. Returns `None` if `indices` is empty or any index
+/// is out of range.
+pub fn MERKLE_MULTIPROOF(leaves: &[Hash32], indices: &[usize]) -> Option<MultiProof> {
+    MerkleTree::new(leaves).multiproof(indices)
+}
+
+/// Reconstruct the root from a multiproof and compare it to `root`.
+///
+/// `leaves` are the proven leaf values in `proof.indices` order. Returns
+/// `false` for any malformed proof (wrong leaf count, unsorted or out-of-range
+/// indices, missing or surplus siblings).
+pub fn verify_multiproof(proof: &MultiProof, leaves: &[Hash32], root: &Hash32) -> bool {
+    if leaves.len() != proof.indices.len() || proof.indices.is_empty() {
+        return false;
+    }
+    if !proof.indices.windows(2).all(|w| w[0] < w[1])
+        || *proof.indices.last().unwrap() >= proof.leaf_count
+    {
+        return false;
+    }
+
+    let mut known: Vec<(usize, Hash32)> = proof
+        .indices
         .iter()
-        .map(|leaf| sha256_concat(&[MERKLE_LEAF_DOMAIN, leaf]))
+        .zip(leaves)
+        .map(|(i, leaf)| (*i, hash_leaf(leaf)))
         .collect();
+    let mut siblings = proof.siblings.iter();
+    let mut level_len = proof.leaf_count;
 
-    while level.len() > 1 {
-        if level.len() % 2 == 1 {
-            let last = *level.last().unwrap();
-            level.push(last);
+    while level_len > 1 {
+        let mut parents = Vec::with_capacity(known.len());
+        let mut i = 0;
+        while i < known.len() {
+            let (idx, node) = known[i];
+            let parent = if idx % 2 == 1 {
+                match siblings.next() {
+                    Some(left) => hash_node(left, &node),
+                    None => return false,
+                }
+            } else if idx + 1 < level_len {
+                match known.get(i + 1) {
+                    Some((next, right)) if *next == idx + 1 => {
+                        i += 1;
+                        hash_node(&node, right)
+                    }
+                    _ => match siblings.next() {
+                        Some(right) => hash_node(&node, right),
+                        None => return false,
+                    },
+                }
+            } else {
+                hash_node(&node, &node)
+            };
+            parents.push((idx / 2, parent));
+            i += 1;
         }
-        let mut next = Vec::with_capacity(level.len() / 2);
-        for pair in level.chunks(2) {
-            let combined = sha256_concat(&[MERKLE_NODE_DOMAIN, &pair[0], &pair[1]]);
-            next.push(combined);
+        known = parents;
+        level_len = level_len.div_ceil(2);
+    }
+
+    siblings.next().is_none() && ct_eq_hash(&known[0].1, root)
+}
+
+/// Verify that `leaf` is included under `root` using a proof from `MERKLE_PROOF`.
+///
+/// An empty proof is the single-leaf case: `root` must equal
+/// `SHA256(MERKLE_LEAF_DOMAIN || leaf)`.
+pub fn MERKLE_VERIFY(leaf: &Hash32, proof: &[(Hash32, bool)], root: &Hash32) -> bool {
+    if proof.is_empty() {
+        return ct_eq_hash(&hash_leaf(leaf), root);
+    }
+    let mut acc = hash_leaf(leaf);
+    for (sibling, sibling_is_right) in proof {
+        acc = if *sibling_is_right {
+            hash_node(&acc, sibling)
+        } else {
+            hash_node(sibling, &acc)
+        };
+    }
+    ct_eq_hash(&acc, root)
+}
+
+/// `MERKLE_VERIFY` for untrusted proofs: a proof with more than `max_depth`
+/// siblings is rejected with `ProofTooDeep` before any hashing, capping the
+/// work an attacker can force per verification.
+///
+/// A tree of `n > 1` leaves has depth `n.next_power_of_two().ilog2()`, so that
+/// is the tightest bound for an expected tree size.
+pub fn MERKLE_VERIFY_BOUNDED(
+    leaf: &Hash32,
+    proof: &[(Hash32, bool)],
+    root: &Hash32,
+    max_depth: usize,
+) -> Result<bool, DefinitionError> {
+    if proof.len() > max_depth {
+        return Err(DefinitionError::ProofTooDeep {
+            depth: proof.len(),
+            max: max_depth,
+        });
+    }
+    Ok(MERKLE_VERIFY(leaf, proof, root))
+}
+
+/// Compares two hashes in time independent of where they first differ.
+///
+/// `==` on arrays may return at the first mismatching byte, which lets an
+/// attacker who can time verification learn how much of a forged root was
+/// correct. Consensus checks against an expected root use this instead.
+pub fn ct_eq_hash(a: &Hash32, b: &Hash32) -> bool {
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+    }
+    std::hint::black_box(diff) == 0
+}
+
+/// Compact binary encoding of a `MERKLE_PROOF` proof.
+///
+/// Layout: `depth: u8`, then a direction bitmap of `ceil(depth / 8)` bytes (bit
+/// `i`, LSB first, set when sibling `i` is on the right), then the `depth`
+/// siblings as packed 32-byte hashes. A depth-20 proof is 1 + 3 + 640 bytes.
+///
+/// # Panics
+/// If the proof is deeper than 255 levels (more than 2^255 leaves).
+pub fn encode_proof(proof: &[(Hash32, bool)]) -> Vec<u8> {
+    let depth = u8::try_from(proof.len()).expect("proof depth fits in u8");
+    let bitmap_len = proof.len().div_ceil(8);
+    let mut out = Vec::with_capacity(1 + bitmap_len + 32 * proof.len());
+    out.push(depth);
+    out.resize(1 + bitmap_len, 0);
+    for (i, (_, sibling_is_right)) in proof.iter().enumerate() {
+        if *sibling_is_right {
+            out[1 + i / 8] |= 1 << (i % 8);
         }
-        level = next;
     }
-    level[0]
+    for (sibling, _) in proof {
+        out.extend_from_slice(sibling);
+    }
+    out
+}
+
+/// Decode a proof produced by `encode_proof`, rejecting wrong lengths and
+/// non-zero bitmap padding so every proof has exactly one encoding.
+pub fn decode_proof(bytes: &[u8]) -> Result<Vec<(Hash32, bool)>, DefinitionError> {
+    let Some((&depth, rest)) = bytes.split_first() else {
+        return Err(DefinitionError::ProofLengthMismatch {
+            expected: 1,
+            actual: 0,
+        });
+    };
+    let depth = depth as usize;
+    let bitmap_len = depth.div_ceil(8);
+    let expected = 1 + bitmap_len + 32 * depth;
+    if bytes.len() != expected {
+        return Err(DefinitionError::ProofLengthMismatch {
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    let (bitmap, siblings) = rest.split_at(bitmap_len);
+    if !depth.is_multiple_of(8) && bitmap[bitmap_len - 1] >> (depth % 8) != 0 {
+        return Err(DefinitionError::ProofPaddingBits);
+    }
+    Ok(siblings
+        .chunks_exact(32)
+        .enumerate()
+        .map(|(i, sibling)| {
+            let sibling_is_right = bitmap[i / 8] >> (i % 8) & 1 == 1;
+            (sibling.try_into().unwrap(), sibling_is_right)
+        })
+        .collect())
 }
 
 /// Compute the body root from the two subroots `DATA_ROOT` and `EMISSIONS_ROOT`.
 ///
-/// Implemented as a 2-leaf Merkle root of `[data_root, emissions_root]`.
+/// Implemented as a 2-leaf Merkle root of `[data_root, emissions_root]`. The
+/// argument order matters: only leaf position tells the subroots apart, so
+/// swapping them changes the root. Blocks from `LABELED_BODY_ROOT_VERSION` on
+/// use `COMPUTE_BODY_ROOT_LABELED` instead.
 pub fn COMPUTE_BODY_ROOT(data_root: &Hash32, emissions_root: &Hash32) -> Hash32 {
-    MERKLE_ROOT(&[*data_root, *emissions_root])
+    body_root_with(&Sha256Hasher, data_root, emissions_root)
 }
 
-/// Compute the header root from individual header fields, allowing proofs of each field.
+/// Compute the body root with each subroot hashed under its own label.
 ///
-/// Instead of taking a header struct (to avoid module coupling), we accept individual fields.
-/// The field label is included literally to avoid positional ambiguity.
+/// Per spec: a 2-leaf Merkle root of
+/// `[SHA256(BODY_FIELD_DOMAIN || "data_root" || data_root),
+///   SHA256(BODY_FIELD_DOMAIN || "emissions_root" || emissions_root)]`,
+/// with the fields concatenated as in `LABELED_BODY_ROOT_VERSION`
+/// (`COMPUTE_BODY_ROOT_FOR_VERSION` length-prefixes them from
+/// `LENGTH_PREFIXED_FIELDS_VERSION`). A subroot moved to the other position
+/// no longer carries its label, so the root depends on which value is which
+/// rather than on leaf order alone.
+pub fn COMPUTE_BODY_ROOT_LABELED(data_root: &Hash32, emissions_root: &Hash32) -> Hash32 {
+    labeled_body_root_with(
+        &Sha256Hasher,
+        FieldEncoding::Concatenated,
+        data_root,
+        emissions_root,
+    )
+}
+
+/// The `BODY_ROOT` of a block at `version`: `COMPUTE_BODY_ROOT_LABELED` from
+/// `LABELED_BODY_ROOT_VERSION` on, `COMPUTE_BODY_ROOT` before it.
+pub fn COMPUTE_BODY_ROOT_FOR_VERSION(
+    version: u32,
+    data_root: &Hash32,
+    emissions_root: &Hash32,
+) -> Hash32 {
+    body_root_for_version_with(&Sha256Hasher, version, data_root, emissions_root)
+}
+
+/// `COMPUTE_BODY_ROOT_FOR_VERSION` under an arbitrary hasher.
+pub(crate) fn body_root_for_version_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    version: u32,
+    data_root: &Hash32,
+    emissions_root: &Hash32,
+) -> Hash32 {
+    if version >= LABELED_BODY_ROOT_VERSION {
+        let encoding = FieldEncoding::for_version(version);
+        labeled_body_root_with(hasher, encoding, data_root, emissions_root)
+    } else {
+        body_root_with(hasher, data_root, emissions_root)
+    }
+}
+
+/// `COMPUTE_BODY_ROOT_LABELED` under an arbitrary hasher.
+fn labeled_body_root_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    encoding: FieldEncoding,
+    data_root: &Hash32,
+    emissions_root: &Hash32,
+) -> Hash32 {
+    let leaves = [
+        hash_fields(
+            hasher,
+            encoding,
+            BODY_FIELD_DOMAIN,
+            &[b"data_root", data_root],
+        ),
+        hash_fields(
+            hasher,
+            encoding,
+            BODY_FIELD_DOMAIN,
+            &[b"emissions_root", emissions_root],
+        ),
+    ];
+    merkle_root_in(hasher, &leaves, &mut Vec::new())
+}
+
+/// `COMPUTE_BODY_ROOT` under an arbitrary hasher.
+fn body_root_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    data_root: &Hash32,
+    emissions_root: &Hash32,
+) -> Hash32 {
+    merkle_root_in(hasher, &[*data_root, *emissions_root], &mut Vec::new())
+}
+
+/// Compute the header root of an `L2BlockHeader`.
+#[deprecated(
+    note = "ambiguous between the struct and field forms; use `COMPUTE_HEADER_ROOT_HEADER` or `COMPUTE_HEADER_ROOT_FIELDS`"
+)]
 pub fn COMPUTE_HEADER_ROOT(args: &L2BlockHeader) -> Hash32 {
-    let v_bytes = args.version.to_le_bytes();
-    let e_bytes = args.epoch.to_le_bytes();
-    let dc_bytes = args.data_count.to_le_bytes();
-    let ec_bytes = args.emissions_count.to_le_bytes();
-
-    let leaves: [Hash32; 8] = [
-        sha256_concat(&[HEADER_FIELD_DOMAIN, b"version", &v_bytes]),
-        sha256_concat(&[HEADER_FIELD_DOMAIN, b"network_id", &args.network_id]),
-        sha256_concat(&[HEADER_FIELD_DOMAIN, b"epoch", &e_bytes]),
-        sha256_concat(&[
-            HEADER_FIELD_DOMAIN,
-            b"prev_block_root",
-            &args.prev_block_root,
-        ]),
-        sha256_concat(&[HEADER_FIELD_DOMAIN, b"body_root", &args.body_root]),
-        sha256_concat(&[HEADER_FIELD_DOMAIN, b"data_count", &dc_bytes]),
-        sha256_concat(&[HEADER_FIELD_DOMAIN, b"emissions_count", &ec_bytes]),
-        sha256_concat(&[
+    COMPUTE_HEADER_ROOT_HEADER(args)
+}
+
+/// Compute the header root of an `L2BlockHeader`; delegates to
+/// `COMPUTE_HEADER_ROOT_FIELDS` with the header's fields.
+pub fn COMPUTE_HEADER_ROOT_HEADER(header: &L2BlockHeader) -> Hash32 {
+    COMPUTE_HEADER_ROOT_FIELDS(
+        header.version,
+        &header.network_id,
+        header.epoch,
+        &header.prev_block_root,
+        &header.body_root,
+        header.data_count,
+        header.emissions_count,
+        &header.proposer_pubkey,
+        header.timestamp,
+        &header.extra_data,
+    )
+}
+
+/// Compute the header root from individual header fields, allowing proofs of each field.
+///
+/// Each field is its own leaf with the field label included literally to
+/// avoid positional ambiguity. From `TIMESTAMP_VERSION` on a ninth `timestamp`
+/// leaf is appended, and from `EXTRA_DATA_VERSION` a tenth `extra_data` leaf;
+/// earlier versions keep their shorter roots and ignore those arguments. From
+/// `LENGTH_PREFIXED_FIELDS_VERSION` the label and value of each leaf are
+/// length-prefixed.
+#[allow(clippy::too_many_arguments)]
+pub fn COMPUTE_HEADER_ROOT_FIELDS(
+    version: u32,
+    network_id: &[u8; 32],
+    epoch: u64,
+    prev_block_root: &Hash32,
+    body_root: &Hash32,
+    data_count: u32,
+    emissions_count: u32,
+    proposer_pubkey: &[u8; 48],
+    timestamp: u64,
+    extra_data: &[u8; 32],
+) -> Hash32 {
+    header_root_fields_with(
+        &Sha256Hasher,
+        &HeaderFields {
+            version,
+            network_id,
+            epoch,
+            prev_block_root,
+            body_root,
+            data_count,
+            emissions_count,
+            proposer_pubkey,
+            timestamp,
+            extra_data,
+        },
+    )
+}
+
+/// Borrowed header fields hashed by `header_root_fields_with`.
+struct HeaderFields<'a> {
+    version: u32,
+    network_id: &'a [u8; 32],
+    epoch: u64,
+    prev_block_root: &'a Hash32,
+    body_root: &'a Hash32,
+    data_count: u32,
+    emissions_count: u32,
+    proposer_pubkey: &'a [u8; 48],
+    timestamp: u64,
+    extra_data: &'a [u8; 32],
+}
+
+/// `COMPUTE_HEADER_ROOT_HEADER` under an arbitrary hasher.
+pub(crate) fn header_root_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    header: &L2BlockHeader,
+) -> Hash32 {
+    header_root_fields_with(
+        hasher,
+        &HeaderFields {
+            version: header.version,
+            network_id: &header.network_id,
+            epoch: header.epoch,
+            prev_block_root: &header.prev_block_root,
+            body_root: &header.body_root,
+            data_count: header.data_count,
+            emissions_count: header.emissions_count,
+            proposer_pubkey: &header.proposer_pubkey,
+            timestamp: header.timestamp,
+            extra_data: &header.extra_data,
+        },
+    )
+}
+
+/// `COMPUTE_HEADER_ROOT_FIELDS` under an arbitrary hasher.
+fn header_root_fields_with<H: RootHasher + ?Sized>(hasher: &H, args: &HeaderFields<'_>) -> Hash32 {
+    let endianness = hasher.endianness();
+    let v_bytes = endianness.u32_bytes(args.version);
+    let e_bytes = endianness.u64_bytes(args.epoch);
+    let dc_bytes = endianness.u32_bytes(args.data_count);
+    let ec_bytes = endianness.u32_bytes(args.emissions_count);
+    let field = |label: &[u8], value: &[u8]| {
+        hash_fields(
+            hasher,
+            FieldEncoding::for_version(args.version),
             HEADER_FIELD_DOMAIN,
-            b"proposer_pubkey",
-            &args.proposer_pubkey,
-        ]),
+            &[label, value],
+        )
+    };
+
+    let mut leaves: Vec<Hash32> = vec![
+        field(b"version", &v_bytes),
+        field(b"network_id", args.network_id),
+        field(b"epoch", &e_bytes),
+        field(b"prev_block_root", args.prev_block_root),
+        field(b"body_root", args.body_root),
+        field(b"data_count", &dc_bytes),
+        field(b"emissions_count", &ec_bytes),
+        field(b"proposer_pubkey", args.proposer_pubkey),
     ];
-    MERKLE_ROOT(&leaves)
+    if args.version >= TIMESTAMP_VERSION {
+        leaves.push(field(b"timestamp", &endianness.u64_bytes(args.timestamp)));
+    }
+    if args.version >= EXTRA_DATA_VERSION {
+        leaves.push(field(b"extra_data", args.extra_data));
+    }
+    merkle_root_in(hasher, &leaves, &mut Vec::new())
+}
+
+/// One `COMPUTE_HEADER_ROOT_FIELDS` leaf: the field `label` and its encoded `value`.
+pub(crate) fn header_field_leaf(encoding: FieldEncoding, label: &[u8], value: &[u8]) -> Hash32 {
+    hash_fields(
+        &Sha256Hasher,
+        encoding,
+        HEADER_FIELD_DOMAIN,
+        &[label, value],
+    )
 }
 
 /// Compute the block root from `HEADER_ROOT` and `BODY_ROOT`.
 ///
 /// Per spec: `SHA256(BLOCK_ROOT_DOMAIN || header_root || body_root)`.
 pub fn COMPUTE_BLOCK_ROOT(header_root: &Hash32, body_root: &Hash32) -> Hash32 {
-    sha256_concat(&[BLOCK_ROOT_DOMAIN, header_root, body_root])
+    block_root_with(&Sha256Hasher, header_root, body_root)
+}
+
+/// `COMPUTE_BLOCK_ROOT` under an arbitrary hasher.
+pub(crate) fn block_root_with<H: RootHasher + ?Sized>(
+    hasher: &H,
+    header_root: &Hash32,
+    body_root: &Hash32,
+) -> Hash32 {
+    hash_domain(hasher, BLOCK_ROOT_DOMAIN, &[header_root, body_root])
 }
 
 /// Simple emission tuple returned by `BUILD_CONSENSUS_EMISSIONS`.
 /// Concrete `Emission` types can convert from this tuple.
 pub type EmissionTuple = ([u8; 48], u64);
 
+/// An `EmissionTuple` with named fields, returned by
+/// `BUILD_CONSENSUS_EMISSIONS_SPEC`. Converts to and from `EmissionTuple` and
+/// `Emission`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmissionSpec {
+    /// Recipient BLS public key.
+    pub pubkey: [u8; 48],
+    /// Relative share of the reward pool.
+    pub weight: u64,
+}
+
+impl From<EmissionTuple> for EmissionSpec {
+    fn from((pubkey, weight): EmissionTuple) -> Self {
+        EmissionSpec { pubkey, weight }
+    }
+}
+
+impl From<EmissionSpec> for EmissionTuple {
+    fn from(spec: EmissionSpec) -> Self {
+        (spec.pubkey, spec.weight)
+    }
+}
+
 /// Build the required consensus emissions: one proposer record plus attester records.
 ///
-/// - `proposer_reward_share` is a fixed weight (e.g., 12 for 12.5%).
+/// - `proposer_reward_share` is a fixed weight (e.g., 12 for the mainnet 12%).
 /// - `attester_reward_share` is split equally among attesters using integer division; remainder is undistributed.
 /// - If `attester_reward_share > 0` while `attester_pubkeys` is empty, returns an error.
 pub fn BUILD_CONSENSUS_EMISSIONS(
@@ -180,9 +1240,95 @@ pub fn BUILD_CONSENSUS_EMISSIONS(
     Ok(out)
 }
 
+/// `BUILD_CONSENSUS_EMISSIONS` with each record as an `EmissionSpec`; same
+/// records, order, and errors.
+pub fn BUILD_CONSENSUS_EMISSIONS_SPEC(
+    proposer_pubkey: [u8; 48],
+    attester_pubkeys: &[[u8; 48]],
+    proposer_reward_share: u64,
+    attester_reward_share: u64,
+) -> Result<Vec<EmissionSpec>, DefinitionError> {
+    Ok(BUILD_CONSENSUS_EMISSIONS(
+        proposer_pubkey,
+        attester_pubkeys,
+        proposer_reward_share,
+        attester_reward_share,
+    )?
+    .into_iter()
+    .map(EmissionSpec::from)
+    .collect())
+}
+
+/// Per-block inputs for `build_chain`.
+#[derive(Clone, Debug)]
+pub struct BlockSpec {
+    /// Block version.
+    pub version: u32,
+    /// Network ID shared by the chain.
+    pub network_id: [u8; 32],
+    /// Proposer public key.
+    pub proposer_pubkey: [u8; 48],
+    /// Application data.
+    pub data: Vec<u8>,
+    /// Extra (non-consensus) emissions.
+    pub extra_emissions: Vec<Emission>,
+    /// Attester public keys.
+    pub attester_pubkeys: Vec<[u8; 48]>,
+    /// Consensus emission config.
+    pub cfg: ConsensusEmissionConfig,
+    /// Block timestamp (unix seconds); must be zero before `TIMESTAMP_VERSION`.
+    pub timestamp: u64,
+    /// Opaque application commitment; must be zero before `EXTRA_DATA_VERSION`.
+    pub extra_data: [u8; 32],
+}
+
+/// Build a linked chain of blocks from per-block specs, for tests and simulations.
+///
+/// The first block is a genesis block at epoch 0 with an all-zero
+/// `prev_block_root`; each following block sets `prev_block_root` to its
+/// parent's root and increments the epoch by one. The result passes
+/// `block::validate_chain`.
+pub fn build_chain(
+    specs: impl IntoIterator<Item = BlockSpec>,
+) -> Result<Vec<DigL2Block>, BlockError> {
+    let mut chain: Vec<DigL2Block> = Vec::new();
+    for spec in specs {
+        let (epoch, prev_block_root) = match chain.last() {
+            Some(parent) => (parent.header.epoch + 1, parent.calculate_root()),
+            None => (0, [0u8; 32]),
+        };
+        let args = BuildL2BlockArgs {
+            version: spec.version,
+            timestamp: spec.timestamp,
+            extra_data: spec.extra_data,
+            network_id: spec.network_id.into(),
+            epoch,
+            prev_block_root: prev_block_root.into(),
+            proposer_pubkey: spec.proposer_pubkey.into(),
+            data: spec.data,
+            extra_emissions: spec.extra_emissions,
+            attester_pubkeys: &spec.attester_pubkeys,
+            cfg: &spec.cfg,
+        };
+        chain.push(DigL2Block::build(&args)?);
+    }
+    Ok(chain)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::TestRng;
+    use sha2::{Digest, Sha256};
+
+    /// Plain `SHA256(parts...)`, for spelling out expected version-0 hashes.
+    fn sha256_concat(parts: &[&[u8]]) -> Hash32 {
+        let mut hasher = Sha256::new();
+        for p in parts {
+            hasher.update(p);
+        }
+        hasher.finalize().into()
+    }
 
     fn h32(x: u8) -> Hash32 {
         // helper deterministic array for testing merkle behavior
@@ -191,6 +1337,85 @@ mod tests {
         a
     }
 
+    #[test]
+    fn merkle_verify_bounded_caps_proof_depth() {
+        let leaves: Vec<Hash32> = (0..5).map(h32).collect();
+        let root = MERKLE_ROOT(&leaves);
+        let proof = MERKLE_PROOF(&leaves, 3).unwrap();
+        let depth = leaves.len().next_power_of_two().ilog2() as usize;
+        assert_eq!(proof.len(), depth);
+        assert!(MERKLE_VERIFY_BOUNDED(&leaves[3], &proof, &root, depth).unwrap());
+        assert!(!MERKLE_VERIFY_BOUNDED(&leaves[2], &proof, &root, depth).unwrap());
+
+        // Even a valid proof is refused once it exceeds the cap.
+        assert!(matches!(
+            MERKLE_VERIFY_BOUNDED(&leaves[3], &proof, &root, depth - 1),
+            Err(DefinitionError::ProofTooDeep { depth: 3, max: 2 })
+        ));
+        let padded = vec![(h32(0), true); 10_000];
+        assert!(matches!(
+            MERKLE_VERIFY_BOUNDED(&leaves[3], &padded, &root, 64),
+            Err(DefinitionError::ProofTooDeep {
+                depth: 10_000,
+                max: 64
+            })
+        ));
+    }
+
+    #[test]
+    fn merkle_root_arity_two_is_merkle_root() {
+        for n in 0..10u8 {
+            let leaves: Vec<Hash32> = (0..n).map(h32).collect();
+            assert_eq!(MERKLE_ROOT_ARITY(&leaves, 2), MERKLE_ROOT(&leaves));
+        }
+    }
+
+    #[test]
+    fn merkle_root_arity_four_vector() {
+        // Six leaves h32(1..=6): groups [1,2,3,4] and [5,6,6,6], then a root
+        // over [p0, p1, p1, p1], each parent prefixed by arity 4 as u32 LE.
+        let leaves: Vec<Hash32> = (1..=6).map(h32).collect();
+        let node = |group: [&Hash32; 4]| {
+            let mut h = Sha256::new();
+            h.update(MERKLE_NODE_DOMAIN);
+            h.update(4u32.to_le_bytes());
+            for n in group {
+                h.update(n);
+            }
+            let out: Hash32 = h.finalize().into();
+            out
+        };
+        let l: Vec<Hash32> = leaves.iter().map(hash_leaf).collect();
+        let p0 = node([&l[0], &l[1], &l[2], &l[3]]);
+        let p1 = node([&l[4], &l[5], &l[5], &l[5]]);
+        let root = MERKLE_ROOT_ARITY(&leaves, 4);
+        assert_eq!(root, node([&p0, &p1, &p1, &p1]));
+        assert_eq!(
+            hex::encode(root),
+            "a5fdba0d6f19333ea675326e75ab473c33fa24e6d792bf04103ae68084378278"
+        );
+
+        // Distinct from the binary tree and from other arities.
+        assert_ne!(root, MERKLE_ROOT(&leaves));
+        assert_ne!(root, MERKLE_ROOT_ARITY(&leaves, 8));
+        // A single leaf has no parents under any arity.
+        assert_eq!(MERKLE_ROOT_ARITY(&leaves[..1], 4), hash_leaf(&leaves[0]));
+        assert_eq!(MERKLE_ROOT_ARITY(&[], 4), EMPTY_MERKLE_ROOT());
+    }
+
+    #[test]
+    fn ct_eq_hash_matches_array_equality() {
+        let a = [0x5au8; 32];
+        assert!(ct_eq_hash(&a, &a));
+        for i in [0, 15, 31] {
+            let mut b = a;
+            b[i] ^= 1;
+            assert!(!ct_eq_hash(&a, &b));
+            assert!(!ct_eq_hash(&b, &a));
+        }
+        assert!(!ct_eq_hash(&[0u8; 32], &[0xffu8; 32]));
+    }
+
     #[test]
     fn data_hash_changes_with_value() {
         let h1 = COMPUTE_DATA_HASH(0);
@@ -198,6 +1423,50 @@ mod tests {
         assert_ne!(h1, h2);
     }
 
+    #[test]
+    fn indexed_data_hash_is_position_aware() {
+        assert_ne!(
+            COMPUTE_INDEXED_DATA_HASH(0, 7),
+            COMPUTE_INDEXED_DATA_HASH(1, 7)
+        );
+        assert_ne!(COMPUTE_INDEXED_DATA_HASH(0, 7), COMPUTE_DATA_HASH(7));
+    }
+
+    #[test]
+    fn indexed_emission_hash_is_position_aware() {
+        let pk = [3u8; 48];
+        assert_ne!(
+            COMPUTE_INDEXED_EMISSION_HASH(0, &pk, 5),
+            COMPUTE_INDEXED_EMISSION_HASH(1, &pk, 5)
+        );
+        assert_ne!(
+            COMPUTE_INDEXED_EMISSION_HASH(0, &pk, 5),
+            COMPUTE_EMISSION_HASH(&pk, 5)
+        );
+    }
+
+    #[test]
+    fn data_chunk_hash_is_position_aware() {
+        let chunk = [1u8, 2, 3];
+        assert_ne!(
+            COMPUTE_DATA_CHUNK_HASH(0, &chunk),
+            COMPUTE_DATA_CHUNK_HASH(1, &chunk)
+        );
+        assert_eq!(
+            COMPUTE_DATA_CHUNK_HASH(0, &chunk),
+            sha256_concat(&[DATA_CHUNK_DOMAIN, &0u64.to_le_bytes(), &chunk])
+        );
+    }
+
+    #[test]
+    fn data_root_mode_for_version() {
+        assert_eq!(DataRootMode::for_version(1), DataRootMode::PerByteSorted);
+        assert_eq!(
+            DataRootMode::for_version(CHUNKED_DATA_VERSION),
+            DataRootMode::Chunked
+        );
+    }
+
     #[test]
     fn emission_hash_domain_separated() {
         let pk = [1u8; 48];
@@ -213,6 +1482,20 @@ mod tests {
         assert_eq!(r, expect);
     }
 
+    #[test]
+    fn empty_merkle_root_constant() {
+        let expect = sha256_concat(&[MERKLE_EMPTY_DOMAIN]);
+        assert_eq!(EMPTY_MERKLE_ROOT(), expect);
+        assert_eq!(MERKLE_ROOT(&[]), EMPTY_MERKLE_ROOT());
+
+        let body = crate::body::L2BlockBody {
+            data: vec![],
+            emissions: vec![],
+        };
+        assert_eq!(body.calculate_data_root(), EMPTY_MERKLE_ROOT());
+        assert_eq!(body.calculate_emissions_root(), EMPTY_MERKLE_ROOT());
+    }
+
     #[test]
     fn merkle_root_single() {
         let leaf = h32(7);
@@ -230,6 +1513,621 @@ mod tests {
         assert_ne!(r, r2);
     }
 
+    #[test]
+    fn merkle_root_of_u64s_matches_hand_built_tree() {
+        let items: Vec<u64> = (1..=3).collect();
+        let root = merkle_root_of(&items).unwrap();
+        let item = |json: &[u8]| sha256_concat(&[MERKLE_ITEM_DOMAIN, json]);
+        let leaf = |h: Hash32| sha256_concat(&[MERKLE_LEAF_DOMAIN, &h]);
+        let node = |a: Hash32, b: Hash32| sha256_concat(&[MERKLE_NODE_DOMAIN, &a, &b]);
+        let l3 = leaf(item(b"3"));
+        let expected = node(node(leaf(item(b"1")), leaf(item(b"2"))), node(l3, l3));
+        assert_eq!(root, expected);
+        assert_eq!(merkle_root_of::<u64>(&[]).unwrap(), EMPTY_MERKLE_ROOT());
+
+        let bad: std::collections::BTreeMap<[u8; 2], u8> = [([1, 2], 3)].into();
+        assert!(matches!(
+            merkle_root_of(&[bad]),
+            Err(DefinitionError::LeafEncoding(_))
+        ));
+    }
+
+    /// The original allocating implementation, kept as a parity reference.
+    fn merkle_root_reference(leaves: &[Hash32]) -> Hash32 {
+        if leaves.is_empty() {
+            return sha256_concat(&[MERKLE_EMPTY_DOMAIN]);
+        }
+        let mut level: Vec<Hash32> = leaves
+            .iter()
+            .map(|leaf| sha256_concat(&[MERKLE_LEAF_DOMAIN, leaf]))
+            .collect();
+        while level.len() > 1 {
+            if level.len() % 2 == 1 {
+                let last = *level.last().unwrap();
+                level.push(last);
+            }
+            let mut next = Vec::with_capacity(level.len() / 2);
+            for pair in level.chunks(2) {
+                next.push(sha256_concat(&[MERKLE_NODE_DOMAIN, &pair[0], &pair[1]]));
+            }
+            level = next;
+        }
+        level[0]
+    }
+
+    fn leaves_of(n: usize) -> Vec<Hash32> {
+        (0..n)
+            .map(|i| sha256_concat(&[&(i as u64).to_le_bytes()]))
+            .collect()
+    }
+
+    #[test]
+    fn merkle_root_in_place_matches_reference() {
+        // Deterministic pseudo-random sizes plus every small size.
+        let mut sizes: Vec<usize> = (0..=70).collect();
+        let mut rng = TestRng::new(0x2545_f491_4f6c_dd1d);
+        for _ in 0..40 {
+            sizes.push(rng.below(5000));
+        }
+        for n in sizes {
+            let leaves = leaves_of(n);
+            assert_eq!(
+                MERKLE_ROOT(&leaves),
+                merkle_root_reference(&leaves),
+                "mismatch for {n} leaves"
+            );
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn merkle_root_parallel_matches_sequential() {
+        for n in [1usize, 2, 1000, 1001] {
+            let leaves = leaves_of(n);
+            assert_eq!(
+                MERKLE_ROOT(&leaves),
+                merkle_root_reference(&leaves),
+                "mismatch for {n} leaves"
+            );
+        }
+        let level = leaves_of(1000);
+        let expect: Vec<Hash32> = level
+            .chunks(2)
+            .map(|pair| hash_node(&pair[0], &pair[1]))
+            .collect();
+        assert_eq!(hash_level_parallel(&Sha256Hasher, &level), expect);
+    }
+
+    #[test]
+    fn merkle_tree_root_matches_and_proofs_verify() {
+        for n in 1..=9u8 {
+            let leaves: Vec<Hash32> = (0..n).map(h32).collect();
+            let tree = MerkleTree::new(&leaves);
+            let root = MERKLE_ROOT(&leaves);
+            assert_eq!(tree.root(), root);
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = MERKLE_PROOF(&leaves, i).unwrap();
+                assert!(MERKLE_VERIFY(leaf, &proof, &root));
+                assert!(!MERKLE_VERIFY(&h32(0xff), &proof, &root));
+            }
+            assert!(tree.proof(leaves.len()).is_none());
+        }
+        assert_eq!(MerkleTree::new(&[]).root(), MERKLE_ROOT(&[]));
+    }
+
+    /// Random tree sizes: every size up to 40, then mostly small sizes with a
+    /// few up to several thousand leaves.
+    fn property_sizes(rng: &mut TestRng) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..=40).collect();
+        for i in 0..60 {
+            let max = if i % 10 == 0 { 4000 } else { 300 };
+            sizes.push(rng.below(max + 1));
+        }
+        sizes
+    }
+
+    #[test]
+    fn property_merkle_root_deterministic_and_leaf_sensitive() {
+        let mut rng = TestRng::new(0x9e37_79b9_7f4a_7c15);
+        for n in property_sizes(&mut rng) {
+            let leaves: Vec<Hash32> = (0..n).map(|_| rng.hash()).collect();
+            let root = MERKLE_ROOT(&leaves);
+            assert_eq!(root, MERKLE_ROOT(&leaves.clone()), "n={n}");
+            assert_eq!(root, MerkleTree::new(&leaves).root(), "n={n}");
+            if n == 0 {
+                continue;
+            }
+            // Flip one bit of one byte of one leaf; includes the duplicated odd tail.
+            for i in [0, n - 1, rng.below(n)] {
+                let mut mutated = leaves.clone();
+                mutated[i][rng.below(32)] ^= 1 << rng.below(8);
+                assert_ne!(MERKLE_ROOT(&mutated), root, "n={n} i={i}");
+            }
+        }
+    }
+
+    #[test]
+    fn property_merkle_proofs_are_sound() {
+        let mut rng = TestRng::new(0xd1b5_4a32_d192_ed03);
+        for n in property_sizes(&mut rng) {
+            let leaves: Vec<Hash32> = (0..n).map(|_| rng.hash()).collect();
+            let tree = MerkleTree::new(&leaves);
+            let root = tree.root();
+            assert!(tree.proof(n).is_none());
+            if n == 0 {
+                continue;
+            }
+            let mut indices: Vec<usize> = (0..16).map(|_| rng.below(n)).collect();
+            indices.extend([0, n - 1]);
+            for i in indices {
+                let proof = tree.proof(i).unwrap();
+                assert_eq!(Some(proof.clone()), MERKLE_PROOF(&leaves, i), "n={n} i={i}");
+                assert!(MERKLE_VERIFY(&leaves[i], &proof, &root), "n={n} i={i}");
+
+                let mut wrong_leaf = leaves[i];
+                wrong_leaf[rng.below(32)] ^= 0x80;
+                assert!(!MERKLE_VERIFY(&wrong_leaf, &proof, &root), "n={n} i={i}");
+                if proof.is_empty() {
+                    continue;
+                }
+                let mut tampered = proof.clone();
+                let j = rng.below(tampered.len());
+                // Changing any sibling, including a duplicated odd tail, must break the proof.
+                tampered[j].0[rng.below(32)] ^= 1;
+                assert!(!MERKLE_VERIFY(&leaves[i], &tampered, &root), "n={n} i={i}");
+            }
+        }
+    }
+
+    /// SHA-256 as it would hash under domain version `.0`. Every hash goes
+    /// through `hash_domain`, whose first part is the version-0 tag, so
+    /// re-tagging that part with `domain_for` re-versions the whole scheme.
+    struct BumpedDomain(u8);
+
+    impl RootHasher for BumpedDomain {
+        fn hash(&self, parts: &[&[u8]]) -> Hash32 {
+            let tag = domain_for(self.0, parts[0]);
+            let mut all = vec![tag.as_slice()];
+            all.extend_from_slice(&parts[1..]);
+            Sha256Hasher.hash(&all)
+        }
+    }
+
+    fn pinned_block(version: u32) -> DigL2Block {
+        let cfg = ConsensusEmissionConfig::new(12, 88);
+        let attesters = [[0x11u8; 48], [0x22u8; 48]];
+        DigL2Block::build(&BuildL2BlockArgs {
+            version,
+            network_id: [0xab; 32].into(),
+            epoch: 7,
+            prev_block_root: [0x01; 32].into(),
+            proposer_pubkey: [9; 48].into(),
+            data: vec![1, 2, 3, 4, 5],
+            extra_emissions: vec![Emission {
+                pubkey: [0x33; 48],
+                weight: 7,
+            }],
+            attester_pubkeys: &attesters,
+            cfg: &cfg,
+            timestamp: if version >= TIMESTAMP_VERSION {
+                1_700_000_000
+            } else {
+                0
+            },
+            extra_data: if version >= EXTRA_DATA_VERSION {
+                [0x5e; 32]
+            } else {
+                [0; 32]
+            },
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn domain_version_zero_vectors_are_pinned() {
+        assert_eq!(DOMAIN_VERSION, 0);
+        assert_eq!(domain(BLOCK_ROOT_DOMAIN), BLOCK_ROOT_DOMAIN);
+        assert_eq!(
+            hex::encode(EMPTY_MERKLE_ROOT()),
+            "ab3250a3b9a717239f7ca528161e0c4fe13bcf762e9ea8424675d034fdffed7e"
+        );
+        for (version, expected) in [
+            (
+                1,
+                "0x83d1771dc2c72c125533a3755b4fd635fd97bc3da5cdf87d99c803b0601ed8e0",
+            ),
+            (
+                2,
+                "0x62d4f565bf880015114c909b721abbeb3f0e0f7f00236c9fdfff3761699dfc8b",
+            ),
+            (
+                3,
+                "0xdd07ae7140d7123c05e88f7ea2cc4d3ec12c275b1a1312c07a5d347c18024c70",
+            ),
+            (
+                4,
+                "0xf283017b12f4d080ca8089fb92e09854fa0847c3ffb218efc2e3cf6f3e44d91c",
+            ),
+        ] {
+            assert_eq!(pinned_block(version).calculate_root_hex(), expected);
+        }
+    }
+
+    #[test]
+    fn bumping_domain_version_changes_every_root() {
+        let block = pinned_block(1);
+        let root_v0 = block.calculate_root();
+        assert_eq!(block.calculate_root_with(&BumpedDomain(0)), root_v0);
+
+        let v1 = BumpedDomain(1);
+        assert_ne!(block.calculate_root_with(&v1), root_v0);
+        let body_root_v1 =
+            block
+                .body
+                .calculate_root_for_version_with(&v1, 1, &mut Vec::new(), &mut Vec::new());
+        assert_ne!(body_root_v1, block.header.body_root, "sealed under v0");
+
+        let empty_v1 = merkle_root_in(&v1, &[], &mut Vec::new());
+        assert_ne!(empty_v1, EMPTY_MERKLE_ROOT());
+        assert_eq!(
+            empty_v1,
+            sha256_concat(&[&domain_for(1, MERKLE_EMPTY_DOMAIN)])
+        );
+        assert_eq!(domain_for(1, BLOCK_ROOT_DOMAIN), b"dig:l2:block_root:\x01");
+
+        let tagged = emission_hash_with(&v1, FieldEncoding::Concatenated, &[4u8; 48], 9);
+        let mut tag = EMISSION_HASH_DOMAIN.to_vec();
+        tag.push(1);
+        assert_eq!(
+            tagged,
+            sha256_concat(&[&tag, &[4u8; 48], &9u64.to_le_bytes()])
+        );
+    }
+
+    #[test]
+    fn length_prefixes_separate_shifted_field_boundaries() {
+        // Label "data_count" + value vs label "data" + value "_count" || value
+        // concatenate to the same bytes before LENGTH_PREFIXED_FIELDS_VERSION.
+        let value = 3u32.to_le_bytes();
+        let mut shifted = b"_count".to_vec();
+        shifted.extend_from_slice(&value);
+        let fields = |version| {
+            let encoding = FieldEncoding::for_version(version);
+            (
+                header_field_leaf(encoding, b"data_count", &value),
+                header_field_leaf(encoding, b"data", &shifted),
+            )
+        };
+        let (a5, b5) = fields(LABELED_BODY_ROOT_VERSION);
+        assert_eq!(a5, b5);
+        let (a6, b6) = fields(LENGTH_PREFIXED_FIELDS_VERSION);
+        assert_ne!(a6, b6);
+        assert_eq!(
+            a6,
+            sha256_concat(&[
+                HEADER_FIELD_DOMAIN,
+                &10u32.to_le_bytes(),
+                b"data_count",
+                &4u32.to_le_bytes(),
+                &value
+            ])
+        );
+    }
+
+    #[test]
+    fn length_prefixed_version_commits_through_public_roots() {
+        let v = LENGTH_PREFIXED_FIELDS_VERSION;
+        let prefixed = |tag: &[u8], parts: &[&[u8]]| {
+            let lengths: Vec<[u8; 4]> = parts
+                .iter()
+                .map(|p| (p.len() as u32).to_le_bytes())
+                .collect();
+            let mut all: Vec<&[u8]> = vec![tag];
+            for (len, part) in lengths.iter().zip(parts) {
+                all.push(len);
+                all.push(part);
+            }
+            sha256_concat(&all)
+        };
+
+        let w = 9u64.to_le_bytes();
+        assert_eq!(
+            COMPUTE_EMISSION_HASH_FOR_VERSION(v, &[4u8; 48], 9),
+            prefixed(EMISSION_HASH_DOMAIN, &[&[4u8; 48], &w])
+        );
+        assert_eq!(
+            COMPUTE_EMISSION_HASH_FOR_VERSION(v - 1, &[4u8; 48], 9),
+            COMPUTE_EMISSION_HASH(&[4u8; 48], 9)
+        );
+
+        let (d, e) = ([1u8; 32], [2u8; 32]);
+        assert_eq!(
+            COMPUTE_BODY_ROOT_FOR_VERSION(v, &d, &e),
+            MERKLE_ROOT(&[
+                prefixed(BODY_FIELD_DOMAIN, &[b"data_root", &d]),
+                prefixed(BODY_FIELD_DOMAIN, &[b"emissions_root", &e]),
+            ])
+        );
+
+        // A v6 block seals and verifies with prefixed header, body and
+        // emission leaves; the same contents at v5 give a different root.
+        let block = pinned_block(v);
+        assert!(block.verify(None).is_ok());
+        let header = &block.header;
+        let version = v.to_le_bytes();
+        let epoch = header.epoch.to_le_bytes();
+        let data_count = header.data_count.to_le_bytes();
+        let emissions_count = header.emissions_count.to_le_bytes();
+        let timestamp = header.timestamp.to_le_bytes();
+        let leaves: Vec<Hash32> = [
+            (&b"version"[..], &version[..]),
+            (b"network_id", &header.network_id),
+            (b"epoch", &epoch),
+            (b"prev_block_root", &header.prev_block_root),
+            (b"body_root", &header.body_root),
+            (b"data_count", &data_count),
+            (b"emissions_count", &emissions_count),
+            (b"proposer_pubkey", &header.proposer_pubkey),
+            (b"timestamp", &timestamp),
+            (b"extra_data", &header.extra_data),
+        ]
+        .iter()
+        .map(|(label, value)| prefixed(HEADER_FIELD_DOMAIN, &[label, value]))
+        .collect();
+        assert_eq!(COMPUTE_HEADER_ROOT_HEADER(header), MERKLE_ROOT(&leaves));
+
+        let mut emission_leaves: Vec<Hash32> = block
+            .body
+            .emissions
+            .iter()
+            .map(|em| COMPUTE_EMISSION_HASH_FOR_VERSION(v, &em.pubkey, em.weight))
+            .collect();
+        emission_leaves.sort_unstable();
+        assert_eq!(
+            block.body.calculate_emissions_root_for_version(v),
+            MERKLE_ROOT(&emission_leaves)
+        );
+        assert_ne!(
+            block.body.calculate_emissions_root_for_version(v),
+            block.body.calculate_emissions_root()
+        );
+        assert_ne!(
+            pinned_block(v - 1).body.calculate_root_for_version(v - 1),
+            block.header.body_root
+        );
+    }
+
+    #[test]
+    fn merkle_root_checked_guards_leaf_count() {
+        let leaves = leaves_of(7);
+        assert_eq!(MERKLE_ROOT_CHECKED(&leaves).unwrap(), MERKLE_ROOT(&leaves));
+        assert_eq!(MERKLE_ROOT_CHECKED(&[]).unwrap(), EMPTY_MERKLE_ROOT());
+        // Such slices cannot be materialized, so exercise the guard directly.
+        assert!(check_leaf_count(MAX_MERKLE_LEAVES).is_ok());
+        for count in [MAX_MERKLE_LEAVES + 1, usize::MAX / 2, usize::MAX] {
+            assert!(matches!(
+                check_leaf_count(count),
+                Err(DefinitionError::TooManyLeaves { count: c, max }) if c == count && max == MAX_MERKLE_LEAVES
+            ));
+        }
+    }
+
+    #[test]
+    fn proof_encoding_round_trips_and_verifies() {
+        for n in [1usize, 2, 5, 300, 1 << 10 | 1] {
+            let leaves = leaves_of(n);
+            let tree = MerkleTree::new(&leaves);
+            for i in [0, n / 2, n - 1] {
+                let proof = tree.proof(i).unwrap();
+                let bytes = encode_proof(&proof);
+                assert_eq!(bytes.len(), 1 + proof.len().div_ceil(8) + 32 * proof.len());
+                let decoded = decode_proof(&bytes).unwrap();
+                assert_eq!(decoded, proof);
+                assert!(MERKLE_VERIFY(&leaves[i], &decoded, &tree.root()));
+            }
+        }
+        let depth20: Vec<(Hash32, bool)> = (0..20).map(|i| (h32(i), i % 3 == 0)).collect();
+        assert_eq!(encode_proof(&depth20).len(), 644);
+        assert_eq!(decode_proof(&encode_proof(&[])).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn emission_hash_slice_checks_length() {
+        let pubkey = vec![0x42u8; 48];
+        assert_eq!(
+            compute_emission_hash_slice(&pubkey, 7).unwrap(),
+            COMPUTE_EMISSION_HASH(&[0x42u8; 48], 7)
+        );
+        for len in [0, 47, 49, 96] {
+            assert!(matches!(
+                compute_emission_hash_slice(&vec![0x42u8; len], 7),
+                Err(DefinitionError::BadPubkeyLength { len: l }) if l == len
+            ));
+        }
+    }
+
+    #[test]
+    fn decode_proof_rejects_malformed_input() {
+        assert!(matches!(
+            decode_proof(&[]),
+            Err(DefinitionError::ProofLengthMismatch { .. })
+        ));
+        let bytes = encode_proof(&[(h32(1), true), (h32(2), false)]);
+        assert!(matches!(
+            decode_proof(&bytes[..bytes.len() - 1]),
+            Err(DefinitionError::ProofLengthMismatch {
+                expected: 66,
+                actual: 65
+            })
+        ));
+        let mut padded = bytes.clone();
+        padded[1] |= 0x80;
+        assert!(matches!(
+            decode_proof(&padded),
+            Err(DefinitionError::ProofPaddingBits)
+        ));
+    }
+
+    #[test]
+    fn single_leaf_proof_is_empty() {
+        let leaf = h32(42);
+        let tree = MerkleTree::new(&[leaf]);
+        assert_eq!(tree.proof(0), Some(Vec::new()));
+        assert_eq!(MERKLE_PROOF(&[leaf], 0), Some(Vec::new()));
+        assert_eq!(tree.proof(1), None);
+        assert_eq!(tree.proof_for_leaf(&leaf), Some((0, Vec::new())));
+        assert_eq!(decode_proof(&encode_proof(&[])).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn single_leaf_proof_verifies_against_leaf_hash() {
+        let leaf = h32(42);
+        let root = MERKLE_ROOT(&[leaf]);
+        assert_eq!(root, sha256_concat(&[MERKLE_LEAF_DOMAIN, &leaf]));
+        assert!(MERKLE_VERIFY(&leaf, &[], &root));
+        // The raw leaf is not the root, and other leaves do not verify.
+        assert!(!MERKLE_VERIFY(&leaf, &[], &leaf));
+        assert!(!MERKLE_VERIFY(&h32(43), &[], &root));
+        // Neither does the empty-tree root.
+        assert!(!MERKLE_VERIFY(&leaf, &[], &EMPTY_MERKLE_ROOT()));
+
+        // A single-emission body proves its only leaf with an empty proof.
+        let body = crate::body::L2BlockBody::emissions_only(vec![Emission {
+            pubkey: [7u8; 48],
+            weight: 1,
+        }]);
+        let (leaves, emissions_root) = body.emissions_tree();
+        let proof = MERKLE_PROOF(&leaves, 0).unwrap();
+        assert!(proof.is_empty());
+        assert!(MERKLE_VERIFY(&leaves[0], &proof, &emissions_root));
+    }
+
+    #[test]
+    fn proof_for_leaf_present() {
+        let leaves = [h32(1), h32(2), h32(3), h32(4), h32(5)];
+        let tree = MerkleTree::new(&leaves);
+        let (index, proof) = tree.proof_for_leaf(&h32(4)).unwrap();
+        assert_eq!(index, 3);
+        assert_eq!(proof, tree.proof(3).unwrap());
+        assert!(MERKLE_VERIFY(&h32(4), &proof, &tree.root()));
+    }
+
+    #[test]
+    fn proof_for_leaf_duplicate_returns_first() {
+        let leaves = [h32(1), h32(7), h32(2), h32(7)];
+        let tree = MerkleTree::new(&leaves);
+        let (index, proof) = tree.proof_for_leaf(&h32(7)).unwrap();
+        assert_eq!(index, 1);
+        assert!(MERKLE_VERIFY(&h32(7), &proof, &tree.root()));
+    }
+
+    #[test]
+    fn proof_for_leaf_absent() {
+        let tree = MerkleTree::new(&[h32(1), h32(2), h32(3)]);
+        assert!(tree.proof_for_leaf(&h32(9)).is_none());
+        assert!(MerkleTree::new(&[]).proof_for_leaf(&h32(1)).is_none());
+    }
+
+    fn check_multiproof(leaves: &[Hash32], indices: &[usize]) {
+        let root = MERKLE_ROOT(leaves);
+        let proof = MERKLE_MULTIPROOF(leaves, indices).unwrap();
+        let proven: Vec<Hash32> = proof.indices.iter().map(|i| leaves[*i]).collect();
+        assert!(
+            verify_multiproof(&proof, &proven, &root),
+            "n={} indices={indices:?}",
+            leaves.len()
+        );
+
+        // Never larger than the individual proofs combined.
+        let individual: usize = proof
+            .indices
+            .iter()
+            .map(|i| MERKLE_PROOF(leaves, *i).unwrap().len())
+            .sum();
+        assert!(proof.siblings.len() <= individual);
+
+        // Tampering with any proven leaf or sibling breaks verification.
+        let mut bad_leaves = proven.clone();
+        bad_leaves[0][31] ^= 1;
+        assert!(!verify_multiproof(&proof, &bad_leaves, &root));
+        if !proof.siblings.is_empty() {
+            let mut bad = proof.clone();
+            bad.siblings[0][0] ^= 1;
+            assert!(!verify_multiproof(&bad, &proven, &root));
+            bad.siblings.pop();
+            assert!(!verify_multiproof(&bad, &proven, &root));
+        }
+        let mut surplus = proof.clone();
+        surplus.siblings.push(h32(0));
+        assert!(!verify_multiproof(&surplus, &proven, &root));
+    }
+
+    #[test]
+    fn multiproof_overlapping_and_disjoint_sets() {
+        for n in 2..=16usize {
+            let leaves: Vec<Hash32> = (0..n as u8).map(h32).collect();
+            // Singles and adjacent (overlapping-path) pairs.
+            for i in 0..n {
+                check_multiproof(&leaves, &[i]);
+                if i + 1 < n {
+                    check_multiproof(&leaves, &[i, i + 1]);
+                }
+            }
+            // Disjoint sets: evens, odds, first + last.
+            let evens: Vec<usize> = (0..n).step_by(2).collect();
+            let odds: Vec<usize> = (1..n).step_by(2).collect();
+            check_multiproof(&leaves, &evens);
+            check_multiproof(&leaves, &odds);
+            check_multiproof(&leaves, &[0, n - 1]);
+            // Everything, given unsorted with repeats.
+            let mut all: Vec<usize> = (0..n).rev().collect();
+            all.push(0);
+            check_multiproof(&leaves, &all);
+            // All subsets for small trees.
+            if n <= 8 {
+                for mask in 1..(1u32 << n) {
+                    let set: Vec<usize> = (0..n).filter(|i| mask & (1 << i) != 0).collect();
+                    check_multiproof(&leaves, &set);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn multiproof_dedupes_shared_nodes() {
+        let leaves: Vec<Hash32> = (0..8u8).map(h32).collect();
+        // Siblings 0 and 1 prove each other; only the two uncle subtrees are needed.
+        let proof = MERKLE_MULTIPROOF(&leaves, &[0, 1]).unwrap();
+        assert_eq!(proof.siblings.len(), 2);
+        // With every leaf proven, nothing extra is needed.
+        let all: Vec<usize> = (0..8).collect();
+        assert!(
+            MERKLE_MULTIPROOF(&leaves, &all)
+                .unwrap()
+                .siblings
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn multiproof_rejects_bad_inputs() {
+        let leaves: Vec<Hash32> = (0..4u8).map(h32).collect();
+        assert!(MERKLE_MULTIPROOF(&leaves, &[]).is_none());
+        assert!(MERKLE_MULTIPROOF(&leaves, &[4]).is_none());
+        assert!(MERKLE_MULTIPROOF(&[], &[0]).is_none());
+
+        let root = MERKLE_ROOT(&leaves);
+        let proof = MERKLE_MULTIPROOF(&leaves, &[1, 2]).unwrap();
+        assert!(!verify_multiproof(&proof, &[leaves[1]], &root));
+        let mut unsorted = proof.clone();
+        unsorted.indices = vec![2, 1];
+        assert!(!verify_multiproof(
+            &unsorted,
+            &[leaves[2], leaves[1]],
+            &root
+        ));
+    }
+
     #[test]
     fn body_root_is_merkle_of_two() {
         let d = h32(0x11);
@@ -239,6 +2137,43 @@ mod tests {
         assert_eq!(r, r2);
     }
 
+    #[test]
+    fn body_root_depends_on_subroot_order() {
+        let (a, b) = (h32(0x11), h32(0x22));
+        assert_ne!(COMPUTE_BODY_ROOT(&a, &b), COMPUTE_BODY_ROOT(&b, &a));
+        assert_ne!(
+            COMPUTE_BODY_ROOT_LABELED(&a, &b),
+            COMPUTE_BODY_ROOT_LABELED(&b, &a)
+        );
+    }
+
+    #[test]
+    fn labeled_body_root_vector() {
+        let (d, e) = (h32(0x11), h32(0x22));
+        let leaves = [
+            sha256_concat(&[BODY_FIELD_DOMAIN, b"data_root", &d]),
+            sha256_concat(&[BODY_FIELD_DOMAIN, b"emissions_root", &e]),
+        ];
+        let labeled = COMPUTE_BODY_ROOT_LABELED(&d, &e);
+        assert_eq!(labeled, MERKLE_ROOT(&leaves));
+        assert_eq!(
+            hex::encode(labeled),
+            "0e30307630008c1606cd98c8178fb60de7af7d37ea463503ebac76dd6ae596ab"
+        );
+        assert_ne!(labeled, COMPUTE_BODY_ROOT(&d, &e));
+
+        for version in 1..LABELED_BODY_ROOT_VERSION {
+            assert_eq!(
+                COMPUTE_BODY_ROOT_FOR_VERSION(version, &d, &e),
+                COMPUTE_BODY_ROOT(&d, &e)
+            );
+        }
+        assert_eq!(
+            COMPUTE_BODY_ROOT_FOR_VERSION(LABELED_BODY_ROOT_VERSION, &d, &e),
+            labeled
+        );
+    }
+
     #[test]
     fn header_root_field_permutation_changes_root() {
         let network_id = [2u8; 32];
@@ -254,6 +2189,8 @@ mod tests {
             data_count: 3,
             emissions_count: 4,
             proposer_pubkey: proposer,
+            timestamp: 0,
+            extra_data: [0u8; 32],
         };
         let r2_header = L2BlockHeader {
             version: 1,
@@ -264,12 +2201,57 @@ mod tests {
             data_count: 4,
             emissions_count: 3,
             proposer_pubkey: proposer,
+            timestamp: 0,
+            extra_data: [0u8; 32],
         };
-        let r1 = COMPUTE_HEADER_ROOT(&r1_header);
-        let r2 = COMPUTE_HEADER_ROOT(&r2_header);
+        let r1 = COMPUTE_HEADER_ROOT_HEADER(&r1_header);
+        let r2 = COMPUTE_HEADER_ROOT_HEADER(&r2_header);
         assert_ne!(r1, r2);
     }
 
+    #[test]
+    fn header_root_struct_and_field_forms_agree() {
+        for version in 1..=EXTRA_DATA_VERSION {
+            let h = L2BlockHeader {
+                version,
+                network_id: [2u8; 32],
+                epoch: 9,
+                prev_block_root: [3u8; 32],
+                body_root: [4u8; 32],
+                data_count: 5,
+                emissions_count: 6,
+                proposer_pubkey: [7u8; 48],
+                timestamp: if version >= TIMESTAMP_VERSION {
+                    1_700
+                } else {
+                    0
+                },
+                extra_data: if version >= EXTRA_DATA_VERSION {
+                    [8u8; 32]
+                } else {
+                    [0u8; 32]
+                },
+            };
+            let fields = COMPUTE_HEADER_ROOT_FIELDS(
+                h.version,
+                &h.network_id,
+                h.epoch,
+                &h.prev_block_root,
+                &h.body_root,
+                h.data_count,
+                h.emissions_count,
+                &h.proposer_pubkey,
+                h.timestamp,
+                &h.extra_data,
+            );
+            assert_eq!(COMPUTE_HEADER_ROOT_HEADER(&h), fields);
+            assert_eq!(header_root_with(&Sha256Hasher, &h), fields);
+            #[allow(deprecated)]
+            let legacy = COMPUTE_HEADER_ROOT(&h);
+            assert_eq!(legacy, fields);
+        }
+    }
+
     #[test]
     fn block_root_composition() {
         let header_root = h32(0xaa);
@@ -292,6 +2274,55 @@ mod tests {
         assert_eq!(v[3].1, 29);
     }
 
+    #[test]
+    fn build_consensus_emissions_spec_matches_tuples() {
+        let proposer = [7u8; 48];
+        let attesters = [[1u8; 48], [2u8; 48]];
+        let tuples = BUILD_CONSENSUS_EMISSIONS(proposer, &attesters, 12, 88).unwrap();
+        let specs = BUILD_CONSENSUS_EMISSIONS_SPEC(proposer, &attesters, 12, 88).unwrap();
+        assert_eq!(specs.len(), tuples.len());
+        for (spec, tuple) in specs.iter().zip(&tuples) {
+            assert_eq!(EmissionTuple::from(*spec), *tuple);
+            assert_eq!(EmissionSpec::from(*tuple), *spec);
+        }
+        assert_eq!(
+            specs[0],
+            EmissionSpec {
+                pubkey: proposer,
+                weight: 12
+            }
+        );
+        assert_eq!(specs[1].weight, 44);
+        assert!(matches!(
+            BUILD_CONSENSUS_EMISSIONS_SPEC(proposer, &[], 12, 1),
+            Err(DefinitionError::NoAttestersForNonZeroShare)
+        ));
+    }
+
+    #[test]
+    fn build_chain_links_blocks_and_validates() {
+        let specs = (0..4u8).map(|i| BlockSpec {
+            version: 1,
+            network_id: [0xabu8; 32],
+            proposer_pubkey: [i + 1; 48],
+            data: vec![i; i as usize],
+            extra_emissions: vec![],
+            attester_pubkeys: vec![[0x70 + i; 48]],
+            cfg: ConsensusEmissionConfig::new(12, 88),
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        });
+        let chain = build_chain(specs).unwrap();
+        assert_eq!(chain.len(), 4);
+        assert_eq!(chain[0].header.epoch, 0);
+        assert_eq!(chain[0].header.prev_block_root, [0u8; 32]);
+        for pair in chain.windows(2) {
+            assert_eq!(pair[1].header.prev_block_root, pair[0].calculate_root());
+            assert_eq!(pair[1].header.epoch, pair[0].header.epoch + 1);
+        }
+        crate::block::validate_chain(&chain).unwrap();
+    }
+
     #[test]
     fn build_consensus_emissions_zero_attesters_policy() {
         let proposer = [9u8; 48];
@@ -301,6 +2332,7 @@ mod tests {
         let err = BUILD_CONSENSUS_EMISSIONS(proposer, &[], 12, 1).unwrap_err();
         match err {
             DefinitionError::NoAttestersForNonZeroShare => {}
+            other => panic!("unexpected error: {other:?}"),
         }
     }
 }
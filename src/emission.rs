@@ -13,7 +13,11 @@ use thiserror::Error;
 /// The `pubkey` is a BLS public key (48 bytes), and `weight` is the relative
 /// share in the reward pool. JSON encodes `pubkey` as a `0x`-prefixed hex
 /// string, and `weight` as a number.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Emissions order by `pubkey` bytes, then `weight`: the same key that breaks
+/// leaf-hash ties in the `EMISSIONS_ROOT`. It is not the Merkle leaf order
+/// itself, which sorts by hash first (see `L2BlockBody::emissions_in_merkle_order`).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Emission {
     /// BLS public key (48 bytes), serialized as `0x` hex in JSON.
     #[serde(with = "crate::serde_hex::hex48")]
@@ -23,12 +27,98 @@ pub struct Emission {
 }
 
 impl Emission {
+    /// Creates an emission after checking that `pubkey` is a well-formed
+    /// compressed BLS12-381 G1 point in the prime-order subgroup
+    /// (`bls::validate_g1_pubkey`; feature `bls`).
+    ///
+    /// Constructing the struct directly stays unchecked for callers that have
+    /// already validated their keys.
+    #[cfg(feature = "bls")]
+    pub fn new_checked(pubkey: [u8; 48], weight: u64) -> Result<Self, EmissionError> {
+        crate::bls::validate_g1_pubkey(&pubkey)?;
+        Ok(Emission { pubkey, weight })
+    }
+
+    /// Rejects obviously junk records: an all-zero `pubkey` (`ZeroPubkey`) and,
+    /// unless `allow_zero_weight`, a zero `weight` (`ZeroWeight`), which only
+    /// adds a leaf without paying anyone.
+    pub fn validate(&self, allow_zero_weight: bool) -> Result<(), EmissionError> {
+        if self.pubkey == [0u8; 48] {
+            return Err(EmissionError::ZeroPubkey);
+        }
+        if !allow_zero_weight && self.weight == 0 {
+            return Err(EmissionError::ZeroWeight {
+                pubkey: self.pubkey,
+            });
+        }
+        Ok(())
+    }
+
     /// Computes the per-emission hash as defined by the spec using
     /// `COMPUTE_EMISSION_HASH`. This value can serve directly as a leaf for
     /// inclusion in the emissions Merkle tree.
     pub fn calculate_root(&self) -> definitions::Hash32 {
         definitions::COMPUTE_EMISSION_HASH(&self.pubkey, self.weight)
     }
+
+    /// The per-emission hash as a `0x`-prefixed lowercase hex string.
+    pub fn calculate_root_hex(&self) -> String {
+        crate::serde_hex::encode_lower_hex_prefixed(&self.calculate_root())
+    }
+
+    /// `pubkey` as a borrowed slice.
+    pub fn pubkey_bytes(&self) -> &[u8] {
+        &self.pubkey
+    }
+}
+
+/// Borrows the recipient `pubkey`, e.g. to pass an emission where a key is expected.
+impl AsRef<[u8; 48]> for Emission {
+    fn as_ref(&self) -> &[u8; 48] {
+        &self.pubkey
+    }
+}
+
+impl From<definitions::EmissionTuple> for Emission {
+    fn from((pubkey, weight): definitions::EmissionTuple) -> Self {
+        Emission { pubkey, weight }
+    }
+}
+
+impl From<Emission> for definitions::EmissionTuple {
+    fn from(e: Emission) -> Self {
+        (e.pubkey, e.weight)
+    }
+}
+
+impl From<definitions::EmissionSpec> for Emission {
+    fn from(spec: definitions::EmissionSpec) -> Self {
+        Emission {
+            pubkey: spec.pubkey,
+            weight: spec.weight,
+        }
+    }
+}
+
+impl From<Emission> for definitions::EmissionSpec {
+    fn from(e: Emission) -> Self {
+        definitions::EmissionSpec {
+            pubkey: e.pubkey,
+            weight: e.weight,
+        }
+    }
+}
+
+/// Hashes exactly the fields compared by `Eq` (`pubkey`, then `weight`), so
+/// equal emissions always hash equally and `Emission` can key a `HashMap`.
+///
+/// This deliberately does not feed `calculate_root()` into the hasher: both
+/// identify the same content, but a SHA-256 per lookup would be wasted work.
+impl std::hash::Hash for Emission {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.pubkey.hash(state);
+        self.weight.hash(state);
+    }
 }
 
 /// Errors originating from `Emission`-level operations.
@@ -37,6 +127,19 @@ pub enum EmissionError {
     /// Placeholder for future validation errors (kept to satisfy file-level error requirement).
     #[error("emission error: {0}")]
     Generic(String),
+
+    /// The emission has zero weight.
+    #[error("zero-weight emission to 0x{}", hex::encode(.pubkey))]
+    ZeroWeight { pubkey: [u8; 48] },
+
+    /// The emission pubkey is all zeros.
+    #[error("emission pubkey is all zeros")]
+    ZeroPubkey,
+
+    /// The pubkey is not a valid compressed G1 point.
+    #[cfg(feature = "bls")]
+    #[error("invalid emission pubkey: {0}")]
+    InvalidPubkey(#[from] crate::bls::PubkeyError),
 }
 
 #[cfg(test)]
@@ -54,6 +157,165 @@ mod tests {
         assert_eq!(h1, h2);
     }
 
+    #[cfg(feature = "bls")]
+    #[test]
+    fn new_checked_validates_pubkey() {
+        let generator: [u8; 48] = hex::decode(
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+        let e = Emission::new_checked(generator, 9).unwrap();
+        assert_eq!(
+            e,
+            Emission {
+                pubkey: generator,
+                weight: 9
+            }
+        );
+        assert!(matches!(
+            Emission::new_checked([0u8; 48], 9),
+            Err(EmissionError::InvalidPubkey(
+                crate::bls::PubkeyError::NotCompressed
+            ))
+        ));
+        // A curve point of order 3, outside the subgroup.
+        let mut order_three = [0u8; 48];
+        order_three[0] = 0x80;
+        assert!(matches!(
+            Emission::new_checked(order_three, 9),
+            Err(EmissionError::InvalidPubkey(
+                crate::bls::PubkeyError::NotInSubgroup
+            ))
+        ));
+    }
+
+    #[test]
+    fn validate_flags_zero_weight_and_pubkey() {
+        let e = Emission {
+            pubkey: [7u8; 48],
+            weight: 1,
+        };
+        assert!(e.validate(false).is_ok());
+
+        let zero_weight = Emission { weight: 0, ..e };
+        assert!(zero_weight.validate(true).is_ok());
+        assert!(matches!(
+            zero_weight.validate(false),
+            Err(EmissionError::ZeroWeight { pubkey }) if pubkey == [7u8; 48]
+        ));
+
+        let junk = Emission {
+            pubkey: [0u8; 48],
+            weight: 0,
+        };
+        for allow in [true, false] {
+            assert!(matches!(
+                junk.validate(allow),
+                Err(EmissionError::ZeroPubkey)
+            ));
+        }
+    }
+
+    #[test]
+    fn tuple_conversion_round_trips() {
+        let tuple: definitions::EmissionTuple = ([4u8; 48], 77);
+        let e = Emission::from(tuple);
+        assert_eq!(
+            e,
+            Emission {
+                pubkey: [4u8; 48],
+                weight: 77
+            }
+        );
+        assert_eq!(definitions::EmissionTuple::from(e), tuple);
+
+        let tuples =
+            definitions::BUILD_CONSENSUS_EMISSIONS([1u8; 48], &[[2u8; 48]], 12, 88).unwrap();
+        let emissions: Vec<Emission> = tuples.iter().copied().map(Emission::from).collect();
+        assert_eq!(emissions[1].pubkey, [2u8; 48]);
+        assert_eq!(emissions[1].weight, 88);
+    }
+
+    #[test]
+    fn spec_conversion_round_trips() {
+        let spec = definitions::EmissionSpec {
+            pubkey: [4u8; 48],
+            weight: 77,
+        };
+        let e = Emission::from(spec);
+        assert_eq!(
+            e,
+            Emission {
+                pubkey: [4u8; 48],
+                weight: 77
+            }
+        );
+        assert_eq!(definitions::EmissionSpec::from(e), spec);
+    }
+
+    #[test]
+    fn pubkey_accessors_borrow_field() {
+        let e = Emission {
+            pubkey: [3u8; 48],
+            weight: 1,
+        };
+        assert_eq!(e.pubkey_bytes(), &e.pubkey[..]);
+        let key: &[u8; 48] = e.as_ref();
+        assert!(std::ptr::eq(key, &e.pubkey));
+    }
+
+    #[test]
+    fn emission_root_hex_matches_root() {
+        let e = Emission {
+            pubkey: [3u8; 48],
+            weight: 1,
+        };
+        assert_eq!(
+            e.calculate_root_hex(),
+            format!("0x{}", hex::encode(e.calculate_root()))
+        );
+    }
+
+    #[test]
+    fn emissions_sort_by_pubkey_then_weight() {
+        let e = |p: u8, weight: u64| Emission {
+            pubkey: [p; 48],
+            weight,
+        };
+        let mut shuffled = vec![e(2, 1), e(1, 9), e(3, 0), e(1, 2), e(2, 0)];
+        shuffled.sort();
+        assert_eq!(shuffled, vec![e(1, 2), e(1, 9), e(2, 0), e(2, 1), e(3, 0)]);
+        // Pubkey bytes compare before weight.
+        let mut high = [1u8; 48];
+        high[47] = 2;
+        assert!(
+            e(1, u64::MAX)
+                < Emission {
+                    pubkey: high,
+                    weight: 0
+                }
+        );
+    }
+
+    #[test]
+    fn emission_hash_set_dedupes_identical() {
+        use std::collections::HashSet;
+        let a = Emission {
+            pubkey: [1u8; 48],
+            weight: 5,
+        };
+        let b = Emission {
+            pubkey: [1u8; 48],
+            weight: 6,
+        };
+        let set: HashSet<Emission> = [a.clone(), b.clone(), a.clone()].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&a));
+        assert!(set.contains(&b));
+    }
+
     #[test]
     fn emission_json_round_trip() {
         let e = Emission {
@@ -9,10 +9,20 @@
 
 use thiserror::Error;
 
+/// Basis points in a whole (100%).
+pub const BASIS_POINTS_TOTAL: u16 = 10_000;
+
+/// Mainnet proposer share: a weight of 12 (12% of the combined 100).
+pub const MAINNET_PROPOSER_REWARD_SHARE: u64 = 12;
+
+/// Mainnet total attester share: a weight of 88 (88%), split equally between
+/// the block's attesters.
+pub const MAINNET_ATTESTER_REWARD_SHARE: u64 = 88;
+
 /// Configuration for consensus emissions.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ConsensusEmissionConfig {
-    /// Fixed proposer share (e.g., 12 for 12.5%).
+    /// Fixed proposer share, as a weight (e.g., 12 for the mainnet 12%).
     pub proposer_reward_share: u64,
     /// Total attester share that will be equally split among attesters using
     /// integer division; remainder (if any) is undistributed.
@@ -28,6 +38,47 @@ impl ConsensusEmissionConfig {
         }
     }
 
+    /// The canonical mainnet split, `new(12, 88)`: 12% to the proposer and 88%
+    /// shared by the attesters (`MAINNET_PROPOSER_REWARD_SHARE`,
+    /// `MAINNET_ATTESTER_REWARD_SHARE`).
+    ///
+    /// The shares are plain weights, not basis points, so the fraction helpers
+    /// do not apply to this config.
+    pub fn mainnet_default() -> Self {
+        Self::new(MAINNET_PROPOSER_REWARD_SHARE, MAINNET_ATTESTER_REWARD_SHARE)
+    }
+
+    /// Create a config from percentages in basis points (`1_250` = 12.5%),
+    /// stored directly as the shares.
+    ///
+    /// Rejects splits over 100% (`proposer_bps + attester_bps > 10_000`) with
+    /// `BasisPointsOverTotal`.
+    pub fn from_basis_points(
+        proposer_bps: u16,
+        attester_bps: u16,
+    ) -> Result<Self, EmissionConfigError> {
+        if u32::from(proposer_bps) + u32::from(attester_bps) > u32::from(BASIS_POINTS_TOTAL) {
+            return Err(EmissionConfigError::BasisPointsOverTotal {
+                proposer_bps,
+                attester_bps,
+            });
+        }
+        Ok(Self::new(proposer_bps.into(), attester_bps.into()))
+    }
+
+    /// The proposer share as a fraction of `BASIS_POINTS_TOTAL`, for display.
+    ///
+    /// Only meaningful for shares given in basis points (`from_basis_points`).
+    pub fn proposer_fraction(&self) -> f64 {
+        self.proposer_reward_share as f64 / f64::from(BASIS_POINTS_TOTAL)
+    }
+
+    /// The total attester share as a fraction of `BASIS_POINTS_TOTAL`, for
+    /// display. Only meaningful for shares given in basis points.
+    pub fn attester_fraction(&self) -> f64 {
+        self.attester_reward_share as f64 / f64::from(BASIS_POINTS_TOTAL)
+    }
+
     /// Validate the config against a given number of attesters.
     ///
     /// Policy: if there are zero attesters, `attester_reward_share` must be 0;
@@ -40,12 +91,26 @@ impl ConsensusEmissionConfig {
     }
 }
 
+/// `ConsensusEmissionConfig::mainnet_default`.
+impl Default for ConsensusEmissionConfig {
+    fn default() -> Self {
+        Self::mainnet_default()
+    }
+}
+
 /// Errors that can be produced by configuration validation.
 #[derive(Debug, Error)]
 pub enum EmissionConfigError {
     /// `attester_reward_share > 0` while there are zero attesters.
     #[error("non-zero attester share configured but no attesters provided")]
     NonZeroAttesterShareWithNoAttesters,
+
+    /// `from_basis_points` shares add up to more than 100%.
+    #[error("basis points exceed 100%: proposer {proposer_bps} + attester {attester_bps}")]
+    BasisPointsOverTotal {
+        proposer_bps: u16,
+        attester_bps: u16,
+    },
 }
 
 #[cfg(test)]
@@ -61,9 +126,58 @@ mod tests {
         let err = cfg_bad.validate_for_attesters(0).unwrap_err();
         match err {
             EmissionConfigError::NonZeroAttesterShareWithNoAttesters => {}
+            other => panic!("unexpected error: {other:?}"),
         }
     }
 
+    #[test]
+    fn from_basis_points_validates_total() {
+        let cfg = ConsensusEmissionConfig::from_basis_points(1_250, 8_750).unwrap();
+        assert_eq!(cfg, ConsensusEmissionConfig::new(1_250, 8_750));
+        assert_eq!(cfg.proposer_fraction(), 0.125);
+        assert_eq!(cfg.attester_fraction(), 0.875);
+
+        let partial = ConsensusEmissionConfig::from_basis_points(500, 0).unwrap();
+        assert_eq!(partial.proposer_fraction(), 0.05);
+        assert_eq!(partial.attester_fraction(), 0.0);
+
+        assert!(matches!(
+            ConsensusEmissionConfig::from_basis_points(1_250, 8_751),
+            Err(EmissionConfigError::BasisPointsOverTotal {
+                proposer_bps: 1_250,
+                attester_bps: 8_751
+            })
+        ));
+        // The sum is checked without u16 wrap-around.
+        assert!(ConsensusEmissionConfig::from_basis_points(u16::MAX, u16::MAX).is_err());
+    }
+
+    #[test]
+    fn mainnet_default_is_12_88() {
+        let cfg = ConsensusEmissionConfig::default();
+        assert_eq!(cfg, ConsensusEmissionConfig::mainnet_default());
+        assert_eq!(cfg, ConsensusEmissionConfig::new(12, 88));
+
+        let attesters = [[2u8; 48], [3u8; 48]];
+        let args = crate::block::BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 0,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![],
+            extra_emissions: vec![],
+            attester_pubkeys: &attesters,
+            cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        };
+        let block = crate::block::DigL2Block::build(&args).unwrap();
+        let proposer = block.body.get_emission(&[9u8; 48]).unwrap();
+        assert_eq!(proposer.weight, 12);
+        assert_eq!(block.body.get_emission(&[2u8; 48]).unwrap().weight, 44);
+    }
+
     #[test]
     fn validate_with_attesters_ok() {
         let cfg = ConsensusEmissionConfig::new(12, 88);
@@ -0,0 +1,726 @@
+//! Pluggable hash functions for root computation.
+//!
+//! Every spec root is defined over SHA-256. `RootHasher` lets the same
+//! domain-separated tree be computed with another 32-byte hash, e.g.
+//! `Keccak256` so roots can be recomputed cheaply inside the EVM. Domain tags
+//! and leaf layouts are identical for every hasher; only the hash changes.
+//!
+//! A hasher also picks the byte order of hashed integers (`Endianness`).
+//! Spec roots are little-endian; wrapping a hasher in `BigEndian` hashes them
+//! big-endian instead, matching e.g. Solidity's `abi.encodePacked`.
+//!
+//! `RootHasher` is resolved at compile time. When the algorithm comes from
+//! configuration instead, `RuntimeHasher` is a streaming, object-safe
+//! alternative (`Sha256State`, `Keccak256State`, `Blake3State`, or
+//! `HashAlgorithm::runtime_hasher`) used through
+//! `DigL2Block::calculate_root_dyn`.
+
+use crate::dig_l2_definition::Hash32;
+use sha2::{Digest, Sha256};
+use std::sync::{Mutex, PoisonError};
+
+/// A 32-byte hash over the concatenation of byte slices.
+///
+/// `Sync` so Merkle levels can be hashed across threads with the `parallel`
+/// feature.
+pub trait RootHasher: Sync {
+    /// Hash of `parts[0] || parts[1] || ...`.
+    fn hash(&self, parts: &[&[u8]]) -> Hash32;
+
+    /// Byte order of the integers fed to `hash`. Little-endian, as in the spec,
+    /// unless overridden.
+    fn endianness(&self) -> Endianness {
+        Endianness::Little
+    }
+}
+
+/// Byte order used to encode integers before hashing.
+///
+/// Affects every integer a root commits to: the header `version`, `epoch`,
+/// `data_count`, `emissions_count` and `timestamp`; emission `weight`; data
+/// chunk and indexed data positions; and the `u32` length prefixes of block
+/// versions from `LENGTH_PREFIXED_FIELDS_VERSION` (6). The SHA-256-only spec
+/// functions (`COMPUTE_INDEXED_EMISSION_HASH`, `MERKLE_ROOT_ARITY`) are always
+/// little-endian.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Least significant byte first (the spec encoding).
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+impl Endianness {
+    /// `v` encoded in this byte order.
+    pub fn u32_bytes(self, v: u32) -> [u8; 4] {
+        match self {
+            Endianness::Little => v.to_le_bytes(),
+            Endianness::Big => v.to_be_bytes(),
+        }
+    }
+
+    /// `v` encoded in this byte order.
+    pub fn u64_bytes(self, v: u64) -> [u8; 8] {
+        match self {
+            Endianness::Little => v.to_le_bytes(),
+            Endianness::Big => v.to_be_bytes(),
+        }
+    }
+}
+
+/// Wraps a hasher so that integers are hashed big-endian.
+///
+/// The resulting roots are stable but differ from the spec roots, e.g.
+/// `block.calculate_root_with(&BigEndian(Keccak256))` for EVM verifiers that
+/// pack integers big-endian.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BigEndian<H>(pub H);
+
+impl<H: RootHasher> RootHasher for BigEndian<H> {
+    fn hash(&self, parts: &[&[u8]]) -> Hash32 {
+        self.0.hash(parts)
+    }
+
+    fn endianness(&self) -> Endianness {
+        Endianness::Big
+    }
+}
+
+/// SHA-256, the hash every spec root is defined with.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha256Hasher;
+
+impl RootHasher for Sha256Hasher {
+    fn hash(&self, parts: &[&[u8]]) -> Hash32 {
+        let mut hasher = Sha256::new();
+        for p in parts {
+            hasher.update(p);
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// Ethereum's Keccak-256 (original `0x01` padding, not FIPS-202 SHA3-256), as
+/// computed by Solidity's `keccak256`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Keccak256;
+
+impl RootHasher for Keccak256 {
+    fn hash(&self, parts: &[&[u8]]) -> Hash32 {
+        let mut state = Keccak256State::new();
+        for p in parts {
+            state.update(p);
+        }
+        state.finalize()
+    }
+}
+
+/// A streaming 32-byte hash usable as `&mut dyn RuntimeHasher`, for hash
+/// algorithms chosen at runtime.
+///
+/// `Send` so that a borrowed hasher can back a `RootHasher` (which is `Sync`);
+/// with the `parallel` feature its calls are then serialized.
+pub trait RuntimeHasher: Send {
+    /// Absorbs `data`.
+    fn update(&mut self, data: &[u8]);
+    /// Writes the hash of everything absorbed since the last reset into `out`
+    /// and resets to the initial state, ready for the next input.
+    fn finalize_into(&mut self, out: &mut Hash32);
+    /// Discards any absorbed input and returns to the initial state.
+    ///
+    /// The default finalizes into a scratch buffer; implementations should
+    /// override it with a direct reset.
+    fn reset(&mut self) {
+        self.finalize_into(&mut [0u8; 32]);
+    }
+}
+
+/// Hash algorithms selectable at runtime, e.g. from node configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    /// SHA-256, the spec hash.
+    Sha256,
+    /// Ethereum's Keccak-256.
+    Keccak256,
+    /// BLAKE3 with 32-byte output.
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// A fresh streaming hasher for this algorithm.
+    pub fn runtime_hasher(self) -> Box<dyn RuntimeHasher> {
+        match self {
+            HashAlgorithm::Sha256 => Box::new(Sha256State::default()),
+            HashAlgorithm::Keccak256 => Box::new(Keccak256State::default()),
+            HashAlgorithm::Blake3 => Box::new(Blake3State::default()),
+        }
+    }
+}
+
+/// Streaming SHA-256 as a `RuntimeHasher`.
+#[derive(Clone, Debug, Default)]
+pub struct Sha256State(Sha256);
+
+impl RuntimeHasher for Sha256State {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize_into(&mut self, out: &mut Hash32) {
+        out.copy_from_slice(&self.0.finalize_reset());
+    }
+
+    fn reset(&mut self) {
+        Digest::reset(&mut self.0);
+    }
+}
+
+/// Adapts a borrowed `RuntimeHasher` to `RootHasher`: each `hash` call
+/// absorbs its parts and finalizes, leaving the hasher reset.
+pub(crate) struct DynRootHasher<'a>(Mutex<&'a mut dyn RuntimeHasher>);
+
+impl<'a> DynRootHasher<'a> {
+    pub(crate) fn new(hasher: &'a mut dyn RuntimeHasher) -> Self {
+        DynRootHasher(Mutex::new(hasher))
+    }
+}
+
+impl RootHasher for DynRootHasher<'_> {
+    fn hash(&self, parts: &[&[u8]]) -> Hash32 {
+        let mut hasher = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        for p in parts {
+            hasher.update(p);
+        }
+        let mut out = [0u8; 32];
+        hasher.finalize_into(&mut out);
+        out
+    }
+}
+
+/// Sponge rate in bytes for a 256-bit output (capacity 512 bits).
+const KECCAK_RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// Rotation offsets for the rho step, in pi-step traversal order.
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// Lane visited at each pi step, starting from lane 1.
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// The Keccak-f[1600] permutation.
+fn keccak_f(lanes: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // Theta
+        let mut columns = [0u64; 5];
+        for (x, column) in columns.iter_mut().enumerate() {
+            *column = lanes[x] ^ lanes[x + 5] ^ lanes[x + 10] ^ lanes[x + 15] ^ lanes[x + 20];
+        }
+        for x in 0..5 {
+            let d = columns[(x + 4) % 5] ^ columns[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                lanes[x + 5 * y] ^= d;
+            }
+        }
+        // Rho and pi
+        let mut carried = lanes[1];
+        for (&lane, &rot) in PI.iter().zip(RHO.iter()) {
+            let next = lanes[lane];
+            lanes[lane] = carried.rotate_left(rot);
+            carried = next;
+        }
+        // Chi
+        for row in lanes.chunks_exact_mut(5) {
+            let copy = [row[0], row[1], row[2], row[3], row[4]];
+            for x in 0..5 {
+                row[x] = copy[x] ^ (!copy[(x + 1) % 5] & copy[(x + 2) % 5]);
+            }
+        }
+        // Iota
+        lanes[0] ^= rc;
+    }
+}
+
+/// Incremental Keccak-256 sponge, also usable as a `RuntimeHasher`.
+#[derive(Clone)]
+pub struct Keccak256State {
+    lanes: [u64; 25],
+    block: [u8; KECCAK_RATE],
+    filled: usize,
+}
+
+impl Default for Keccak256State {
+    fn default() -> Self {
+        Keccak256State::new()
+    }
+}
+
+impl std::fmt::Debug for Keccak256State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keccak256State")
+            .field("filled", &self.filled)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RuntimeHasher for Keccak256State {
+    fn update(&mut self, data: &[u8]) {
+        Keccak256State::update(self, data);
+    }
+
+    fn finalize_into(&mut self, out: &mut Hash32) {
+        *out = std::mem::take(self).finalize();
+    }
+
+    fn reset(&mut self) {
+        *self = Keccak256State::new();
+    }
+}
+
+impl Keccak256State {
+    fn new() -> Self {
+        Keccak256State {
+            lanes: [0; 25],
+            block: [0; KECCAK_RATE],
+            filled: 0,
+        }
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            let take = (KECCAK_RATE - self.filled).min(input.len());
+            self.block[self.filled..self.filled + take].copy_from_slice(&input[..take]);
+            self.filled += take;
+            input = &input[take..];
+            if self.filled == KECCAK_RATE {
+                self.absorb_block();
+            }
+        }
+    }
+
+    fn absorb_block(&mut self) {
+        for (lane, bytes) in self.lanes.iter_mut().zip(self.block.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        keccak_f(&mut self.lanes);
+        self.filled = 0;
+    }
+
+    fn finalize(mut self) -> Hash32 {
+        self.block[self.filled..].fill(0);
+        self.block[self.filled] ^= 0x01;
+        self.block[KECCAK_RATE - 1] ^= 0x80;
+        self.absorb_block();
+        let mut out = [0u8; 32];
+        for (bytes, lane) in out.chunks_exact_mut(8).zip(self.lanes.iter()) {
+            bytes.copy_from_slice(&lane.to_le_bytes());
+        }
+        out
+    }
+}
+
+/// BLAKE3 initialization vector (the SHA-256 IV).
+const BLAKE3_IV: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// Message word order for the next BLAKE3 round.
+const BLAKE3_MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const BLAKE3_BLOCK_LEN: usize = 64;
+const BLAKE3_CHUNK_LEN: usize = 1024;
+const CHUNK_START: u32 = 1;
+const CHUNK_END: u32 = 2;
+const PARENT: u32 = 4;
+const ROOT: u32 = 8;
+
+/// The BLAKE3 quarter-round on state words `a`, `b`, `c`, `d`.
+#[allow(clippy::too_many_arguments)]
+fn blake3_g(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+    s[a] = s[a].wrapping_add(s[b]).wrapping_add(mx);
+    s[d] = (s[d] ^ s[a]).rotate_right(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_right(12);
+    s[a] = s[a].wrapping_add(s[b]).wrapping_add(my);
+    s[d] = (s[d] ^ s[a]).rotate_right(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_right(7);
+}
+
+/// The BLAKE3 compression function; returns the full 16-word output.
+fn blake3_compress(
+    cv: &[u32; 8],
+    block: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut s = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        BLAKE3_IV[0],
+        BLAKE3_IV[1],
+        BLAKE3_IV[2],
+        BLAKE3_IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut m = *block;
+    for round in 0..7 {
+        // Columns, then diagonals.
+        blake3_g(&mut s, 0, 4, 8, 12, m[0], m[1]);
+        blake3_g(&mut s, 1, 5, 9, 13, m[2], m[3]);
+        blake3_g(&mut s, 2, 6, 10, 14, m[4], m[5]);
+        blake3_g(&mut s, 3, 7, 11, 15, m[6], m[7]);
+        blake3_g(&mut s, 0, 5, 10, 15, m[8], m[9]);
+        blake3_g(&mut s, 1, 6, 11, 12, m[10], m[11]);
+        blake3_g(&mut s, 2, 7, 8, 13, m[12], m[13]);
+        blake3_g(&mut s, 3, 4, 9, 14, m[14], m[15]);
+        if round < 6 {
+            m = BLAKE3_MSG_PERMUTATION.map(|i| m[i]);
+        }
+    }
+    for i in 0..8 {
+        s[i] ^= s[i + 8];
+        s[i + 8] ^= cv[i];
+    }
+    s
+}
+
+fn blake3_words(block: &[u8; BLAKE3_BLOCK_LEN]) -> [u32; 16] {
+    let mut words = [0u32; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+    words
+}
+
+fn first_8(words: [u32; 16]) -> [u32; 8] {
+    words[..8].try_into().unwrap()
+}
+
+/// A compression whose inputs are fixed but whose flags (e.g. `ROOT`) are not
+/// yet known.
+struct Blake3Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Blake3Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8(blake3_compress(
+            &self.cv,
+            &self.block,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    fn root_hash(&self) -> Hash32 {
+        let words = blake3_compress(&self.cv, &self.block, 0, self.block_len, self.flags | ROOT);
+        let mut out = [0u8; 32];
+        for (bytes, word) in out.chunks_exact_mut(4).zip(words) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+}
+
+fn blake3_parent(left: [u32; 8], right: [u32; 8]) -> Blake3Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(&left);
+    block[8..].copy_from_slice(&right);
+    Blake3Output {
+        cv: BLAKE3_IV,
+        block,
+        counter: 0,
+        block_len: BLAKE3_BLOCK_LEN as u32,
+        flags: PARENT,
+    }
+}
+
+/// The 1024-byte chunk currently being absorbed.
+#[derive(Clone)]
+struct Blake3Chunk {
+    cv: [u32; 8],
+    counter: u64,
+    block: [u8; BLAKE3_BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+}
+
+impl Blake3Chunk {
+    fn new(counter: u64) -> Self {
+        Blake3Chunk {
+            cv: BLAKE3_IV,
+            counter,
+            block: [0; BLAKE3_BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLAKE3_BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // Only compress a full block once more input follows: the last
+            // block of a chunk needs `CHUNK_END`.
+            if self.block_len == BLAKE3_BLOCK_LEN {
+                let words = blake3_words(&self.block);
+                self.cv = first_8(blake3_compress(
+                    &self.cv,
+                    &words,
+                    self.counter,
+                    BLAKE3_BLOCK_LEN as u32,
+                    self.start_flag(),
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0; BLAKE3_BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let take = (BLAKE3_BLOCK_LEN - self.block_len).min(input.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&input[..take]);
+            self.block_len += take;
+            input = &input[take..];
+        }
+    }
+
+    fn output(&self) -> Blake3Output {
+        Blake3Output {
+            cv: self.cv,
+            block: blake3_words(&self.block),
+            counter: self.counter,
+            block_len: self.block_len as u32,
+            flags: self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+/// Incremental BLAKE3 (unkeyed, 32-byte output) as a `RuntimeHasher`.
+#[derive(Clone)]
+pub struct Blake3State {
+    chunk: Blake3Chunk,
+    /// Chaining values of completed subtrees, one per set bit of the chunk count.
+    cv_stack: Vec<[u32; 8]>,
+}
+
+impl Default for Blake3State {
+    fn default() -> Self {
+        Blake3State {
+            chunk: Blake3Chunk::new(0),
+            cv_stack: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Blake3State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Blake3State")
+            .field("chunks", &self.chunk.counter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Blake3State {
+    /// Merges the new chunk's chaining value with every completed subtree of
+    /// equal size, then pushes the result.
+    fn push_chunk_cv(&mut self, mut cv: [u32; 8], mut total_chunks: u64) {
+        while total_chunks & 1 == 0 {
+            let left = self.cv_stack.pop().expect("subtree for each trailing zero");
+            cv = blake3_parent(left, cv).chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack.push(cv);
+    }
+}
+
+impl RuntimeHasher for Blake3State {
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.chunk.len() == BLAKE3_CHUNK_LEN {
+                let cv = self.chunk.output().chaining_value();
+                let total_chunks = self.chunk.counter + 1;
+                self.push_chunk_cv(cv, total_chunks);
+                self.chunk = Blake3Chunk::new(total_chunks);
+            }
+            let take = (BLAKE3_CHUNK_LEN - self.chunk.len()).min(data.len());
+            self.chunk.update(&data[..take]);
+            data = &data[take..];
+        }
+    }
+
+    fn finalize_into(&mut self, out: &mut Hash32) {
+        let mut output = self.chunk.output();
+        for left in self.cv_stack.iter().rev() {
+            output = blake3_parent(*left, output.chaining_value());
+        }
+        *out = output.root_hash();
+        *self = Blake3State::default();
+    }
+
+    fn reset(&mut self) {
+        *self = Blake3State::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keccak(data: &[u8]) -> String {
+        hex::encode(Keccak256.hash(&[data]))
+    }
+
+    #[test]
+    fn keccak256_known_vectors() {
+        assert_eq!(
+            keccak(b""),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            keccak(b"abc"),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+    }
+
+    #[test]
+    fn keccak256_split_input_matches_whole() {
+        // Cross the 136-byte rate boundary with uneven part sizes.
+        let data: Vec<u8> = (0..300u32).map(|i| (i * 7) as u8).collect();
+        let whole = Keccak256.hash(&[&data]);
+        for split in [0, 1, 135, 136, 137, 272, 300] {
+            let (a, b) = data.split_at(split);
+            assert_eq!(Keccak256.hash(&[a, b]), whole, "split at {split}");
+        }
+    }
+
+    #[test]
+    fn endianness_encodes_integers() {
+        assert_eq!(Endianness::default(), Endianness::Little);
+        assert_eq!(Endianness::Little.u32_bytes(1), [1, 0, 0, 0]);
+        assert_eq!(Endianness::Big.u32_bytes(1), [0, 0, 0, 1]);
+        assert_eq!(Endianness::Big.u64_bytes(0x0102), [0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(Sha256Hasher.endianness(), Endianness::Little);
+        assert_eq!(BigEndian(Keccak256).endianness(), Endianness::Big);
+        assert_eq!(
+            BigEndian(Keccak256).hash(&[b"abc"]),
+            Keccak256.hash(&[b"abc"])
+        );
+    }
+
+    fn runtime(algorithm: HashAlgorithm, data: &[u8]) -> String {
+        let mut hasher = algorithm.runtime_hasher();
+        hasher.update(data);
+        let mut out = [0u8; 32];
+        hasher.finalize_into(&mut out);
+        hex::encode(out)
+    }
+
+    #[test]
+    fn blake3_known_vectors() {
+        assert_eq!(
+            runtime(HashAlgorithm::Blake3, b""),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(
+            runtime(HashAlgorithm::Blake3, b"abc"),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+    }
+
+    #[test]
+    fn runtime_hashers_stream_and_reset() {
+        // Cross block (64), chunk (1024) and subtree boundaries.
+        let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+        for algorithm in [
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Keccak256,
+            HashAlgorithm::Blake3,
+        ] {
+            let whole = runtime(algorithm, &data);
+            let mut hasher = algorithm.runtime_hasher();
+            for split in [0, 1, 63, 64, 65, 1023, 1024, 1025, 2048, 4096, 5000] {
+                let (a, b) = data.split_at(split);
+                hasher.update(a);
+                hasher.update(b);
+                let mut out = [0u8; 32];
+                hasher.finalize_into(&mut out);
+                assert_eq!(hex::encode(out), whole, "{algorithm:?} split at {split}");
+            }
+        }
+        assert_eq!(
+            runtime(HashAlgorithm::Sha256, b"abc"),
+            hex::encode(Sha256Hasher.hash(&[b"abc"]))
+        );
+        assert_eq!(
+            runtime(HashAlgorithm::Keccak256, b"abc"),
+            hex::encode(Keccak256.hash(&[b"abc"]))
+        );
+    }
+
+    #[test]
+    fn sha256_hasher_matches_sha2() {
+        let expect: Hash32 = Sha256::digest(b"dig:l2:abc").into();
+        assert_eq!(Sha256Hasher.hash(&[b"dig:l2:", b"abc"]), expect);
+    }
+}
@@ -4,39 +4,133 @@
 //! individually domain-separated header fields. This allows proving single
 //! fields against the overall `BLOCK_ROOT` without revealing the entire header.
 
-use crate::dig_l2_definition as definitions;
+use crate::dig_l2_definition::{self as definitions, FieldEncoding};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Header for an L2 block.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Serializes with snake_case field names; deserialization also accepts the
+/// camelCase spellings (`prevBlockRoot`, `dataCount`, ...).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct L2BlockHeader {
     /// Block version; must match network consensus version.
     pub version: u32,
     /// Network ID (32 bytes), serialized as `0x` hex.
-    #[serde(with = "crate::serde_hex::hex32")]
+    #[serde(with = "crate::serde_hex::hex32", alias = "networkId")]
     pub network_id: [u8; 32],
     /// Epoch number.
     pub epoch: u64,
     /// Previous block root (32 bytes), serialized as `0x` hex.
-    #[serde(with = "crate::serde_hex::hex32")]
+    #[serde(with = "crate::serde_hex::hex32", alias = "prevBlockRoot")]
     pub prev_block_root: [u8; 32],
     /// Body root (32 bytes), serialized as `0x` hex.
-    #[serde(with = "crate::serde_hex::hex32")]
+    #[serde(with = "crate::serde_hex::hex32", alias = "bodyRoot")]
     pub body_root: [u8; 32],
     /// Count of data items (bytes) in the body.
+    #[serde(alias = "dataCount")]
     pub data_count: u32,
     /// Count of emissions in the body.
+    #[serde(alias = "emissionsCount")]
     pub emissions_count: u32,
     /// Proposer public key (48 bytes), serialized as `0x` hex.
-    #[serde(with = "crate::serde_hex::hex48")]
+    #[serde(with = "crate::serde_hex::hex48", alias = "proposerPubkey")]
     pub proposer_pubkey: [u8; 48],
+    /// Block time in unix seconds; committed to by the header root from
+    /// `TIMESTAMP_VERSION` on and must be zero before it. Defaults to zero
+    /// when absent from JSON.
+    #[serde(default)]
+    pub timestamp: u64,
+    /// Opaque application commitment (e.g. an app state root), serialized as
+    /// `0x` hex. Committed to by the header root from `EXTRA_DATA_VERSION` on
+    /// and must be zero before it. Defaults to zero when absent from JSON.
+    #[serde(with = "crate::serde_hex::hex32", alias = "extraData", default)]
+    pub extra_data: [u8; 32],
 }
 
 impl L2BlockHeader {
     /// Calculates the `HEADER_ROOT` using the spec function.
     pub fn calculate_root(&self) -> definitions::Hash32 {
-        definitions::COMPUTE_HEADER_ROOT(self)
+        definitions::COMPUTE_HEADER_ROOT_HEADER(self)
+    }
+
+    /// The body-independent part of this header's root (see `HeaderCommitment`).
+    pub fn commitment(&self) -> HeaderCommitment {
+        HeaderCommitment::new(
+            self.version,
+            &self.network_id,
+            self.epoch,
+            &self.prev_block_root,
+            &self.proposer_pubkey,
+            self.timestamp,
+            &self.extra_data,
+        )
+    }
+
+    /// The `HEADER_ROOT` as a `0x`-prefixed lowercase hex string.
+    pub fn calculate_root_hex(&self) -> String {
+        crate::serde_hex::encode_lower_hex_prefixed(&self.calculate_root())
+    }
+
+    /// The `BLOCK_ROOT` of this header with a trusted `body_root`, e.g. one
+    /// proven separately, without the body itself.
+    ///
+    /// The supplied root is the second `COMPUTE_BLOCK_ROOT` input even if it
+    /// differs from the header's `body_root` field. The header root still
+    /// hashes that field, so the result only equals `DigL2Block::calculate_root`
+    /// when both agree with the body.
+    pub fn block_root_with_body_root(
+        &self,
+        body_root: &definitions::Hash32,
+    ) -> definitions::Hash32 {
+        definitions::COMPUTE_BLOCK_ROOT(&self.calculate_root(), body_root)
+    }
+
+    /// `network_id` as a borrowed slice.
+    pub fn network_id_bytes(&self) -> &[u8] {
+        &self.network_id
+    }
+
+    /// `prev_block_root` as a borrowed slice.
+    pub fn prev_block_root_bytes(&self) -> &[u8] {
+        &self.prev_block_root
+    }
+
+    /// `body_root` as a borrowed slice.
+    pub fn body_root_bytes(&self) -> &[u8] {
+        &self.body_root
+    }
+
+    /// `proposer_pubkey` as a borrowed slice.
+    pub fn proposer_pubkey_bytes(&self) -> &[u8] {
+        &self.proposer_pubkey
+    }
+
+    /// `extra_data` as a borrowed slice.
+    pub fn extra_data_bytes(&self) -> &[u8] {
+        &self.extra_data
+    }
+
+    /// Flags suspicious equalities between the 32-byte fields.
+    ///
+    /// Identical non-zero values in `network_id`, `prev_block_root` and
+    /// `body_root` usually mean one array was passed for another. This is a
+    /// non-fatal developer aid; an empty vector means nothing looked wrong.
+    pub fn lint(&self) -> Vec<LintWarning> {
+        let fields: [(&'static str, &[u8; 32]); 3] = [
+            ("network_id", &self.network_id),
+            ("prev_block_root", &self.prev_block_root),
+            ("body_root", &self.body_root),
+        ];
+        let mut warnings = Vec::new();
+        for (i, (first, a)) in fields.iter().enumerate() {
+            for (second, b) in &fields[i + 1..] {
+                if a == b && a.iter().any(|&x| x != 0) {
+                    warnings.push(LintWarning::DuplicateHashFields { first, second });
+                }
+            }
+        }
+        warnings
     }
 
     /// Validates that the header version matches the expected consensus version.
@@ -50,7 +144,31 @@ impl L2BlockHeader {
         Ok(())
     }
 
+    /// Validates that `timestamp` is zero for versions whose root does not commit to it.
+    pub fn validate_timestamp(&self) -> Result<(), HeaderError> {
+        if self.version < definitions::TIMESTAMP_VERSION && self.timestamp != 0 {
+            return Err(HeaderError::UncommittedTimestamp {
+                version: self.version,
+            });
+        }
+        Ok(())
+    }
+
+    /// Validates that `extra_data` is zero for versions whose root does not commit to it.
+    pub fn validate_extra_data(&self) -> Result<(), HeaderError> {
+        if self.version < definitions::EXTRA_DATA_VERSION && self.extra_data != [0u8; 32] {
+            return Err(HeaderError::UncommittedExtraData {
+                version: self.version,
+            });
+        }
+        Ok(())
+    }
+
     /// Validates that `data_count` and `emissions_count` match the provided body lengths.
+    ///
+    /// The header counts widen losslessly to `usize` here; the opposite
+    /// direction can truncate, which is why block assembly converts body
+    /// lengths with a checked `u32::try_from` instead.
     pub fn validate_counts(
         &self,
         data_len: usize,
@@ -74,6 +192,98 @@ impl L2BlockHeader {
     }
 }
 
+/// A header root with every leaf except `body_root`, `data_count` and
+/// `emissions_count` hashed up front.
+///
+/// While a body is being packed, `finalize` supplies the three body-derived
+/// fields and only hashes their leaves, instead of all header fields each time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeaderCommitment {
+    /// Header leaves in `COMPUTE_HEADER_ROOT_FIELDS` order; the body-derived slots are
+    /// overwritten by `finalize`.
+    leaves: Vec<definitions::Hash32>,
+    /// `FieldEncoding` of the header version, for the body-derived leaves.
+    encoding: FieldEncoding,
+}
+
+/// Leaf positions of the body-derived fields in `COMPUTE_HEADER_ROOT_FIELDS`.
+const BODY_ROOT_LEAF: usize = 4;
+const DATA_COUNT_LEAF: usize = 5;
+const EMISSIONS_COUNT_LEAF: usize = 6;
+
+impl HeaderCommitment {
+    /// Hashes the static header fields. `timestamp` and `extra_data` are only
+    /// committed to from `TIMESTAMP_VERSION` and `EXTRA_DATA_VERSION` on, as in
+    /// `COMPUTE_HEADER_ROOT_FIELDS`.
+    pub fn new(
+        version: u32,
+        network_id: &[u8; 32],
+        epoch: u64,
+        prev_block_root: &[u8; 32],
+        proposer_pubkey: &[u8; 48],
+        timestamp: u64,
+        extra_data: &[u8; 32],
+    ) -> Self {
+        let encoding = FieldEncoding::for_version(version);
+        let leaf =
+            |label: &[u8], value: &[u8]| definitions::header_field_leaf(encoding, label, value);
+        let mut leaves = vec![
+            leaf(b"version", &version.to_le_bytes()),
+            leaf(b"network_id", network_id),
+            leaf(b"epoch", &epoch.to_le_bytes()),
+            leaf(b"prev_block_root", prev_block_root),
+            [0u8; 32],
+            [0u8; 32],
+            [0u8; 32],
+            leaf(b"proposer_pubkey", proposer_pubkey),
+        ];
+        if version >= definitions::TIMESTAMP_VERSION {
+            leaves.push(leaf(b"timestamp", &timestamp.to_le_bytes()));
+        }
+        if version >= definitions::EXTRA_DATA_VERSION {
+            leaves.push(leaf(b"extra_data", extra_data));
+        }
+        HeaderCommitment { leaves, encoding }
+    }
+
+    /// The full `HEADER_ROOT` for the given body-derived fields.
+    pub fn finalize(
+        &self,
+        body_root: &definitions::Hash32,
+        data_count: u32,
+        emissions_count: u32,
+    ) -> definitions::Hash32 {
+        let leaf = |label: &[u8], value: &[u8]| {
+            definitions::header_field_leaf(self.encoding, label, value)
+        };
+        let mut leaves = self.leaves.clone();
+        leaves[BODY_ROOT_LEAF] = leaf(b"body_root", body_root);
+        leaves[DATA_COUNT_LEAF] = leaf(b"data_count", &data_count.to_le_bytes());
+        leaves[EMISSIONS_COUNT_LEAF] = leaf(b"emissions_count", &emissions_count.to_le_bytes());
+        definitions::MERKLE_ROOT(&leaves)
+    }
+}
+
+/// Non-fatal findings reported by `L2BlockHeader::lint`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LintWarning {
+    /// Two 32-byte fields hold the same non-zero value (likely a copy-paste error).
+    DuplicateHashFields {
+        first: &'static str,
+        second: &'static str,
+    },
+}
+
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintWarning::DuplicateHashFields { first, second } => {
+                write!(f, "{first} equals {second}; were the arguments swapped?")
+            }
+        }
+    }
+}
+
 /// Errors that can be emitted by header-level validation or operations.
 #[derive(Debug, Error)]
 pub enum HeaderError {
@@ -88,6 +298,14 @@ pub enum HeaderError {
         expected: usize,
         actual: usize,
     },
+
+    /// A non-zero timestamp on a version whose header root does not include it.
+    #[error("timestamp is not committed to by version {version} headers")]
+    UncommittedTimestamp { version: u32 },
+
+    /// Non-zero extra data on a version whose header root does not include it.
+    #[error("extra_data is not committed to by version {version} headers")]
+    UncommittedExtraData { version: u32 },
 }
 
 #[cfg(test)]
@@ -104,9 +322,26 @@ mod tests {
             data_count: 2,
             emissions_count: 1,
             proposer_pubkey: [9u8; 48],
+            timestamp: 0,
+            extra_data: [0u8; 32],
         }
     }
 
+    #[test]
+    fn byte_accessors_borrow_fields() {
+        let mut h = sample_header();
+        h.extra_data = [4u8; 32];
+        assert_eq!(h.network_id_bytes(), &h.network_id[..]);
+        assert_eq!(h.prev_block_root_bytes(), &h.prev_block_root[..]);
+        assert_eq!(h.body_root_bytes(), &h.body_root[..]);
+        assert_eq!(h.proposer_pubkey_bytes(), &h.proposer_pubkey[..]);
+        assert_eq!(h.extra_data_bytes(), &h.extra_data[..]);
+        assert!(std::ptr::eq(
+            h.network_id_bytes().as_ptr(),
+            h.network_id.as_ptr()
+        ));
+    }
+
     #[test]
     fn header_root_changes_when_field_changes() {
         let h1 = sample_header();
@@ -116,6 +351,254 @@ mod tests {
         assert_ne!(h1.calculate_root(), h2.calculate_root());
     }
 
+    #[test]
+    fn timestamp_leaf_is_gated_by_version() {
+        let legacy = sample_header();
+        let mut stamped = sample_header();
+        stamped.timestamp = 1_700_000_000;
+        // Before TIMESTAMP_VERSION the timestamp is not part of the root.
+        assert_eq!(legacy.calculate_root(), stamped.calculate_root());
+        assert!(stamped.validate_timestamp().is_err());
+
+        stamped.version = definitions::TIMESTAMP_VERSION;
+        let mut later = stamped.clone();
+        later.timestamp += 1;
+        assert_ne!(stamped.calculate_root(), later.calculate_root());
+        assert!(stamped.validate_timestamp().is_ok());
+
+        // A v3 root is the v1 leaves plus the timestamp leaf.
+        let mut v3_leaves = Vec::new();
+        for (label, value) in [
+            (&b"version"[..], &3u32.to_le_bytes()[..]),
+            (b"network_id", &stamped.network_id),
+            (b"epoch", &stamped.epoch.to_le_bytes()),
+            (b"prev_block_root", &stamped.prev_block_root),
+            (b"body_root", &stamped.body_root),
+            (b"data_count", &stamped.data_count.to_le_bytes()),
+            (b"emissions_count", &stamped.emissions_count.to_le_bytes()),
+            (b"proposer_pubkey", &stamped.proposer_pubkey),
+            (b"timestamp", &stamped.timestamp.to_le_bytes()),
+        ] {
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            hasher.update(definitions::HEADER_FIELD_DOMAIN);
+            hasher.update(label);
+            hasher.update(value);
+            v3_leaves.push(hasher.finalize().into());
+        }
+        assert_eq!(
+            stamped.calculate_root(),
+            definitions::MERKLE_ROOT(&v3_leaves)
+        );
+    }
+
+    #[test]
+    fn extra_data_leaf_is_gated_by_version() {
+        // Zero extra_data leaves legacy roots exactly as before the field existed.
+        let legacy = sample_header();
+        let legacy_leaves: Vec<definitions::Hash32> = [
+            (&b"version"[..], &1u32.to_le_bytes()[..]),
+            (b"network_id", &legacy.network_id),
+            (b"epoch", &legacy.epoch.to_le_bytes()),
+            (b"prev_block_root", &legacy.prev_block_root),
+            (b"body_root", &legacy.body_root),
+            (b"data_count", &legacy.data_count.to_le_bytes()),
+            (b"emissions_count", &legacy.emissions_count.to_le_bytes()),
+            (b"proposer_pubkey", &legacy.proposer_pubkey),
+        ]
+        .iter()
+        .map(|(label, value)| {
+            definitions::header_field_leaf(FieldEncoding::Concatenated, label, value)
+        })
+        .collect();
+        assert_eq!(
+            legacy.calculate_root(),
+            definitions::MERKLE_ROOT(&legacy_leaves)
+        );
+
+        let mut tagged = sample_header();
+        tagged.extra_data = [0x42u8; 32];
+        assert_eq!(tagged.calculate_root(), legacy.calculate_root());
+        assert!(matches!(
+            tagged.validate_extra_data(),
+            Err(HeaderError::UncommittedExtraData { version: 1 })
+        ));
+
+        tagged.version = definitions::EXTRA_DATA_VERSION;
+        assert!(tagged.validate_extra_data().is_ok());
+        let mut other = tagged.clone();
+        other.extra_data[0] ^= 1;
+        assert_ne!(tagged.calculate_root(), other.calculate_root());
+
+        // A v4 root is the v3 leaves plus the extra_data leaf.
+        let mut v3 = tagged.clone();
+        v3.version = definitions::TIMESTAMP_VERSION;
+        let mut v4_leaves = legacy_leaves.clone();
+        v4_leaves[0] = definitions::header_field_leaf(
+            FieldEncoding::Concatenated,
+            b"version",
+            &definitions::EXTRA_DATA_VERSION.to_le_bytes(),
+        );
+        v4_leaves.push(definitions::header_field_leaf(
+            FieldEncoding::Concatenated,
+            b"timestamp",
+            &0u64.to_le_bytes(),
+        ));
+        v4_leaves.push(definitions::header_field_leaf(
+            FieldEncoding::Concatenated,
+            b"extra_data",
+            &[0x42u8; 32],
+        ));
+        assert_eq!(
+            tagged.calculate_root(),
+            definitions::MERKLE_ROOT(&v4_leaves)
+        );
+        assert_ne!(tagged.calculate_root(), v3.calculate_root());
+        assert_eq!(
+            tagged.commitment().finalize(&tagged.body_root, 2, 1),
+            tagged.calculate_root()
+        );
+    }
+
+    #[test]
+    fn extra_data_serde_round_trip_and_default() {
+        let mut h = sample_header();
+        h.version = definitions::EXTRA_DATA_VERSION;
+        h.extra_data = [0xa5u8; 32];
+        let json = serde_json::to_string(&h).unwrap();
+        assert!(json.contains(&format!("\"extra_data\":\"0x{}\"", "a5".repeat(32))));
+        let back: L2BlockHeader = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, h);
+
+        let mut value: serde_json::Value = serde_json::to_value(sample_header()).unwrap();
+        value.as_object_mut().unwrap().remove("extra_data");
+        let old: L2BlockHeader = serde_json::from_value(value).unwrap();
+        assert_eq!(old.extra_data, [0u8; 32]);
+        assert_eq!(old, sample_header());
+    }
+
+    #[test]
+    fn timestamp_serde_round_trip_and_default() {
+        let mut h = sample_header();
+        h.version = definitions::TIMESTAMP_VERSION;
+        h.timestamp = 1_700_000_000;
+        let json = serde_json::to_string(&h).unwrap();
+        assert!(json.contains("\"timestamp\":1700000000"));
+        let back: L2BlockHeader = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, h);
+
+        // Headers serialized before the field existed decode with timestamp 0.
+        let mut value: serde_json::Value = serde_json::to_value(sample_header()).unwrap();
+        value.as_object_mut().unwrap().remove("timestamp");
+        let old: L2BlockHeader = serde_json::from_value(value).unwrap();
+        assert_eq!(old, sample_header());
+    }
+
+    #[test]
+    fn camel_case_aliases_deserialize() {
+        let h = sample_header();
+        let snake = serde_json::to_value(&h).unwrap();
+        let mut camel = serde_json::Map::new();
+        for (k, v) in snake.as_object().unwrap() {
+            let key = match k.as_str() {
+                "network_id" => "networkId",
+                "prev_block_root" => "prevBlockRoot",
+                "body_root" => "bodyRoot",
+                "data_count" => "dataCount",
+                "emissions_count" => "emissionsCount",
+                "proposer_pubkey" => "proposerPubkey",
+                "extra_data" => "extraData",
+                other => other,
+            };
+            camel.insert(key.to_string(), v.clone());
+        }
+        let json = serde_json::Value::Object(camel).to_string();
+        assert!(json.contains("\"prevBlockRoot\":\"0x0202"));
+        let back: L2BlockHeader = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, h);
+        assert_eq!(back.calculate_root(), h.calculate_root());
+        // Serialization stays snake_case.
+        assert!(
+            serde_json::to_string(&back)
+                .unwrap()
+                .contains("prev_block_root")
+        );
+    }
+
+    #[test]
+    fn header_commitment_finalizes_to_header_root() {
+        for version in [1, definitions::TIMESTAMP_VERSION] {
+            let mut h = sample_header();
+            h.version = version;
+            h.timestamp = if version >= definitions::TIMESTAMP_VERSION {
+                1_700_000_000
+            } else {
+                0
+            };
+            let commitment = h.commitment();
+            // Finalize repeatedly as the body changes during packing.
+            for (body_root, data_count, emissions_count) in
+                [([3u8; 32], 2, 1), ([4u8; 32], 10, 3), ([0u8; 32], 0, 0)]
+            {
+                h.body_root = body_root;
+                h.data_count = data_count;
+                h.emissions_count = emissions_count;
+                assert_eq!(
+                    commitment.finalize(&body_root, data_count, emissions_count),
+                    definitions::COMPUTE_HEADER_ROOT_HEADER(&h)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn header_root_hex_matches_root() {
+        let h = sample_header();
+        assert_eq!(
+            h.calculate_root_hex(),
+            format!("0x{}", hex::encode(h.calculate_root()))
+        );
+    }
+
+    #[test]
+    fn lint_clean_header_has_no_warnings() {
+        assert!(sample_header().lint().is_empty());
+        let mut genesis = sample_header();
+        genesis.prev_block_root = [0u8; 32];
+        genesis.body_root = [0u8; 32];
+        assert!(genesis.lint().is_empty());
+    }
+
+    #[test]
+    fn lint_flags_duplicated_hash_fields() {
+        let mut h = sample_header();
+        h.prev_block_root = h.network_id;
+        h.body_root = h.network_id;
+        let warnings = h.lint();
+        assert_eq!(
+            warnings,
+            vec![
+                LintWarning::DuplicateHashFields {
+                    first: "network_id",
+                    second: "prev_block_root",
+                },
+                LintWarning::DuplicateHashFields {
+                    first: "network_id",
+                    second: "body_root",
+                },
+                LintWarning::DuplicateHashFields {
+                    first: "prev_block_root",
+                    second: "body_root",
+                },
+            ]
+        );
+        assert!(
+            warnings[0]
+                .to_string()
+                .contains("network_id equals prev_block_root")
+        );
+    }
+
     #[test]
     fn version_validation() {
         let h = sample_header();
@@ -130,6 +613,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn counts_validation_with_zero_counts() {
+        let mut h = sample_header();
+        h.data_count = 0;
+        h.emissions_count = 0;
+        assert!(h.validate_counts(0, 0).is_ok());
+        assert!(matches!(
+            h.validate_counts(3, 0),
+            Err(HeaderError::CountMismatch {
+                field: "data_count",
+                expected: 0,
+                actual: 3
+            })
+        ));
+        assert!(matches!(
+            h.validate_counts(0, 1),
+            Err(HeaderError::CountMismatch {
+                field: "emissions_count",
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn counts_validation() {
         let h = sample_header();
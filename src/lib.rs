@@ -4,8 +4,24 @@
 //!
 //! Current modules implemented:
 //! - `serde_hex`: Serde helpers to encode/decode byte arrays and vectors as 0x-prefixed hex.
+//! - `serde_u64_string`: Serde helper to encode `u64` as a decimal string for JavaScript clients.
 //! - `dig_l2_definition`: CAPITALIZED spec functions (hash domains, Merkle, roots, consensus emissions tuples).
 //! - `emission`, `body`, `header`, `block`: core L2 types each with `calculate_root()`.
+//! - `hasher`: `RootHasher` with `Sha256Hasher` and `Keccak256`, for computing roots under other hashes, `BigEndian` for big-endian integer encoding, and the object-safe `RuntimeHasher` (SHA-256, Keccak-256, BLAKE3) for hashes picked at runtime.
+//! - `bls` (feature `bls`): validation of compressed BLS12-381 G1 pubkeys, including the subgroup check.
+//! - `builder`: `DigL2BlockBuilder`, a fluent one-stop construction API.
+//! - `cbor` (feature `cbor`): CBOR encoding with raw byte strings, behind `block::to_cbor`/`from_cbor`.
+//! - `memoized_block`: `MemoizedBlock`, caching the block root across mutations.
+//! - `msgpack` (feature `msgpack`): MessagePack encoding with raw `bin` fields, behind `block::to_msgpack`/`from_msgpack`.
+//! - `secret` (feature `zeroize`): `SecretPubkeyInput`, zeroed on drop, for sensitive builder inputs.
+//! - `ssz` (feature `ssz`): SimpleSerialize encoding compatible with Ethereum tooling.
+//! - `testing` (feature `testing`): reusable invariants such as `assert_root_stable`.
+//! - `types`: typed wrappers (`HashHex`, `NetworkId`, `BlockRoot`, `Pubkey`).
+//! - `wasm` (feature `wasm`): JSON-in/hex-out root and verify functions for browser builds.
+//! - `web`: `WebBlock` and friends, the block JSON with `u64` fields as strings.
+//!
+//! Each module keeps its own error enum; `DigBlockError` unifies them for
+//! callers that span several layers, together with the `Result` alias.
 //!
 //! # Example
 //!
@@ -16,13 +32,14 @@
 //! ```rust
 //! use dig_network_block::block::{DigL2Block, BuildL2BlockArgs};
 //! use dig_network_block::emission_config::ConsensusEmissionConfig;
+//! use dig_network_block::types::{BlockRoot, NetworkId, Pubkey};
 //!
 //! // 1) Create a DIG block
 //! let version = 1u32;
-//! let network_id = [1u8; 32];
+//! let network_id = NetworkId::from([1u8; 32]);
 //! let epoch = 42u64;
-//! let prev_block_root = [0u8; 32];
-//! let proposer_pubkey = [9u8; 48];
+//! let prev_block_root = BlockRoot::from([0u8; 32]);
+//! let proposer_pubkey = Pubkey::from([9u8; 48]);
 //! let data = vec![1u8, 2, 3, 4, 5];
 //! let extra_emissions = vec![]; // none for this example
 //! let attesters: Vec<[u8; 48]> = vec![]; // no attesters
@@ -37,6 +54,8 @@
 //!     extra_emissions,
 //!     attester_pubkeys: &attesters,
 //!     cfg: &cfg,
+//!     timestamp: 0,
+//!     extra_data: [0u8; 32],
 //! };
 //!
 //! let block = DigL2Block::build(&args).unwrap();
@@ -60,10 +79,171 @@
 //! assert_eq!(root1, root2);
 //! ```
 
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+mod binary_serde;
 pub mod block;
+#[cfg(feature = "bls")]
+pub mod bls;
 pub mod body;
+pub mod builder;
+mod canonical_json;
+#[cfg(feature = "cbor")]
+pub mod cbor;
 pub mod dig_l2_definition;
 pub mod emission;
 pub mod emission_config;
+pub mod hasher;
 pub mod header;
+pub mod memoized_block;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+#[cfg(feature = "zeroize")]
+pub mod secret;
 pub mod serde_hex;
+pub mod serde_u64_string;
+#[cfg(feature = "ssz")]
+pub mod ssz;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod types;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod web;
+
+use thiserror::Error;
+
+/// Crate-level error wrapping every per-module error.
+///
+/// Each variant labels the layer it came from and exposes the original error
+/// via `source()`, so `Display` reads as `<layer> error: <detail>`.
+#[derive(Debug, Error)]
+pub enum DigBlockError {
+    /// Hex (de)serialization failure.
+    #[error("hex error: {0}")]
+    Hex(#[from] serde_hex::HexSerdeError),
+
+    /// Spec-function failure.
+    #[error("definition error: {0}")]
+    Definition(#[from] dig_l2_definition::DefinitionError),
+
+    /// Emission-level failure.
+    #[error("emission error: {0}")]
+    Emission(#[from] emission::EmissionError),
+
+    /// Malformed BLS public key.
+    #[cfg(feature = "bls")]
+    #[error("pubkey error: {0}")]
+    Pubkey(#[from] bls::PubkeyError),
+
+    /// Emission config failure.
+    #[error("emission config error: {0}")]
+    EmissionConfig(#[from] emission_config::EmissionConfigError),
+
+    /// Header-level failure.
+    #[error("header error: {0}")]
+    Header(#[from] header::HeaderError),
+
+    /// Body-level failure.
+    #[error("body error: {0}")]
+    Body(#[from] body::BodyError),
+
+    /// Block construction/validation failure.
+    #[error("block error: {0}")]
+    Block(#[from] block::BlockError),
+
+    /// SSZ decoding failure.
+    #[cfg(feature = "ssz")]
+    #[error("ssz error: {0}")]
+    Ssz(#[from] ssz::SszError),
+}
+
+/// Result alias using `DigBlockError`.
+pub type Result<T> = core::result::Result<T, DigBlockError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn sub_errors_convert_and_chain() {
+        let cases: Vec<(DigBlockError, &str)> = vec![
+            (
+                serde_hex::HexSerdeError::MissingPrefix.into(),
+                "hex error: ",
+            ),
+            (
+                dig_l2_definition::DefinitionError::NoAttestersForNonZeroShare.into(),
+                "definition error: ",
+            ),
+            (
+                emission::EmissionError::Generic("x".into()).into(),
+                "emission error: ",
+            ),
+            (
+                emission_config::EmissionConfigError::NonZeroAttesterShareWithNoAttesters.into(),
+                "emission config error: ",
+            ),
+            (
+                header::HeaderError::VersionMismatch {
+                    expected: 2,
+                    found: 1,
+                }
+                .into(),
+                "header error: ",
+            ),
+            (body::BodyError::Generic("x".into()).into(), "body error: "),
+            (
+                block::BlockError::MissingField { field: "epoch" }.into(),
+                "block error: ",
+            ),
+        ];
+        for (err, prefix) in cases {
+            let msg = err.to_string();
+            let source = err.source().expect("wrapped error is the source");
+            assert_eq!(msg, format!("{prefix}{source}"));
+        }
+    }
+
+    #[test]
+    fn question_mark_lifts_module_errors() {
+        fn parse() -> Result<types::HashHex> {
+            Ok("0x00".parse::<types::HashHex>()?)
+        }
+        let err = parse().unwrap_err();
+        assert!(matches!(err, DigBlockError::Hex(_)));
+        assert_eq!(
+            err.to_string(),
+            "hex error: length mismatch: expected 32 bytes, got 1 bytes"
+        );
+    }
+
+    /// Roots in `vectors/blocks.json` (from `examples/gen_vectors.rs`) must not drift.
+    #[test]
+    fn golden_vectors_still_match() {
+        let vectors: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../vectors/blocks.json")).unwrap();
+        assert_eq!(vectors.len(), 8);
+        let hex = |h: [u8; 32]| format!("0x{}", hex::encode(h));
+        for v in &vectors {
+            let name = v["name"].as_str().unwrap();
+            let block: block::DigL2Block = serde_json::from_value(v["block"].clone()).unwrap();
+            let version = block.header.version;
+            let mode = dig_l2_definition::DataRootMode::for_version(version);
+            let roots = [
+                ("data_root", block.body.calculate_data_root_with_mode(mode)),
+                (
+                    "emissions_root",
+                    block.body.calculate_emissions_root_for_version(version),
+                ),
+                ("body_root", block.body.calculate_root_for_version(version)),
+                ("header_root", block.header.calculate_root()),
+                ("block_root", block.calculate_root()),
+            ];
+            for (field, root) in roots {
+                assert_eq!(v[field].as_str().unwrap(), hex(root), "{name}: {field}");
+            }
+            assert!(block.verify(None).is_ok(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Root memoization for long-lived, mutable blocks.
+//!
+//! `MemoizedBlock` owns a `DigL2Block` and only exposes mutation through
+//! methods that bump an internal `u64` generation counter. The last computed
+//! root is cached together with the generation it was computed at, so
+//! `calculate_root` only rehashes after a mutation.
+//!
+//! Thread-safety: the cache uses `Cell`, so `MemoizedBlock` is `Send` but not
+//! `Sync`. Share it across threads behind a `Mutex` (or `RwLock` with writes
+//! for `calculate_root`) rather than by reference.
+
+use crate::block::DigL2Block;
+use crate::body::L2BlockBody;
+use crate::dig_l2_definition::Hash32;
+use crate::emission::Emission;
+use crate::header::L2BlockHeader;
+use std::cell::Cell;
+
+/// A `DigL2Block` whose root is cached until the next mutation.
+#[derive(Clone, Debug)]
+pub struct MemoizedBlock {
+    block: DigL2Block,
+    generation: u64,
+    cache: Cell<Option<(u64, Hash32)>>,
+}
+
+impl MemoizedBlock {
+    /// Wrap a block; nothing is cached yet.
+    pub fn new(block: DigL2Block) -> Self {
+        Self {
+            block,
+            generation: 0,
+            cache: Cell::new(None),
+        }
+    }
+
+    /// Read-only access to the wrapped block.
+    pub fn block(&self) -> &DigL2Block {
+        &self.block
+    }
+
+    /// Unwrap the block, discarding the cache.
+    pub fn into_inner(self) -> DigL2Block {
+        self.block
+    }
+
+    /// Current generation; incremented by every mutating method.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The cached root if it was computed at the current generation.
+    pub fn cached_root(&self) -> Option<Hash32> {
+        match self.cache.get() {
+            Some((generation, root)) if generation == self.generation => Some(root),
+            _ => None,
+        }
+    }
+
+    /// `DigL2Block::calculate_root`, recomputed only when the generation changed.
+    pub fn calculate_root(&self) -> Hash32 {
+        if let Some(root) = self.cached_root() {
+            return root;
+        }
+        let root = self.block.calculate_root();
+        self.cache.set(Some((self.generation, root)));
+        root
+    }
+
+    /// Apply an arbitrary edit to the block and invalidate the cache.
+    pub fn update<R>(&mut self, edit: impl FnOnce(&mut DigL2Block) -> R) -> R {
+        self.generation = self.generation.wrapping_add(1);
+        edit(&mut self.block)
+    }
+
+    /// Replace the header.
+    pub fn set_header(&mut self, header: L2BlockHeader) {
+        self.update(|b| b.header = header);
+    }
+
+    /// Replace the body.
+    pub fn set_body(&mut self, body: L2BlockBody) {
+        self.update(|b| b.body = body);
+    }
+
+    /// Replace the body data.
+    pub fn set_data(&mut self, data: Vec<u8>) {
+        self.update(|b| b.body.data = data);
+    }
+
+    /// Append an emission to the body.
+    pub fn push_emission(&mut self, emission: Emission) {
+        self.update(|b| b.body.emissions.push(emission));
+    }
+
+    /// Set the header epoch.
+    pub fn set_epoch(&mut self, epoch: u64) {
+        self.update(|b| b.header.epoch = epoch);
+    }
+
+    /// Set the header previous block root.
+    pub fn set_prev_block_root(&mut self, prev_block_root: [u8; 32]) {
+        self.update(|b| b.header.prev_block_root = prev_block_root);
+    }
+}
+
+impl From<DigL2Block> for MemoizedBlock {
+    fn from(block: DigL2Block) -> Self {
+        Self::new(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emission_config::ConsensusEmissionConfig;
+
+    fn sample() -> DigL2Block {
+        DigL2Block::builder()
+            .version(1)
+            .network_id([1u8; 32].into())
+            .epoch(3)
+            .prev_block_root([0u8; 32].into())
+            .proposer([9u8; 48].into())
+            .data(vec![1, 2, 3])
+            .config(ConsensusEmissionConfig::new(12, 0))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn repeated_reads_reuse_cache() {
+        let m = MemoizedBlock::new(sample());
+        assert_eq!(m.cached_root(), None);
+        let r1 = m.calculate_root();
+        assert_eq!(m.cached_root(), Some(r1));
+        assert_eq!(m.calculate_root(), r1);
+        assert_eq!(m.generation(), 0);
+    }
+
+    #[test]
+    fn mutation_invalidates_cache() {
+        let mut m = MemoizedBlock::new(sample());
+        let r1 = m.calculate_root();
+
+        m.set_epoch(4);
+        assert_eq!(m.generation(), 1);
+        assert_eq!(m.cached_root(), None);
+        let r2 = m.calculate_root();
+        assert_ne!(r1, r2);
+        assert_eq!(r2, m.block().calculate_root());
+
+        m.set_data(vec![9]);
+        assert_eq!(m.cached_root(), None);
+        assert_eq!(m.calculate_root(), m.block().calculate_root());
+    }
+}
@@ -0,0 +1,244 @@
+//! MessagePack encoding of the block types (feature `msgpack`).
+//!
+//! The same serde mapping as `cbor`: byte fields become `bin` items instead of
+//! `0x` hex text, and structs become maps keyed by field name. Every item uses
+//! its shortest form; strings, byte strings, arrays and maps longer than
+//! `u32::MAX` cannot be encoded (`FormatError::LengthOverflow`).
+//!
+//! The decoder rejects extension types and the reserved `0xc1` byte with
+//! `FormatError::Unsupported`. A decoded block is not checked; call
+//! `DigL2Block::verify`.
+
+pub use crate::binary_serde::FormatError;
+use crate::binary_serde::{self, Head, Wire, take_array};
+use serde::{Deserialize, Serialize};
+
+/// Encodes `value` as MessagePack.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FormatError> {
+    binary_serde::to_vec::<MsgPack, T>(value)
+}
+
+/// Decodes exactly one MessagePack-encoded `T` from `bytes`.
+pub fn from_slice<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, FormatError> {
+    binary_serde::from_slice::<MsgPack, T>(bytes)
+}
+
+/// MessagePack item headers.
+struct MsgPack;
+
+impl MsgPack {
+    /// Appends a `len` header: `fix | len` when `fix = Some((fix, limit))`
+    /// and `len < limit`, else the narrowest of the 8-, 16- and 32-bit
+    /// markers the kind has.
+    fn write_length(
+        out: &mut Vec<u8>,
+        len: usize,
+        fix: Option<(u8, usize)>,
+        (m8, m16, m32): (Option<u8>, Option<u8>, u8),
+    ) -> Result<(), FormatError> {
+        if let Some((fix, limit)) = fix
+            && len < limit
+        {
+            out.push(fix | len as u8);
+        } else if let (Some(m8), Ok(n)) = (m8, u8::try_from(len)) {
+            out.extend_from_slice(&[m8, n]);
+        } else if let (Some(m16), Ok(n)) = (m16, u16::try_from(len)) {
+            out.push(m16);
+            out.extend_from_slice(&n.to_be_bytes());
+        } else if let Ok(n) = u32::try_from(len) {
+            out.push(m32);
+            out.extend_from_slice(&n.to_be_bytes());
+        } else {
+            return Err(FormatError::LengthOverflow { len: len as u64 });
+        }
+        Ok(())
+    }
+}
+
+impl Wire for MsgPack {
+    fn write_head(out: &mut Vec<u8>, head: Head) -> Result<(), FormatError> {
+        match head {
+            Head::Unsigned(n) => {
+                if n < 0x80 {
+                    out.push(n as u8);
+                } else if let Ok(n) = u8::try_from(n) {
+                    out.extend_from_slice(&[0xcc, n]);
+                } else if let Ok(n) = u16::try_from(n) {
+                    out.push(0xcd);
+                    out.extend_from_slice(&n.to_be_bytes());
+                } else if let Ok(n) = u32::try_from(n) {
+                    out.push(0xce);
+                    out.extend_from_slice(&n.to_be_bytes());
+                } else {
+                    out.push(0xcf);
+                    out.extend_from_slice(&n.to_be_bytes());
+                }
+            }
+            Head::Negative(v) => {
+                if v >= -32 {
+                    out.push(v as u8);
+                } else if let Ok(v) = i8::try_from(v) {
+                    out.extend_from_slice(&[0xd0, v as u8]);
+                } else if let Ok(v) = i16::try_from(v) {
+                    out.push(0xd1);
+                    out.extend_from_slice(&v.to_be_bytes());
+                } else if let Ok(v) = i32::try_from(v) {
+                    out.push(0xd2);
+                    out.extend_from_slice(&v.to_be_bytes());
+                } else {
+                    out.push(0xd3);
+                    out.extend_from_slice(&v.to_be_bytes());
+                }
+            }
+            Head::Bytes(len) => Self::write_length(out, len, None, (Some(0xc4), Some(0xc5), 0xc6))?,
+            Head::Text(len) => {
+                Self::write_length(out, len, Some((0xa0, 32)), (Some(0xd9), Some(0xda), 0xdb))?
+            }
+            Head::Array(len) => {
+                Self::write_length(out, len, Some((0x90, 16)), (None, Some(0xdc), 0xdd))?
+            }
+            Head::Map(len) => {
+                Self::write_length(out, len, Some((0x80, 16)), (None, Some(0xde), 0xdf))?
+            }
+            Head::Null => out.push(0xc0),
+            Head::Bool(false) => out.push(0xc2),
+            Head::Bool(true) => out.push(0xc3),
+            Head::F32(v) => {
+                out.push(0xca);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Head::F64(v) => {
+                out.push(0xcb);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    fn read_head(input: &mut &[u8]) -> Result<Head, FormatError> {
+        let signed = |v: i64| match u64::try_from(v) {
+            Ok(n) => Head::Unsigned(n),
+            Err(_) => Head::Negative(v),
+        };
+        let [byte] = take_array(input)?;
+        let len8 = |input: &mut &[u8]| take_array::<1>(input).map(|[n]| usize::from(n));
+        let len16 =
+            |input: &mut &[u8]| take_array(input).map(|b| usize::from(u16::from_be_bytes(b)));
+        let len32 = |input: &mut &[u8]| {
+            take_array(input).and_then(|b| binary_serde::length(u32::from_be_bytes(b).into()))
+        };
+        Ok(match byte {
+            0x00..=0x7f => Head::Unsigned(byte.into()),
+            0x80..=0x8f => Head::Map(usize::from(byte & 0x0f)),
+            0x90..=0x9f => Head::Array(usize::from(byte & 0x0f)),
+            0xa0..=0xbf => Head::Text(usize::from(byte & 0x1f)),
+            0xc0 => Head::Null,
+            0xc2 => Head::Bool(false),
+            0xc3 => Head::Bool(true),
+            0xc4 => Head::Bytes(len8(input)?),
+            0xc5 => Head::Bytes(len16(input)?),
+            0xc6 => Head::Bytes(len32(input)?),
+            0xca => Head::F32(f32::from_be_bytes(take_array(input)?)),
+            0xcb => Head::F64(f64::from_be_bytes(take_array(input)?)),
+            0xcc => Head::Unsigned(u8::from_be_bytes(take_array(input)?).into()),
+            0xcd => Head::Unsigned(u16::from_be_bytes(take_array(input)?).into()),
+            0xce => Head::Unsigned(u32::from_be_bytes(take_array(input)?).into()),
+            0xcf => Head::Unsigned(u64::from_be_bytes(take_array(input)?)),
+            0xd0 => signed(i8::from_be_bytes(take_array(input)?).into()),
+            0xd1 => signed(i16::from_be_bytes(take_array(input)?).into()),
+            0xd2 => signed(i32::from_be_bytes(take_array(input)?).into()),
+            0xd3 => signed(i64::from_be_bytes(take_array(input)?)),
+            0xd9 => Head::Text(len8(input)?),
+            0xda => Head::Text(len16(input)?),
+            0xdb => Head::Text(len32(input)?),
+            0xdc => Head::Array(len16(input)?),
+            0xdd => Head::Array(len32(input)?),
+            0xde => Head::Map(len16(input)?),
+            0xdf => Head::Map(len32(input)?),
+            0xe0..=0xff => Head::Negative(i64::from(byte as i8)),
+            _ => return Err(FormatError::Unsupported { byte }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Encodes `value`, checks the bytes against `expected` hex (forms from
+    /// the MessagePack spec) and decodes them back.
+    fn check<T>(value: T, expected: &str)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+    {
+        let bytes = to_vec(&value).unwrap();
+        assert_eq!(hex::encode(&bytes), expected, "{value:?}");
+        assert_eq!(from_slice::<T>(&bytes).unwrap(), value);
+    }
+
+    /// A `Vec<u8>` carried through `serde_hex`, i.e. as a `bin` item.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Bytes(#[serde(with = "crate::serde_hex::hex_vec")] Vec<u8>);
+
+    #[test]
+    fn encodes_shortest_forms() {
+        check(0u8, "00");
+        check(127u8, "7f");
+        check(128u8, "cc80");
+        check(256u16, "cd0100");
+        check(65_536u32, "ce00010000");
+        check(1u64 << 32, "cf0000000100000000");
+        check(-1i8, "ff");
+        check(-32i8, "e0");
+        check(-33i8, "d0df");
+        check(-129i16, "d1ff7f");
+        check(-32_769i32, "d2ffff7fff");
+        check(i64::MIN, "d38000000000000000");
+        check(1.5f32, "ca3fc00000");
+        check(1.5f64, "cb3ff8000000000000");
+        check(None::<u8>, "c0");
+        check(false, "c2");
+        check(true, "c3");
+        check("a".to_owned(), "a161");
+        check("x".repeat(32), format!("d920{}", "78".repeat(32)).as_str());
+        check(vec![1u8, 2, 3], "93010203");
+        check(vec![0u8; 16], format!("dc0010{}", "00".repeat(16)).as_str());
+        check(BTreeMap::from([("a".to_owned(), 1u8)]), "81a16101");
+        check(Bytes(vec![1, 2, 3, 4]), "c40401020304");
+        check(
+            Bytes(vec![7; 256]),
+            format!("c50100{}", "07".repeat(256)).as_str(),
+        );
+    }
+
+    #[test]
+    fn signed_markers_decode_non_negative_values() {
+        assert_eq!(from_slice::<u8>(&[0xd0, 0x05]), Ok(5));
+        assert_eq!(from_slice::<i64>(&[0xd1, 0xff, 0xfe]), Ok(-2));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(from_slice::<u8>(&[]), Err(FormatError::UnexpectedEof));
+        assert_eq!(
+            from_slice::<u8>(&[0x01, 0x02]),
+            Err(FormatError::TrailingBytes { len: 1 })
+        );
+        assert_eq!(
+            from_slice::<Bytes>(&[0xc4, 4, 1, 2]),
+            Err(FormatError::UnexpectedEof)
+        );
+        // Reserved byte, ext 8, fixext 1.
+        for byte in [0xc1, 0xc7, 0xd4] {
+            assert_eq!(
+                from_slice::<u8>(&[byte, 0, 0]),
+                Err(FormatError::Unsupported { byte })
+            );
+        }
+        assert_eq!(
+            from_slice::<String>(&[0xa1, 0xff]),
+            Err(FormatError::InvalidUtf8)
+        );
+    }
+}
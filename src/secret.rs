@@ -0,0 +1,119 @@
+//! Wipe-on-drop wrapper for sensitive builder inputs (feature `zeroize`).
+//!
+//! The crate itself only handles public keys, but callers embedding the
+//! builder in signing flows sometimes carry secret material through block
+//! assembly. `SecretPubkeyInput` holds such 48-byte values and overwrites them
+//! with zeros when dropped, so they do not linger in freed memory.
+//!
+//! The wipe uses volatile writes followed by a compiler fence so it cannot be
+//! optimized away as a dead store; no external `zeroize` dependency is needed.
+//! Copies the caller makes with `expose` are not tracked.
+
+use std::sync::atomic::{Ordering, compiler_fence};
+
+/// A 48-byte value (e.g. key material) zeroed on drop.
+///
+/// Deliberately not `Copy`, and `Debug` prints a placeholder, so the bytes are
+/// only reachable through `expose`. `==` takes the same time wherever the
+/// values first differ.
+#[derive(Clone)]
+pub struct SecretPubkeyInput {
+    bytes: [u8; 48],
+}
+
+impl SecretPubkeyInput {
+    /// Wraps `bytes`.
+    pub fn new(bytes: [u8; 48]) -> Self {
+        SecretPubkeyInput { bytes }
+    }
+
+    /// The wrapped bytes.
+    pub fn expose(&self) -> &[u8; 48] {
+        &self.bytes
+    }
+}
+
+impl From<[u8; 48]> for SecretPubkeyInput {
+    fn from(bytes: [u8; 48]) -> Self {
+        SecretPubkeyInput::new(bytes)
+    }
+}
+
+impl PartialEq for SecretPubkeyInput {
+    fn eq(&self, other: &Self) -> bool {
+        let mut diff = 0u8;
+        for (x, y) in self.bytes.iter().zip(&other.bytes) {
+            diff |= x ^ y;
+        }
+        std::hint::black_box(diff) == 0
+    }
+}
+
+impl Eq for SecretPubkeyInput {}
+
+impl std::fmt::Debug for SecretPubkeyInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretPubkeyInput(<redacted>)")
+    }
+}
+
+impl Drop for SecretPubkeyInput {
+    fn drop(&mut self) {
+        wipe(&mut self.bytes);
+    }
+}
+
+/// Overwrites `bytes` with zeros in a way the optimizer must keep.
+fn wipe(bytes: &mut [u8]) {
+    for b in bytes.iter_mut() {
+        // SAFETY: `b` is a unique, aligned reference to an initialized `u8`.
+        unsafe { std::ptr::write_volatile(b, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::ManuallyDrop;
+
+    #[test]
+    fn secret_is_zeroed_on_drop() {
+        let mut slot = ManuallyDrop::new(SecretPubkeyInput::new([0x5au8; 48]));
+        assert_eq!(slot.expose(), &[0x5au8; 48]);
+        // One raw pointer for both the drop and the reads, so no later borrow
+        // of `slot` invalidates it.
+        let raw: *mut SecretPubkeyInput = &mut *slot;
+
+        // SAFETY: `raw` points to a live, initialized value that is dropped
+        // exactly once here; `ManuallyDrop` keeps its storage allocated.
+        unsafe { std::ptr::drop_in_place(raw) };
+        // SAFETY: a raw place projection into the still-allocated storage; no
+        // reference to the dropped value is created.
+        let bytes = unsafe { &raw const (*raw).bytes }.cast::<u8>();
+        for i in 0..48 {
+            // SAFETY: `bytes + i` lies within the 48-byte field, and any bit
+            // pattern is a valid `u8`.
+            assert_eq!(unsafe { std::ptr::read_volatile(bytes.add(i)) }, 0);
+        }
+    }
+
+    #[test]
+    fn eq_compares_every_byte() {
+        let a = SecretPubkeyInput::new([0x5au8; 48]);
+        assert_eq!(a, a.clone());
+        for i in [0, 47] {
+            let mut bytes = [0x5au8; 48];
+            bytes[i] ^= 1;
+            assert_ne!(a, SecretPubkeyInput::new(bytes));
+        }
+    }
+
+    #[test]
+    fn debug_redacts_bytes() {
+        let secret = SecretPubkeyInput::from([0x5au8; 48]);
+        let shown = format!("{secret:?}");
+        assert_eq!(shown, "SecretPubkeyInput(<redacted>)");
+        assert!(!shown.contains("5a"));
+    }
+}
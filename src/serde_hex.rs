@@ -4,7 +4,9 @@
 //! - `hex32`: for `[u8; 32]` with exact length enforcement.
 //! - `hex48`: for `[u8; 48]` with exact length enforcement.
 //!
-//! These helpers ensure strict `0x` prefix and lowercase hex encoding.
+//! These helpers ensure strict `0x` prefix and lowercase hex encoding for
+//! human-readable formats (JSON). Binary formats (`is_human_readable() ==
+//! false`, e.g. CBOR or MessagePack) get the raw bytes instead.
 
 use serde::{Deserialize, Deserializer, Serializer};
 use thiserror::Error;
@@ -16,10 +18,14 @@ pub enum HexSerdeError {
     #[error("missing 0x prefix")]
     MissingPrefix,
 
-    /// Input contained non-hex characters or odd-length digits.
+    /// Input contained non-hex characters.
     #[error("invalid hex encoding: {0}")]
     InvalidHex(String),
 
+    /// The digits after `0x` have odd length `len`, so they cannot form whole bytes.
+    #[error("odd hex length: {len} digits after 0x")]
+    OddLength { len: usize },
+
     /// For fixed-size arrays: decoded byte length did not match the expected size.
     #[error("length mismatch: expected {expected} bytes, got {actual} bytes")]
     LengthMismatch { expected: usize, actual: usize },
@@ -33,37 +39,129 @@ fn strip_0x(s: &str) -> Result<&str, HexSerdeError> {
     }
 }
 
-fn encode_lower_hex_prefixed(bytes: &[u8]) -> String {
+pub(crate) fn encode_lower_hex_prefixed(bytes: &[u8]) -> String {
     let mut out = String::with_capacity(2 + bytes.len() * 2);
     out.push_str("0x");
     out.push_str(&hex::encode(bytes));
     out
 }
 
+/// Decode a `0x`-prefixed hex string of any length.
+pub(crate) fn decode_hex_prefixed(s: &str) -> Result<Vec<u8>, HexSerdeError> {
+    let hex_part = strip_0x(s)?;
+    if !hex_part.len().is_multiple_of(2) {
+        return Err(HexSerdeError::OddLength {
+            len: hex_part.len(),
+        });
+    }
+    hex::decode(hex_part).map_err(|e| HexSerdeError::InvalidHex(e.to_string()))
+}
+
+/// Decode a `0x`-prefixed hex string into exactly `N` bytes.
+pub(crate) fn decode_hex_array<const N: usize>(s: &str) -> Result<[u8; N], HexSerdeError> {
+    let bytes = decode_hex_prefixed(s)?;
+    if bytes.len() != N {
+        return Err(HexSerdeError::LengthMismatch {
+            expected: N,
+            actual: bytes.len(),
+        });
+    }
+    let mut arr = [0u8; N];
+    arr.copy_from_slice(&bytes);
+    Ok(arr)
+}
+
+/// Serialize as `0x` hex for human-readable formats, raw bytes otherwise.
+fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&encode_lower_hex_prefixed(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+/// Deserialize from `0x` hex for human-readable formats, raw bytes otherwise.
+fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        let s: String = String::deserialize(deserializer)?;
+        decode_hex_prefixed(&s).map_err(|e| serde::de::Error::custom(e.to_string()))
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+/// Like `deserialize_bytes`, enforcing exactly `N` bytes.
+fn deserialize_array<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        let s: String = String::deserialize(deserializer)?;
+        return decode_hex_array(&s).map_err(|e| serde::de::Error::custom(e.to_string()));
+    }
+    let bytes = deserializer.deserialize_byte_buf(BytesVisitor)?;
+    bytes.try_into().map_err(|bytes: Vec<u8>| {
+        serde::de::Error::custom(
+            HexSerdeError::LengthMismatch {
+                expected: N,
+                actual: bytes.len(),
+            }
+            .to_string(),
+        )
+    })
+}
+
+/// Accepts raw bytes, or a sequence of `u8` for formats without a bytes type.
+struct BytesVisitor;
+
+impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element::<u8>()? {
+            out.push(b);
+        }
+        Ok(out)
+    }
+}
+
 /// Serde helpers for `Vec<u8>` as 0x-hex.
 pub mod hex_vec {
     use super::*;
 
-    /// Serialize a `Vec<u8>` as an `"0x..."` lowercase hex string.
+    /// Serialize a `Vec<u8>` as an `"0x..."` lowercase hex string (raw bytes in binary formats).
     pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let s = encode_lower_hex_prefixed(bytes);
-        serializer.serialize_str(&s)
+        serialize_bytes(bytes, serializer)
     }
 
-    /// Deserialize a `Vec<u8>` from an `"0x..."` lowercase hex string.
+    /// Deserialize a `Vec<u8>` from an `"0x..."` lowercase hex string (raw bytes in binary formats).
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s: String = String::deserialize(deserializer)?;
-        let hex_part = strip_0x(&s).map_err(|e| serde::de::Error::custom(e.to_string()))?;
-        let bytes = hex::decode(hex_part).map_err(|e| {
-            serde::de::Error::custom(HexSerdeError::InvalidHex(e.to_string()).to_string())
-        })?;
-        Ok(bytes)
+        deserialize_bytes(deserializer)
     }
 }
 
@@ -71,37 +169,20 @@ pub mod hex_vec {
 pub mod hex32 {
     use super::*;
 
-    /// Serialize a `[u8; 32]` as an `"0x..."` lowercase hex string.
+    /// Serialize a `[u8; 32]` as an `"0x..."` lowercase hex string (raw bytes in binary formats).
     pub fn serialize<S>(bytes: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let s = encode_lower_hex_prefixed(bytes);
-        serializer.serialize_str(&s)
+        serialize_bytes(bytes, serializer)
     }
 
-    /// Deserialize a `[u8; 32]` from an `"0x..."` lowercase hex string.
+    /// Deserialize a `[u8; 32]` from an `"0x..."` lowercase hex string (raw bytes in binary formats).
     pub fn deserialize<'de, D>(deserializer: D) -> Result<[u8; 32], D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s: String = String::deserialize(deserializer)?;
-        let hex_part = strip_0x(&s).map_err(|e| serde::de::Error::custom(e.to_string()))?;
-        let bytes = hex::decode(hex_part).map_err(|e| {
-            serde::de::Error::custom(HexSerdeError::InvalidHex(e.to_string()).to_string())
-        })?;
-        if bytes.len() != 32 {
-            return Err(serde::de::Error::custom(
-                HexSerdeError::LengthMismatch {
-                    expected: 32,
-                    actual: bytes.len(),
-                }
-                .to_string(),
-            ));
-        }
-        let mut arr = [0u8; 32];
-        arr.copy_from_slice(&bytes);
-        Ok(arr)
+        deserialize_array(deserializer)
     }
 }
 
@@ -109,37 +190,20 @@ pub mod hex32 {
 pub mod hex48 {
     use super::*;
 
-    /// Serialize a `[u8; 48]` as an `"0x..."` lowercase hex string.
+    /// Serialize a `[u8; 48]` as an `"0x..."` lowercase hex string (raw bytes in binary formats).
     pub fn serialize<S>(bytes: &[u8; 48], serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let s = encode_lower_hex_prefixed(bytes);
-        serializer.serialize_str(&s)
+        serialize_bytes(bytes, serializer)
     }
 
-    /// Deserialize a `[u8; 48]` from an `"0x..."` lowercase hex string.
+    /// Deserialize a `[u8; 48]` from an `"0x..."` lowercase hex string (raw bytes in binary formats).
     pub fn deserialize<'de, D>(deserializer: D) -> Result<[u8; 48], D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s: String = String::deserialize(deserializer)?;
-        let hex_part = strip_0x(&s).map_err(|e| serde::de::Error::custom(e.to_string()))?;
-        let bytes = hex::decode(hex_part).map_err(|e| {
-            serde::de::Error::custom(HexSerdeError::InvalidHex(e.to_string()).to_string())
-        })?;
-        if bytes.len() != 48 {
-            return Err(serde::de::Error::custom(
-                HexSerdeError::LengthMismatch {
-                    expected: 48,
-                    actual: bytes.len(),
-                }
-                .to_string(),
-            ));
-        }
-        let mut arr = [0u8; 48];
-        arr.copy_from_slice(&bytes);
-        Ok(arr)
+        deserialize_array(deserializer)
     }
 }
 
@@ -223,6 +287,229 @@ mod tests {
         assert!(msg.contains("length mismatch"));
     }
 
+    /// Minimal non-human-readable format: a value is a single byte string.
+    mod binary {
+        use serde::ser::{self, Impossible, Serialize};
+        use serde::{de, forward_to_deserialize_any};
+
+        #[derive(Debug)]
+        pub struct Error(pub String);
+
+        impl std::fmt::Display for Error {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl std::error::Error for Error {}
+
+        impl ser::Error for Error {
+            fn custom<T: std::fmt::Display>(msg: T) -> Self {
+                Error(msg.to_string())
+            }
+        }
+
+        impl de::Error for Error {
+            fn custom<T: std::fmt::Display>(msg: T) -> Self {
+                Error(msg.to_string())
+            }
+        }
+
+        pub fn to_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, Error> {
+            value.serialize(Serializer)
+        }
+
+        pub fn from_bytes<T: de::DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+            T::deserialize(Deserializer(bytes))
+        }
+
+        struct Serializer;
+
+        macro_rules! unsupported {
+            ($($name:ident($($ty:ty),*)),* $(,)?) => {
+                $(fn $name(self, $(_: $ty),*) -> Result<Vec<u8>, Error> {
+                    Err(Error(stringify!($name).into()))
+                })*
+            };
+        }
+
+        impl ser::Serializer for Serializer {
+            type Ok = Vec<u8>;
+            type Error = Error;
+            type SerializeSeq = Impossible<Vec<u8>, Error>;
+            type SerializeTuple = Impossible<Vec<u8>, Error>;
+            type SerializeTupleStruct = Impossible<Vec<u8>, Error>;
+            type SerializeTupleVariant = Impossible<Vec<u8>, Error>;
+            type SerializeMap = Impossible<Vec<u8>, Error>;
+            type SerializeStruct = Impossible<Vec<u8>, Error>;
+            type SerializeStructVariant = Impossible<Vec<u8>, Error>;
+
+            fn is_human_readable(&self) -> bool {
+                false
+            }
+
+            fn serialize_bytes(self, v: &[u8]) -> Result<Vec<u8>, Error> {
+                Ok(v.to_vec())
+            }
+
+            fn serialize_newtype_struct<T: ?Sized + Serialize>(
+                self,
+                _: &'static str,
+                value: &T,
+            ) -> Result<Vec<u8>, Error> {
+                value.serialize(self)
+            }
+
+            unsupported!(
+                serialize_bool(bool),
+                serialize_i8(i8),
+                serialize_i16(i16),
+                serialize_i32(i32),
+                serialize_i64(i64),
+                serialize_u8(u8),
+                serialize_u16(u16),
+                serialize_u32(u32),
+                serialize_u64(u64),
+                serialize_f32(f32),
+                serialize_f64(f64),
+                serialize_char(char),
+                serialize_str(&str),
+                serialize_none(),
+                serialize_unit(),
+                serialize_unit_struct(&'static str),
+                serialize_unit_variant(&'static str, u32, &'static str),
+            );
+
+            fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<Vec<u8>, Error> {
+                Err(Error("some".into()))
+            }
+
+            fn serialize_newtype_variant<T: ?Sized + Serialize>(
+                self,
+                _: &'static str,
+                _: u32,
+                _: &'static str,
+                _: &T,
+            ) -> Result<Vec<u8>, Error> {
+                Err(Error("newtype_variant".into()))
+            }
+
+            fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+                Err(Error("seq".into()))
+            }
+
+            fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+                Err(Error("tuple".into()))
+            }
+
+            fn serialize_tuple_struct(
+                self,
+                _: &'static str,
+                _: usize,
+            ) -> Result<Self::SerializeTupleStruct, Error> {
+                Err(Error("tuple_struct".into()))
+            }
+
+            fn serialize_tuple_variant(
+                self,
+                _: &'static str,
+                _: u32,
+                _: &'static str,
+                _: usize,
+            ) -> Result<Self::SerializeTupleVariant, Error> {
+                Err(Error("tuple_variant".into()))
+            }
+
+            fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+                Err(Error("map".into()))
+            }
+
+            fn serialize_struct(
+                self,
+                _: &'static str,
+                _: usize,
+            ) -> Result<Self::SerializeStruct, Error> {
+                Err(Error("struct".into()))
+            }
+
+            fn serialize_struct_variant(
+                self,
+                _: &'static str,
+                _: u32,
+                _: &'static str,
+                _: usize,
+            ) -> Result<Self::SerializeStructVariant, Error> {
+                Err(Error("struct_variant".into()))
+            }
+        }
+
+        struct Deserializer<'a>(&'a [u8]);
+
+        impl<'de> de::Deserializer<'de> for Deserializer<'_> {
+            type Error = Error;
+
+            fn is_human_readable(&self) -> bool {
+                false
+            }
+
+            fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.visit_bytes(self.0)
+            }
+
+            fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+                self,
+                _: &'static str,
+                visitor: V,
+            ) -> Result<V::Value, Error> {
+                visitor.visit_newtype_struct(self)
+            }
+
+            forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+                bytes byte_buf option unit unit_struct seq tuple
+                tuple_struct map struct enum identifier ignored_any
+            }
+        }
+    }
+
+    #[test]
+    fn binary_formats_get_raw_bytes() {
+        let v = VecWrap(vec![0x00, 0x01, 0xaa, 0xff]);
+        let bytes = binary::to_bytes(&v).unwrap();
+        assert_eq!(bytes, vec![0x00, 0x01, 0xaa, 0xff]);
+        assert_eq!(binary::from_bytes::<VecWrap>(&bytes).unwrap(), v);
+
+        let a = Arr32Wrap([0x5au8; 32]);
+        assert_eq!(binary::to_bytes(&a).unwrap(), vec![0x5au8; 32]);
+        assert_eq!(binary::from_bytes::<Arr32Wrap>(&[0x5au8; 32]).unwrap(), a);
+
+        let a = Arr48Wrap([0xa5u8; 48]);
+        assert_eq!(binary::to_bytes(&a).unwrap(), vec![0xa5u8; 48]);
+        assert_eq!(binary::from_bytes::<Arr48Wrap>(&[0xa5u8; 48]).unwrap(), a);
+    }
+
+    #[test]
+    fn binary_arrays_enforce_length() {
+        let err = binary::from_bytes::<Arr32Wrap>(&[0u8; 31]).unwrap_err();
+        assert!(err.to_string().contains("length mismatch"));
+        let err = binary::from_bytes::<Arr48Wrap>(&[0u8; 49]).unwrap_err();
+        assert!(err.to_string().contains("length mismatch"));
+    }
+
+    #[test]
+    fn vec_round_trip_random_bytes() {
+        let mut rng = crate::testing::TestRng::new(0x853c_49e6_748f_ea9b);
+        for _ in 0..200 {
+            let len = rng.below(300);
+            let v = VecWrap(rng.bytes(len));
+            let s = serde_json::to_string(&v).unwrap();
+            assert_eq!(s, format!("\"0x{}\"", hex::encode(&v.0)));
+            assert_eq!(serde_json::from_str::<VecWrap>(&s).unwrap(), v);
+            let raw = binary::to_bytes(&v).unwrap();
+            assert_eq!(binary::from_bytes::<VecWrap>(&raw).unwrap(), v);
+        }
+    }
+
     #[test]
     fn invalid_hex_char_rejected() {
         let s = "\"0xzz\""; // invalid hex
@@ -230,4 +517,27 @@ mod tests {
         let msg = err.to_string();
         assert!(msg.contains("invalid hex encoding"));
     }
+
+    #[test]
+    fn odd_length_rejected_before_decoding() {
+        // Odd length is reported even when a character is also invalid.
+        for s in ["\"0xabc\"", "\"0xzzz\""] {
+            let msg = serde_json::from_str::<VecWrap>(s).unwrap_err().to_string();
+            assert!(msg.contains("odd hex length: 3 digits after 0x"), "{msg}");
+        }
+        let odd32 = format!("\"0x{}\"", "a".repeat(63));
+        let msg = serde_json::from_str::<Arr32Wrap>(&odd32)
+            .unwrap_err()
+            .to_string();
+        assert!(msg.contains("odd hex length: 63 digits"), "{msg}");
+        let odd48 = format!("\"0x{}\"", "a".repeat(97));
+        let msg = serde_json::from_str::<Arr48Wrap>(&odd48)
+            .unwrap_err()
+            .to_string();
+        assert!(msg.contains("odd hex length: 97 digits"), "{msg}");
+        assert!(matches!(
+            super::decode_hex_prefixed("0x1"),
+            Err(super::HexSerdeError::OddLength { len: 1 })
+        ));
+    }
 }
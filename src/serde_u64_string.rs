@@ -0,0 +1,85 @@
+//! Serde helper to encode `u64` values as decimal strings.
+//!
+//! JavaScript numbers lose precision above 2^53, so a TypeScript client
+//! parsing `"weight": 18446744073709551615` sees a different value. Fields
+//! tagged `#[serde(with = "crate::serde_u64_string")]` serialize as
+//! `"18446744073709551615"` instead. Deserialization accepts the string form
+//! and, for compatibility with older payloads, a plain JSON number.
+//!
+//! The core block types keep numeric JSON; `web` applies this helper to their
+//! `u64` fields.
+
+use serde::{Deserializer, Serializer};
+
+/// Serialize a `u64` as a decimal string.
+pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Deserialize a `u64` from a decimal string or a non-negative integer.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(U64Visitor)
+}
+
+struct U64Visitor;
+
+impl serde::de::Visitor<'_> for U64Visitor {
+    type Value = u64;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a u64 as a decimal string or integer")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v).map_err(|_| E::custom(format!("negative u64: {v}")))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        // `u64::from_str` accepts a leading `+`; require plain digits.
+        if v.is_empty() || !v.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(E::custom(format!("invalid decimal u64: {v:?}")));
+        }
+        v.parse()
+            .map_err(|_| E::custom(format!("u64 out of range: {v}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+    struct Wrap(#[serde(with = "crate::serde_u64_string")] u64);
+
+    #[test]
+    fn max_round_trips_exactly() {
+        let s = serde_json::to_string(&Wrap(u64::MAX)).unwrap();
+        assert_eq!(s, "\"18446744073709551615\"");
+        assert_eq!(serde_json::from_str::<Wrap>(&s).unwrap(), Wrap(u64::MAX));
+    }
+
+    #[test]
+    fn accepts_numbers_and_rejects_junk() {
+        assert_eq!(serde_json::from_str::<Wrap>("7").unwrap(), Wrap(7));
+        for bad in [
+            "\"\"",
+            "\"+7\"",
+            "\"-1\"",
+            "\"1.5\"",
+            "\"18446744073709551616\"",
+            "-1",
+        ] {
+            assert!(serde_json::from_str::<Wrap>(bad).is_err(), "{bad}");
+        }
+    }
+}
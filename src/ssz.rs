@@ -0,0 +1,325 @@
+//! SimpleSerialize (SSZ) encoding for the block types (feature `ssz`).
+//!
+//! Schemas, following the SSZ spec for containers, vectors and lists:
+//! - `Emission`: `Container { pubkey: Vector[uint8, 48], weight: uint64 }` (56 bytes)
+//! - `L2BlockHeader`: `Container { version: uint32, network_id: Bytes32,
+//!   epoch: uint64, prev_block_root: Bytes32, body_root: Bytes32,
+//!   data_count: uint32, emissions_count: uint32, proposer_pubkey: Bytes48,
+//!   timestamp: uint64, extra_data: Bytes32 }`
+//! - `L2BlockBody`: `Container { data: List[uint8, N], emissions: List[Emission, M] }`
+//! - `DigL2Block`: `Container { header: L2BlockHeader, body: L2BlockBody }`
+//!
+//! Integers are little-endian. Variable-size fields are written as 4-byte
+//! offsets in the fixed part followed by their contents, so encoding panics
+//! if an offset would exceed `u32::MAX` (a body of over 4 GiB). This encoding
+//! is purely additive; Merkle roots remain the SHA-256 roots of
+//! `dig_l2_definition`.
+
+use crate::block::DigL2Block;
+use crate::body::L2BlockBody;
+use crate::emission::Emission;
+use crate::header::L2BlockHeader;
+use thiserror::Error;
+
+/// SSZ size of an encoded `Emission`.
+pub const EMISSION_SSZ_LEN: usize = 48 + 8;
+/// SSZ size of an encoded `L2BlockHeader`.
+pub const HEADER_SSZ_LEN: usize = 4 + 32 + 8 + 32 + 32 + 4 + 4 + 48 + 8 + 32;
+/// Size of an SSZ offset.
+const OFFSET_LEN: usize = 4;
+
+/// SSZ encoding and decoding.
+pub trait Ssz: Sized {
+    /// Append the SSZ encoding of `self` to `buf`.
+    fn ssz_append(&self, buf: &mut Vec<u8>);
+
+    /// Decode a value from exactly `bytes`.
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, SszError>;
+
+    /// The SSZ encoding of `self`.
+    fn to_ssz_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.ssz_append(&mut buf);
+        buf
+    }
+}
+
+/// Errors produced while decoding SSZ.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SszError {
+    /// Input length does not fit the type.
+    #[error("invalid ssz length for {ty}: {len} bytes")]
+    InvalidLength { ty: &'static str, len: usize },
+
+    /// A variable-size field offset is out of order or out of bounds.
+    #[error("invalid ssz offset {offset} for {ty}")]
+    InvalidOffset { ty: &'static str, offset: usize },
+}
+
+/// Little-endian reader over a fixed-size part whose length was already checked.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let (head, rest) = self.0.split_at(N);
+        self.0 = rest;
+        head.try_into().unwrap()
+    }
+}
+
+fn read_offset(bytes: &[u8], at: usize) -> usize {
+    u32::from_le_bytes(bytes[at..at + OFFSET_LEN].try_into().unwrap()) as usize
+}
+
+/// Writes a 4-byte SSZ offset.
+///
+/// # Panics
+/// If `offset` exceeds `u32::MAX`, rather than writing a truncated offset
+/// that disagrees with the payload.
+fn push_offset(buf: &mut Vec<u8>, offset: usize) {
+    let offset = u32::try_from(offset).expect("ssz offset fits in u32");
+    buf.extend_from_slice(&offset.to_le_bytes());
+}
+
+impl Ssz for Emission {
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.pubkey);
+        buf.extend_from_slice(&self.weight.to_le_bytes());
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, SszError> {
+        if bytes.len() != EMISSION_SSZ_LEN {
+            return Err(SszError::InvalidLength {
+                ty: "Emission",
+                len: bytes.len(),
+            });
+        }
+        let mut r = Reader(bytes);
+        Ok(Emission {
+            pubkey: r.take(),
+            weight: u64::from_le_bytes(r.take()),
+        })
+    }
+}
+
+impl Ssz for L2BlockHeader {
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.version.to_le_bytes());
+        buf.extend_from_slice(&self.network_id);
+        buf.extend_from_slice(&self.epoch.to_le_bytes());
+        buf.extend_from_slice(&self.prev_block_root);
+        buf.extend_from_slice(&self.body_root);
+        buf.extend_from_slice(&self.data_count.to_le_bytes());
+        buf.extend_from_slice(&self.emissions_count.to_le_bytes());
+        buf.extend_from_slice(&self.proposer_pubkey);
+        buf.extend_from_slice(&self.timestamp.to_le_bytes());
+        buf.extend_from_slice(&self.extra_data);
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, SszError> {
+        if bytes.len() != HEADER_SSZ_LEN {
+            return Err(SszError::InvalidLength {
+                ty: "L2BlockHeader",
+                len: bytes.len(),
+            });
+        }
+        let mut r = Reader(bytes);
+        Ok(L2BlockHeader {
+            version: u32::from_le_bytes(r.take()),
+            network_id: r.take(),
+            epoch: u64::from_le_bytes(r.take()),
+            prev_block_root: r.take(),
+            body_root: r.take(),
+            data_count: u32::from_le_bytes(r.take()),
+            emissions_count: u32::from_le_bytes(r.take()),
+            proposer_pubkey: r.take(),
+            timestamp: u64::from_le_bytes(r.take()),
+            extra_data: r.take(),
+        })
+    }
+}
+
+impl Ssz for L2BlockBody {
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        let fixed = 2 * OFFSET_LEN;
+        push_offset(buf, fixed);
+        push_offset(buf, fixed + self.data.len());
+        buf.extend_from_slice(&self.data);
+        for e in &self.emissions {
+            e.ssz_append(buf);
+        }
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, SszError> {
+        let ty = "L2BlockBody";
+        let fixed = 2 * OFFSET_LEN;
+        if bytes.len() < fixed {
+            return Err(SszError::InvalidLength {
+                ty,
+                len: bytes.len(),
+            });
+        }
+        let data_offset = read_offset(bytes, 0);
+        if data_offset != fixed {
+            return Err(SszError::InvalidOffset {
+                ty,
+                offset: data_offset,
+            });
+        }
+        let emissions_offset = read_offset(bytes, OFFSET_LEN);
+        if emissions_offset < data_offset || emissions_offset > bytes.len() {
+            return Err(SszError::InvalidOffset {
+                ty,
+                offset: emissions_offset,
+            });
+        }
+        let emissions_bytes = &bytes[emissions_offset..];
+        if !emissions_bytes.len().is_multiple_of(EMISSION_SSZ_LEN) {
+            return Err(SszError::InvalidLength {
+                ty: "List[Emission]",
+                len: emissions_bytes.len(),
+            });
+        }
+        let emissions = emissions_bytes
+            .chunks_exact(EMISSION_SSZ_LEN)
+            .map(Emission::from_ssz_bytes)
+            .collect::<Result<_, _>>()?;
+        Ok(L2BlockBody {
+            data: bytes[data_offset..emissions_offset].to_vec(),
+            emissions,
+        })
+    }
+}
+
+impl Ssz for DigL2Block {
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.header.ssz_append(buf);
+        push_offset(buf, HEADER_SSZ_LEN + OFFSET_LEN);
+        self.body.ssz_append(buf);
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, SszError> {
+        let ty = "DigL2Block";
+        let fixed = HEADER_SSZ_LEN + OFFSET_LEN;
+        if bytes.len() < fixed {
+            return Err(SszError::InvalidLength {
+                ty,
+                len: bytes.len(),
+            });
+        }
+        let body_offset = read_offset(bytes, HEADER_SSZ_LEN);
+        if body_offset != fixed {
+            return Err(SszError::InvalidOffset {
+                ty,
+                offset: body_offset,
+            });
+        }
+        Ok(DigL2Block {
+            header: L2BlockHeader::from_ssz_bytes(&bytes[..HEADER_SSZ_LEN])?,
+            body: L2BlockBody::from_ssz_bytes(&bytes[body_offset..])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_block() -> DigL2Block {
+        let body = L2BlockBody {
+            data: vec![1, 2, 3],
+            emissions: vec![Emission {
+                pubkey: [0x11u8; 48],
+                weight: 5,
+            }],
+        };
+        let header = L2BlockHeader {
+            version: 1,
+            network_id: [0xaau8; 32],
+            epoch: 2,
+            prev_block_root: [0xbbu8; 32],
+            body_root: [0xccu8; 32],
+            data_count: 3,
+            emissions_count: 1,
+            proposer_pubkey: [0xddu8; 48],
+            timestamp: 0x0102,
+            extra_data: [0xeeu8; 32],
+        };
+        DigL2Block { header, body }
+    }
+
+    #[test]
+    fn emission_known_vector() {
+        let e = Emission {
+            pubkey: [0x11u8; 48],
+            weight: 5,
+        };
+        let expect = format!("{}0500000000000000", "11".repeat(48));
+        assert_eq!(hex::encode(e.to_ssz_bytes()), expect);
+    }
+
+    #[test]
+    fn block_known_vector() {
+        let expect = [
+            // header (204 bytes)
+            "01000000".to_string(),
+            "aa".repeat(32),
+            "0200000000000000".to_string(),
+            "bb".repeat(32),
+            "cc".repeat(32),
+            "03000000".to_string(),
+            "01000000".to_string(),
+            "dd".repeat(48),
+            "0201000000000000".to_string(),
+            "ee".repeat(32),
+            // offset of body = 204 + 4 = 208 = 0xd0
+            "d0000000".to_string(),
+            // body: data offset 8, emissions offset 8 + 3 = 11
+            "08000000".to_string(),
+            "0b000000".to_string(),
+            "010203".to_string(),
+            "11".repeat(48),
+            "0500000000000000".to_string(),
+        ]
+        .concat();
+        let block = small_block();
+        assert_eq!(hex::encode(block.to_ssz_bytes()), expect);
+        let back = DigL2Block::from_ssz_bytes(&hex::decode(&expect).unwrap()).unwrap();
+        assert_eq!(back, block);
+        crate::testing::assert_root_stable(&block);
+    }
+
+    #[test]
+    fn empty_body_round_trip() {
+        let body = L2BlockBody {
+            data: vec![],
+            emissions: vec![],
+        };
+        let bytes = body.to_ssz_bytes();
+        assert_eq!(hex::encode(&bytes), "0800000008000000");
+        assert_eq!(L2BlockBody::from_ssz_bytes(&bytes).unwrap(), body);
+    }
+
+    #[test]
+    fn decode_rejects_malformed_input() {
+        let bytes = small_block().to_ssz_bytes();
+        assert!(matches!(
+            DigL2Block::from_ssz_bytes(&bytes[..100]),
+            Err(SszError::InvalidLength { .. })
+        ));
+        // Trailing partial emission.
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert!(matches!(
+            DigL2Block::from_ssz_bytes(&extra),
+            Err(SszError::InvalidLength { .. })
+        ));
+        // Emissions offset beyond the input.
+        let mut bad_offset = bytes.clone();
+        bad_offset[HEADER_SSZ_LEN + OFFSET_LEN + 4] = 0xff;
+        assert!(matches!(
+            DigL2Block::from_ssz_bytes(&bad_offset),
+            Err(SszError::InvalidOffset { .. })
+        ));
+    }
+}
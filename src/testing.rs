@@ -0,0 +1,289 @@
+//! Test helpers shared by this crate and downstream tests (feature `testing`).
+//!
+//! - `assert_root_stable`: serialization round-trip invariant.
+//! - `FuzzBytes`, `FromFuzz`, `raw_block`: byte-driven generators for fuzz
+//!   targets (e.g. a `cargo-fuzz` `fuzz_target!` over `&[u8]`).
+//! - `TestRng`: seeded generator for property tests.
+
+use crate::block::{self, DigL2Block};
+use crate::body::L2BlockBody;
+use crate::dig_l2_definition as definitions;
+use crate::emission::Emission;
+use crate::header::L2BlockHeader;
+
+/// Asserts that `block` survives every enabled serialization format with an
+/// unchanged `calculate_root`.
+///
+/// Covers JSON, canonical JSON (`block::to_canonical_json`), canonical bytes
+/// (`to_canonical_bytes`) and, with their features, CBOR, MessagePack and SSZ.
+/// New formats should be added here so each one is held to the same invariant.
+///
+/// # Panics
+/// If any format fails to round-trip or yields a different root.
+pub fn assert_root_stable(block: &DigL2Block) {
+    let root = block.calculate_root();
+    let check = |format: &str, decoded: DigL2Block| {
+        assert_eq!(decoded, *block, "{format} round-trip changed the block");
+        assert_eq!(
+            decoded.calculate_root(),
+            root,
+            "{format} round-trip changed the root"
+        );
+    };
+
+    let json = serde_json::to_string(block).expect("JSON encode");
+    check("JSON", serde_json::from_str(&json).expect("JSON decode"));
+
+    let canonical = block::to_canonical_json(block);
+    check(
+        "canonical JSON",
+        serde_json::from_str(&canonical).expect("canonical JSON decode"),
+    );
+
+    let bytes = block.to_canonical_bytes();
+    check(
+        "canonical bytes",
+        DigL2Block::from_canonical_bytes(&bytes).expect("canonical bytes decode"),
+    );
+
+    #[cfg(feature = "cbor")]
+    check(
+        "CBOR",
+        block::from_cbor(&block::to_cbor(block)).expect("CBOR decode"),
+    );
+
+    #[cfg(feature = "msgpack")]
+    check(
+        "MessagePack",
+        block::from_msgpack(&block::to_msgpack(block)).expect("MessagePack decode"),
+    );
+
+    #[cfg(feature = "ssz")]
+    {
+        use crate::ssz::Ssz;
+        let bytes = block.to_ssz_bytes();
+        check(
+            "SSZ",
+            DigL2Block::from_ssz_bytes(&bytes).expect("SSZ decode"),
+        );
+    }
+}
+
+/// Most data bytes a generated body carries.
+const FUZZ_MAX_DATA: usize = 256;
+/// Most emissions a generated body carries.
+const FUZZ_MAX_EMISSIONS: usize = 8;
+
+/// Fuzzer-provided bytes consumed front to back; reads past the end yield zeros,
+/// so every input maps to some value.
+#[derive(Clone, Debug)]
+pub struct FuzzBytes<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> FuzzBytes<'a> {
+    /// Wraps the raw fuzzer input.
+    pub fn new(data: &'a [u8]) -> Self {
+        FuzzBytes { data }
+    }
+
+    /// Fills `out` from the input, zero-padding once it is exhausted.
+    pub fn fill(&mut self, out: &mut [u8]) {
+        let n = out.len().min(self.data.len());
+        out[..n].copy_from_slice(&self.data[..n]);
+        out[n..].fill(0);
+        self.data = &self.data[n..];
+    }
+
+    /// The next `N` bytes.
+    pub fn array<const N: usize>(&mut self) -> [u8; N] {
+        let mut out = [0u8; N];
+        self.fill(&mut out);
+        out
+    }
+
+    /// The next little-endian `u32`.
+    pub fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.array())
+    }
+
+    /// The next little-endian `u64`.
+    pub fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.array())
+    }
+
+    /// A length in `0..=max`.
+    pub fn len_up_to(&mut self, max: usize) -> usize {
+        self.u32() as usize % (max + 1)
+    }
+
+    /// Whether the input is exhausted.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+/// Types that can be generated from fuzzer input.
+///
+/// `Emission`, `L2BlockBody` and `L2BlockHeader` are raw values with no
+/// invariants. `DigL2Block` is self-consistent (see its impl); use `raw_block`
+/// for possibly invalid blocks.
+pub trait FromFuzz: Sized {
+    /// Generates a value, consuming bytes from `u`.
+    fn from_fuzz(u: &mut FuzzBytes<'_>) -> Self;
+}
+
+impl FromFuzz for Emission {
+    fn from_fuzz(u: &mut FuzzBytes<'_>) -> Self {
+        Emission {
+            pubkey: u.array(),
+            weight: u.u64(),
+        }
+    }
+}
+
+impl FromFuzz for L2BlockBody {
+    fn from_fuzz(u: &mut FuzzBytes<'_>) -> Self {
+        let mut data = vec![0u8; u.len_up_to(FUZZ_MAX_DATA)];
+        u.fill(&mut data);
+        let emissions = (0..u.len_up_to(FUZZ_MAX_EMISSIONS))
+            .map(|_| Emission::from_fuzz(u))
+            .collect();
+        L2BlockBody { data, emissions }
+    }
+}
+
+impl FromFuzz for L2BlockHeader {
+    fn from_fuzz(u: &mut FuzzBytes<'_>) -> Self {
+        L2BlockHeader {
+            version: u.u32(),
+            network_id: u.array(),
+            epoch: u.u64(),
+            prev_block_root: u.array(),
+            body_root: u.array(),
+            data_count: u.u32(),
+            emissions_count: u.u32(),
+            proposer_pubkey: u.array(),
+            timestamp: u.u64(),
+            extra_data: u.array(),
+        }
+    }
+}
+
+/// A block that passes `verify`: the version is one of the defined versions,
+/// fields a version does not commit to are zero, emission pubkeys are
+/// distinct, and counts and `body_root` are filled in by `reseal`.
+impl FromFuzz for DigL2Block {
+    fn from_fuzz(u: &mut FuzzBytes<'_>) -> Self {
+        let mut header = L2BlockHeader::from_fuzz(u);
+        let mut body = L2BlockBody::from_fuzz(u);
+        header.version = 1 + header.version % definitions::ORDERED_EMISSIONS_VERSION;
+        if header.version < definitions::TIMESTAMP_VERSION {
+            header.timestamp = 0;
+        }
+        if header.version < definitions::EXTRA_DATA_VERSION {
+            header.extra_data = [0u8; 32];
+        }
+        let mut seen = std::collections::HashSet::new();
+        body.emissions.retain(|e| seen.insert(e.pubkey));
+        let mut block = DigL2Block { header, body };
+        block.reseal().expect("generated counts fit u32");
+        block
+    }
+}
+
+/// Seeded xorshift64* generator for property tests; a fixed seed makes a
+/// failure reproducible. Not suitable for anything but tests.
+#[derive(Clone, Debug)]
+pub struct TestRng(u64);
+
+impl TestRng {
+    /// A generator started from `seed`. A zero seed (a fixed point of
+    /// xorshift) is replaced by a fixed non-zero one.
+    pub fn new(seed: u64) -> Self {
+        TestRng(if seed == 0 {
+            0x9e37_79b9_7f4a_7c15
+        } else {
+            seed
+        })
+    }
+
+    /// The next 64 pseudo-random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A value in `0..n`.
+    ///
+    /// # Panics
+    /// If `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// `len` pseudo-random bytes.
+    pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_u64() as u8).collect()
+    }
+
+    /// A pseudo-random 32-byte hash.
+    pub fn hash(&mut self) -> definitions::Hash32 {
+        let mut out = [0u8; 32];
+        for chunk in out.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+        out
+    }
+}
+
+/// A block whose header and body are generated independently, so counts,
+/// `body_root` and version rules are usually violated. Meant for decode and
+/// `verify` error paths.
+pub fn raw_block(u: &mut FuzzBytes<'_>) -> DigL2Block {
+    DigL2Block {
+        header: L2BlockHeader::from_fuzz(u),
+        body: L2BlockBody::from_fuzz(u),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random fuzz inputs.
+    fn inputs() -> impl Iterator<Item = Vec<u8>> {
+        let mut rng = TestRng::new(0x9e37_79b9_7f4a_7c15);
+        (0..64usize).map(move |i| rng.bytes(i * 23))
+    }
+
+    #[test]
+    fn generated_blocks_verify() {
+        for input in inputs() {
+            let block = DigL2Block::from_fuzz(&mut FuzzBytes::new(&input));
+            block.verify(None).unwrap();
+            assert_root_stable(&block);
+        }
+    }
+
+    #[test]
+    fn raw_blocks_never_panic_in_verify() {
+        let mut rejected = 0;
+        for input in inputs() {
+            let block = raw_block(&mut FuzzBytes::new(&input));
+            rejected += usize::from(block.verify(None).is_err());
+        }
+        assert!(rejected > 0);
+    }
+
+    #[test]
+    fn fuzz_bytes_zero_pads_when_exhausted() {
+        let mut u = FuzzBytes::new(&[1, 2, 3]);
+        assert_eq!(u.array::<2>(), [1, 2]);
+        assert_eq!(u.u32(), 3);
+        assert!(u.is_empty());
+        assert_eq!(u.u64(), 0);
+    }
+}
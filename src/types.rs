@@ -0,0 +1,233 @@
+//! Strongly typed wrappers around the raw byte arrays used by the spec.
+//!
+//! - `HashHex`: a `Hash32` that displays and parses as `0x`-prefixed lowercase
+//!   hex and serializes through `serde_hex::hex32`.
+//! - `NetworkId`, `BlockRoot`, `Pubkey`: distinct wrappers for header inputs so
+//!   that, for example, a block root cannot be passed where a network ID is
+//!   expected. They serialize exactly like the raw hex fields. Hashing always
+//!   operates on the raw bytes.
+//!
+//! ```compile_fail
+//! use dig_network_block::block::DigL2Block;
+//! use dig_network_block::types::BlockRoot;
+//!
+//! // A BlockRoot is not a NetworkId, even though both wrap [u8; 32].
+//! let _ = DigL2Block::builder().network_id(BlockRoot::from([0u8; 32]));
+//! ```
+
+use crate::dig_l2_definition::Hash32;
+use crate::serde_hex::{self, HexSerdeError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A 32-byte hash (e.g. a block root) with hex `Display`/`FromStr`.
+///
+/// Serializes exactly like a `#[serde(with = "hex32")]` field, so it can be
+/// used directly in caller structs in place of an annotated `Hash32`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct HashHex(#[serde(with = "crate::serde_hex::hex32")] pub Hash32);
+
+impl From<Hash32> for HashHex {
+    fn from(hash: Hash32) -> Self {
+        HashHex(hash)
+    }
+}
+
+impl From<HashHex> for Hash32 {
+    fn from(hash: HashHex) -> Self {
+        hash.0
+    }
+}
+
+impl AsRef<[u8]> for HashHex {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl PartialEq<Hash32> for HashHex {
+    fn eq(&self, other: &Hash32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for HashHex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&serde_hex::encode_lower_hex_prefixed(&self.0))
+    }
+}
+
+impl FromStr for HashHex {
+    type Err = HexSerdeError;
+
+    /// Parses a `0x`-prefixed hex string of exactly 32 bytes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_hex::decode_hex_array(s).map(HashHex)
+    }
+}
+
+/// Defines a transparent hex-serialized wrapper around a fixed-size byte array.
+macro_rules! byte_newtype {
+    ($(#[$meta:meta])* $name:ident, $len:literal, $with:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(#[serde(with = $with)] pub [u8; $len]);
+
+        impl $name {
+            /// The wrapped bytes.
+            pub fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                $name(bytes)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&serde_hex::encode_lower_hex_prefixed(&self.0))
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = HexSerdeError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                serde_hex::decode_hex_array(s).map($name)
+            }
+        }
+    };
+}
+
+byte_newtype!(
+    /// Network identifier (32 bytes).
+    NetworkId,
+    32,
+    "crate::serde_hex::hex32"
+);
+
+byte_newtype!(
+    /// Root of a block (32 bytes), e.g. a `prev_block_root`.
+    BlockRoot,
+    32,
+    "crate::serde_hex::hex32"
+);
+
+byte_newtype!(
+    /// BLS public key (48 bytes).
+    Pubkey,
+    48,
+    "crate::serde_hex::hex48"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_from_str_round_trip() {
+        let mut raw = [0u8; 32];
+        raw[0] = 0xab;
+        raw[31] = 0x01;
+        let h = HashHex(raw);
+        let s = h.to_string();
+        assert_eq!(s, format!("0x{}", hex::encode(raw)));
+        assert_eq!(s.parse::<HashHex>().unwrap(), h);
+    }
+
+    #[test]
+    fn from_str_rejects_wrong_length_and_prefix() {
+        let short = format!("0x{}", "00".repeat(31));
+        match short.parse::<HashHex>().unwrap_err() {
+            HexSerdeError::LengthMismatch { expected, actual } => {
+                assert_eq!((expected, actual), (32, 31));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        let unprefixed = "00".repeat(32);
+        assert!(matches!(
+            unprefixed.parse::<HashHex>(),
+            Err(HexSerdeError::MissingPrefix)
+        ));
+    }
+
+    #[test]
+    fn newtypes_serialize_like_raw_hex_fields() {
+        #[derive(Serialize)]
+        struct Raw {
+            #[serde(with = "crate::serde_hex::hex32")]
+            network_id: [u8; 32],
+            #[serde(with = "crate::serde_hex::hex32")]
+            prev_block_root: [u8; 32],
+            #[serde(with = "crate::serde_hex::hex48")]
+            proposer_pubkey: [u8; 48],
+        }
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Typed {
+            network_id: NetworkId,
+            prev_block_root: BlockRoot,
+            proposer_pubkey: Pubkey,
+        }
+        let raw = Raw {
+            network_id: [1u8; 32],
+            prev_block_root: [2u8; 32],
+            proposer_pubkey: [3u8; 48],
+        };
+        let typed = Typed {
+            network_id: raw.network_id.into(),
+            prev_block_root: raw.prev_block_root.into(),
+            proposer_pubkey: raw.proposer_pubkey.into(),
+        };
+        let json = serde_json::to_string(&typed).unwrap();
+        assert_eq!(json, serde_json::to_string(&raw).unwrap());
+        assert_eq!(serde_json::from_str::<Typed>(&json).unwrap(), typed);
+        let back: [u8; 48] = typed.proposer_pubkey.into();
+        assert_eq!(back, raw.proposer_pubkey);
+        assert_eq!(
+            typed.network_id.to_string().parse::<NetworkId>().unwrap(),
+            typed.network_id
+        );
+    }
+
+    #[test]
+    fn serde_matches_hex32() {
+        let h = HashHex([7u8; 32]);
+        let json = serde_json::to_string(&h).unwrap();
+        assert_eq!(json, format!("\"{h}\""));
+        let back: HashHex = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, h);
+    }
+
+    #[test]
+    fn hash_hex_in_dto_round_trips_and_equals_raw() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct RootDto {
+            root: HashHex,
+        }
+        let raw: Hash32 = crate::dig_l2_definition::EMPTY_MERKLE_ROOT();
+        let dto = RootDto { root: raw.into() };
+        let json = serde_json::to_string(&dto).unwrap();
+        assert_eq!(json, format!("{{\"root\":\"0x{}\"}}", hex::encode(raw)));
+        let back: RootDto = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, dto);
+
+        assert_eq!(back.root, raw);
+        assert_eq!(back.root.as_ref(), &raw[..]);
+        let unwrapped: Hash32 = back.root.into();
+        assert_eq!(unwrapped, raw);
+    }
+}
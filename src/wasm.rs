@@ -0,0 +1,73 @@
+//! JSON-in/hex-out entry points for browser builds (feature `wasm`).
+//!
+//! These are thin wrappers over `DigL2Block::calculate_root_hex` and
+//! `DigL2Block::verify` using only `&str`/`String`/`bool` and
+//! `Result<String, String>`, so they can be exported unchanged with
+//! `#[wasm_bindgen]`, where an `Err` becomes a thrown JS exception. The
+//! bindgen attributes are not applied in this crate; a wasm wrapper crate
+//! re-exports these functions.
+
+use crate::block::DigL2Block;
+
+/// `0x`-prefixed lowercase hex `BLOCK_ROOT` of a JSON-encoded block.
+///
+/// Fails with the parse error message if `json` is not a valid `DigL2Block`.
+pub fn block_root_from_json(json: &str) -> Result<String, String> {
+    serde_json::from_str::<DigL2Block>(json)
+        .map(|block| block.calculate_root_hex())
+        .map_err(|e| crate::block::BlockError::from(e).to_string())
+}
+
+/// Whether `json` decodes to a block that passes `DigL2Block::verify(None)`.
+pub fn verify_block_json(json: &str) -> bool {
+    serde_json::from_str::<DigL2Block>(json).is_ok_and(|block| block.verify(None).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BuildL2BlockArgs;
+    use crate::emission_config::ConsensusEmissionConfig;
+
+    fn sample_json() -> (DigL2Block, String) {
+        let cfg = ConsensusEmissionConfig::new(12, 0);
+        let block = DigL2Block::build(&BuildL2BlockArgs {
+            version: 1,
+            network_id: [0xabu8; 32].into(),
+            epoch: 3,
+            prev_block_root: [0u8; 32].into(),
+            proposer_pubkey: [9u8; 48].into(),
+            data: vec![1, 2, 3],
+            extra_emissions: vec![],
+            attester_pubkeys: &[],
+            cfg: &cfg,
+            timestamp: 0,
+            extra_data: [0u8; 32],
+        })
+        .unwrap();
+        let json = serde_json::to_string(&block).unwrap();
+        (block, json)
+    }
+
+    #[test]
+    fn json_root_matches_native_root() {
+        let (block, json) = sample_json();
+        assert_eq!(
+            block_root_from_json(&json),
+            Ok(format!("0x{}", hex::encode(block.calculate_root())))
+        );
+        assert!(verify_block_json(&json));
+    }
+
+    #[test]
+    fn invalid_or_inconsistent_json_is_rejected() {
+        let err = block_root_from_json("{}").unwrap_err();
+        assert!(err.starts_with("invalid block JSON: "), "{err}");
+        assert!(block_root_from_json("not json").is_err());
+        assert!(!verify_block_json("not json"));
+
+        let (mut block, _) = sample_json();
+        block.body.data.push(4);
+        assert!(!verify_block_json(&serde_json::to_string(&block).unwrap()));
+    }
+}
@@ -0,0 +1,243 @@
+//! JSON shapes for JavaScript clients, with every `u64` as a decimal string.
+//!
+//! `WebBlock` mirrors `DigL2Block` field for field, with the same names,
+//! camelCase aliases, defaults and `0x` hex byte fields. The only difference
+//! is that `epoch`, `timestamp` and emission `weight` use `serde_u64_string`,
+//! so values above 2^53 survive `JSON.parse`; numbers are still accepted, so
+//! any JSON the core types read also parses here. Convert with `From` in
+//! either direction; roots are always computed on the core types.
+
+use crate::block::DigL2Block;
+use crate::body::L2BlockBody;
+use crate::emission::Emission;
+use crate::header::L2BlockHeader;
+use serde::{Deserialize, Serialize};
+
+/// `Emission` with `weight` as a decimal string.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebEmission {
+    /// BLS public key (48 bytes), serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex48")]
+    pub pubkey: [u8; 48],
+    /// Relative share of reward pool, serialized as a decimal string.
+    #[serde(with = "crate::serde_u64_string")]
+    pub weight: u64,
+}
+
+/// `L2BlockHeader` with `epoch` and `timestamp` as decimal strings.
+///
+/// Like the core header, deserialization also accepts the camelCase
+/// spellings, and `timestamp` and `extra_data` default to zero when absent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebHeader {
+    /// Block version.
+    pub version: u32,
+    /// Network ID (32 bytes), serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex32", alias = "networkId")]
+    pub network_id: [u8; 32],
+    /// Epoch number, serialized as a decimal string.
+    #[serde(with = "crate::serde_u64_string")]
+    pub epoch: u64,
+    /// Previous block root (32 bytes), serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex32", alias = "prevBlockRoot")]
+    pub prev_block_root: [u8; 32],
+    /// Body root (32 bytes), serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex32", alias = "bodyRoot")]
+    pub body_root: [u8; 32],
+    /// Count of data items (bytes) in the body.
+    #[serde(alias = "dataCount")]
+    pub data_count: u32,
+    /// Count of emissions in the body.
+    #[serde(alias = "emissionsCount")]
+    pub emissions_count: u32,
+    /// Proposer public key (48 bytes), serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex48", alias = "proposerPubkey")]
+    pub proposer_pubkey: [u8; 48],
+    /// Block time in unix seconds, serialized as a decimal string. Defaults
+    /// to zero when absent.
+    #[serde(with = "crate::serde_u64_string", default)]
+    pub timestamp: u64,
+    /// Opaque application commitment, serialized as `0x` hex. Defaults to
+    /// zero when absent.
+    #[serde(with = "crate::serde_hex::hex32", alias = "extraData", default)]
+    pub extra_data: [u8; 32],
+}
+
+/// `L2BlockBody` with `WebEmission`s.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebBody {
+    /// Application data bytes, serialized as `0x` hex.
+    #[serde(with = "crate::serde_hex::hex_vec")]
+    pub data: Vec<u8>,
+    /// Reward distribution records.
+    pub emissions: Vec<WebEmission>,
+}
+
+/// `DigL2Block` in its JavaScript-safe JSON shape.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebBlock {
+    pub header: WebHeader,
+    pub body: WebBody,
+}
+
+impl From<Emission> for WebEmission {
+    fn from(e: Emission) -> Self {
+        WebEmission {
+            pubkey: e.pubkey,
+            weight: e.weight,
+        }
+    }
+}
+
+impl From<WebEmission> for Emission {
+    fn from(e: WebEmission) -> Self {
+        Emission {
+            pubkey: e.pubkey,
+            weight: e.weight,
+        }
+    }
+}
+
+impl From<L2BlockHeader> for WebHeader {
+    fn from(h: L2BlockHeader) -> Self {
+        WebHeader {
+            version: h.version,
+            network_id: h.network_id,
+            epoch: h.epoch,
+            prev_block_root: h.prev_block_root,
+            body_root: h.body_root,
+            data_count: h.data_count,
+            emissions_count: h.emissions_count,
+            proposer_pubkey: h.proposer_pubkey,
+            timestamp: h.timestamp,
+            extra_data: h.extra_data,
+        }
+    }
+}
+
+impl From<WebHeader> for L2BlockHeader {
+    fn from(h: WebHeader) -> Self {
+        L2BlockHeader {
+            version: h.version,
+            network_id: h.network_id,
+            epoch: h.epoch,
+            prev_block_root: h.prev_block_root,
+            body_root: h.body_root,
+            data_count: h.data_count,
+            emissions_count: h.emissions_count,
+            proposer_pubkey: h.proposer_pubkey,
+            timestamp: h.timestamp,
+            extra_data: h.extra_data,
+        }
+    }
+}
+
+impl From<L2BlockBody> for WebBody {
+    fn from(b: L2BlockBody) -> Self {
+        WebBody {
+            data: b.data,
+            emissions: b.emissions.into_iter().map(WebEmission::from).collect(),
+        }
+    }
+}
+
+impl From<WebBody> for L2BlockBody {
+    fn from(b: WebBody) -> Self {
+        L2BlockBody {
+            data: b.data,
+            emissions: b.emissions.into_iter().map(Emission::from).collect(),
+        }
+    }
+}
+
+impl From<DigL2Block> for WebBlock {
+    fn from(block: DigL2Block) -> Self {
+        WebBlock {
+            header: block.header.into(),
+            body: block.body.into(),
+        }
+    }
+}
+
+impl From<WebBlock> for DigL2Block {
+    fn from(block: WebBlock) -> Self {
+        DigL2Block {
+            header: block.header.into(),
+            body: block.body.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big_block() -> DigL2Block {
+        let body = L2BlockBody::emissions_only(vec![Emission {
+            pubkey: [5u8; 48],
+            weight: u64::MAX,
+        }]);
+        let header = L2BlockHeader {
+            version: crate::dig_l2_definition::TIMESTAMP_VERSION,
+            network_id: [1u8; 32],
+            epoch: (1 << 53) + 1,
+            prev_block_root: [0u8; 32],
+            body_root: body.calculate_root_for_version(crate::dig_l2_definition::TIMESTAMP_VERSION),
+            data_count: 0,
+            emissions_count: 1,
+            proposer_pubkey: [5u8; 48],
+            timestamp: 1_700_000_000,
+            extra_data: [0u8; 32],
+        };
+        DigL2Block { header, body }
+    }
+
+    #[test]
+    fn u64_fields_round_trip_as_strings() {
+        let block = big_block();
+        let json = serde_json::to_string(&WebBlock::from(block.clone())).unwrap();
+        let v: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(v["body"]["emissions"][0]["weight"], "18446744073709551615");
+        assert_eq!(v["header"]["epoch"], "9007199254740993");
+        assert_eq!(v["header"]["timestamp"], "1700000000");
+        assert_eq!(v["header"]["data_count"], 0);
+
+        let back = DigL2Block::from(serde_json::from_str::<WebBlock>(&json).unwrap());
+        assert_eq!(back, block);
+        assert_eq!(back.body.emissions[0].weight, u64::MAX);
+        assert_eq!(back.calculate_root(), block.calculate_root());
+        assert!(back.verify(None).is_ok());
+    }
+
+    #[test]
+    fn accepts_core_shaped_json() {
+        // A v1 block as the core types read it: numeric u64s, camelCase keys,
+        // and no timestamp or extra_data.
+        let mut block = big_block();
+        block.header.version = 1;
+        block.header.epoch = 7;
+        block.header.timestamp = 0;
+        block.body.emissions[0].weight = 3;
+        block.reseal().unwrap();
+        let mut json = serde_json::to_value(&block).unwrap();
+        let header = json["header"].as_object_mut().unwrap();
+        header.remove("timestamp");
+        header.remove("extra_data");
+        for (snake, camel) in [
+            ("network_id", "networkId"),
+            ("prev_block_root", "prevBlockRoot"),
+            ("body_root", "bodyRoot"),
+            ("data_count", "dataCount"),
+            ("emissions_count", "emissionsCount"),
+            ("proposer_pubkey", "proposerPubkey"),
+        ] {
+            let value = header.remove(snake).unwrap();
+            header.insert(camel.to_string(), value);
+        }
+
+        let core: DigL2Block = serde_json::from_value(json.clone()).unwrap();
+        let web: WebBlock = serde_json::from_value(json).unwrap();
+        assert_eq!(DigL2Block::from(web), core);
+        assert_eq!(core, block);
+    }
+}